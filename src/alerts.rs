@@ -0,0 +1,381 @@
+use std::time::{Duration, Instant};
+
+use crate::monitor::MonitorData;
+use crate::settings::{AlertComparison, AlertMetric, AlertRule, Settings};
+
+/// A rule starting or stopping to fire.
+#[derive(Clone, Debug)]
+pub struct AlertEvent {
+    /// Position of the rule in `Settings::alert_rules`.
+    pub index: usize,
+    pub rule: AlertRule,
+    pub fired: bool,
+    /// `None` when a firing rule was edited away or its metric stopped reporting.
+    pub value: Option<f64>,
+}
+
+impl AlertEvent {
+    /// Notification title and body.
+    pub fn message(&self) -> (String, String) {
+        let rule = &self.rule;
+        let unit = rule.metric.unit();
+        let reading = self.value.map_or_else(
+            || "No longer being checked".to_string(),
+            |value| format!("Now {value:.1}{unit}"),
+        );
+        let direction = match rule.comparison {
+            AlertComparison::Above => "above",
+            AlertComparison::Below => "below",
+        };
+        if self.fired {
+            let held = if rule.duration_secs > 0 {
+                format!(" for {} s", rule.duration_secs)
+            } else {
+                String::new()
+            };
+            (
+                format!(
+                    "{} {direction} {}{unit}",
+                    rule.metric.label(),
+                    rule.threshold
+                ),
+                format!("{reading}{held}."),
+            )
+        } else {
+            (
+                format!("{} back to normal", rule.metric.label()),
+                format!("{reading}."),
+            )
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default)]
+struct RuleState {
+    breached_since: Option<Instant>,
+    firing: bool,
+}
+
+/// Tracks each rule across samples and reports when one fires or clears.
+#[derive(Default)]
+pub struct AlertEngine {
+    rules: Vec<AlertRule>,
+    states: Vec<RuleState>,
+}
+
+impl AlertEngine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feeds one sample through every rule. A rule that is edited starts over; if it was
+    /// firing, its alert is cleared first.
+    pub fn evaluate(
+        &mut self,
+        settings: &Settings,
+        data: &MonitorData,
+        now: Instant,
+    ) -> Vec<AlertEvent> {
+        let mut events = Vec::new();
+        if self.rules != settings.alert_rules {
+            let mut states = vec![RuleState::default(); settings.alert_rules.len()];
+            for (index, (rule, state)) in self.rules.iter().zip(&self.states).enumerate() {
+                if settings.alert_rules.get(index) == Some(rule) {
+                    states[index] = *state;
+                } else if state.firing {
+                    events.push(AlertEvent {
+                        index,
+                        rule: rule.clone(),
+                        fired: false,
+                        value: None,
+                    });
+                }
+            }
+            self.states = states;
+            self.rules.clone_from(&settings.alert_rules);
+        }
+
+        for (index, (rule, state)) in self.rules.iter().zip(&mut self.states).enumerate() {
+            let value = if rule.enabled {
+                metric_value(&rule.metric, settings, data)
+            } else {
+                None
+            };
+            let event = |fired, value| AlertEvent {
+                index,
+                rule: rule.clone(),
+                fired,
+                value,
+            };
+            let Some(value) = value else {
+                if state.firing {
+                    events.push(event(false, None));
+                }
+                *state = RuleState::default();
+                continue;
+            };
+
+            if state.firing {
+                if is_cleared(rule, value) {
+                    *state = RuleState::default();
+                    events.push(event(false, Some(value)));
+                }
+            } else if is_breached(rule, value) {
+                let since = *state.breached_since.get_or_insert(now);
+                if now.duration_since(since) >= Duration::from_secs(rule.duration_secs) {
+                    state.firing = true;
+                    events.push(event(true, Some(value)));
+                }
+            } else {
+                state.breached_since = None;
+            }
+        }
+        events
+    }
+
+    /// Metrics of the rules currently firing.
+    pub fn firing(&self) -> impl Iterator<Item = &AlertMetric> {
+        self.rules
+            .iter()
+            .zip(&self.states)
+            .filter(|(_, state)| state.firing)
+            .map(|(rule, _)| &rule.metric)
+    }
+}
+
+fn is_breached(rule: &AlertRule, value: f64) -> bool {
+    match rule.comparison {
+        AlertComparison::Above => value > rule.threshold,
+        AlertComparison::Below => value < rule.threshold,
+    }
+}
+
+/// A firing rule only clears once the value is back past the threshold by the hysteresis
+/// margin, so a reading hovering at the limit does not flap.
+fn is_cleared(rule: &AlertRule, value: f64) -> bool {
+    let margin = rule.hysteresis.abs();
+    match rule.comparison {
+        AlertComparison::Above => value <= rule.threshold - margin,
+        AlertComparison::Below => value >= rule.threshold + margin,
+    }
+}
+
+/// Current value of `metric`, or `None` when this machine does not report it.
+#[allow(clippy::cast_precision_loss)]
+pub fn metric_value(metric: &AlertMetric, settings: &Settings, data: &MonitorData) -> Option<f64> {
+    const GIB: f64 = 1024.0 * 1024.0 * 1024.0;
+    let percent = |used: u64, total: u64| (total > 0).then(|| used as f64 / total as f64 * 100.0);
+    match metric {
+        AlertMetric::CpuUsage => Some(f64::from(data.cpu_usage)),
+        AlertMetric::RamUsage => percent(data.ram_used, data.ram_total),
+        AlertMetric::SwapUsage => percent(data.swap_used, data.swap_total),
+        AlertMetric::GpuUsage => data.gpu_usage().map(f64::from),
+        AlertMetric::DiskFree => data
+            .selected_mounts(&settings.disk_mounts)
+            .filter(|m| m.total > 0)
+            .map(|m| m.total.saturating_sub(m.used) as f64 / GIB)
+            .min_by(f64::total_cmp),
+        AlertMetric::Temperature => data
+            .temperatures
+            .iter()
+            .map(|t| f64::from(t.celsius))
+            .max_by(f64::total_cmp),
+        AlertMetric::NetworkRate => Some(
+            data.network_interfaces(
+                &settings.network_filter,
+                settings.network_interface.as_deref(),
+            )
+            .map(|i| i.rx_bytes_per_sec + i.tx_bytes_per_sec)
+            .sum::<f64>()
+                / 1024.0,
+        ),
+        AlertMetric::BatteryCharge => data
+            .power
+            .batteries
+            .first()
+            .map(|b| f64::from(b.capacity_percent)),
+        AlertMetric::Command(name) => data
+            .commands
+            .iter()
+            .find(|r| &r.name == name)
+            .and_then(|r| r.value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cpu_rule() -> AlertRule {
+        AlertRule {
+            threshold: 90.0,
+            duration_secs: 30,
+            hysteresis: 5.0,
+            ..AlertRule::default()
+        }
+    }
+
+    fn settings_with(rules: Vec<AlertRule>) -> Settings {
+        Settings {
+            alert_rules: rules,
+            ..Settings::default()
+        }
+    }
+
+    fn cpu(usage: f32) -> MonitorData {
+        MonitorData {
+            cpu_usage: usage,
+            ..MonitorData::default()
+        }
+    }
+
+    /// `(fired, value)` of each event.
+    fn summary(events: &[AlertEvent]) -> Vec<(bool, Option<f64>)> {
+        events.iter().map(|e| (e.fired, e.value)).collect()
+    }
+
+    #[test]
+    fn fires_only_after_the_breach_is_held() {
+        let settings = settings_with(vec![cpu_rule()]);
+        let mut engine = AlertEngine::new();
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+
+        assert!(engine.evaluate(&settings, &cpu(95.0), at(0)).is_empty());
+        assert!(engine.evaluate(&settings, &cpu(97.0), at(29)).is_empty());
+        let events = engine.evaluate(&settings, &cpu(96.0), at(30));
+        assert_eq!(summary(&events), [(true, Some(96.0))]);
+        assert_eq!(engine.firing().count(), 1);
+
+        // Still breached: no repeat notification.
+        assert!(engine.evaluate(&settings, &cpu(99.0), at(31)).is_empty());
+    }
+
+    #[test]
+    fn dipping_below_before_the_hold_restarts_the_timer() {
+        let settings = settings_with(vec![cpu_rule()]);
+        let mut engine = AlertEngine::new();
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+
+        assert!(engine.evaluate(&settings, &cpu(95.0), at(0)).is_empty());
+        assert!(engine.evaluate(&settings, &cpu(80.0), at(20)).is_empty());
+        assert!(engine.evaluate(&settings, &cpu(95.0), at(40)).is_empty());
+        assert!(engine.evaluate(&settings, &cpu(95.0), at(69)).is_empty());
+        assert_eq!(engine.evaluate(&settings, &cpu(95.0), at(70)).len(), 1);
+    }
+
+    #[test]
+    fn clears_only_past_the_hysteresis_margin() {
+        let settings = settings_with(vec![AlertRule {
+            duration_secs: 0,
+            ..cpu_rule()
+        }]);
+        let mut engine = AlertEngine::new();
+        let now = Instant::now();
+
+        assert_eq!(engine.evaluate(&settings, &cpu(91.0), now).len(), 1);
+        // Back under the threshold but inside the 5-point margin: still firing.
+        assert!(engine.evaluate(&settings, &cpu(88.0), now).is_empty());
+        assert!(engine.evaluate(&settings, &cpu(85.5), now).is_empty());
+        assert_eq!(engine.firing().count(), 1);
+
+        let events = engine.evaluate(&settings, &cpu(85.0), now);
+        assert_eq!(summary(&events), [(false, Some(85.0))]);
+        assert_eq!(engine.firing().count(), 0);
+    }
+
+    #[test]
+    fn below_rules_clear_above_the_margin() {
+        let rule = AlertRule {
+            metric: AlertMetric::BatteryCharge,
+            comparison: AlertComparison::Below,
+            threshold: 20.0,
+            duration_secs: 0,
+            hysteresis: 5.0,
+            ..AlertRule::default()
+        };
+        let settings = settings_with(vec![rule]);
+        let mut engine = AlertEngine::new();
+        let now = Instant::now();
+        let battery = |percent: f32| {
+            let mut data = MonitorData::default();
+            data.power.batteries.push(crate::power::BatteryStats {
+                name: "BAT0".to_string(),
+                capacity_percent: percent,
+                state: crate::power::ChargeState::Discharging,
+                power_watts: None,
+                time_to_empty_secs: None,
+                time_to_full_secs: None,
+            });
+            data
+        };
+
+        assert_eq!(engine.evaluate(&settings, &battery(19.0), now).len(), 1);
+        assert!(engine.evaluate(&settings, &battery(24.0), now).is_empty());
+        assert_eq!(engine.evaluate(&settings, &battery(25.0), now).len(), 1);
+    }
+
+    #[test]
+    fn editing_a_firing_rule_clears_it() {
+        let mut settings = settings_with(vec![
+            AlertRule {
+                duration_secs: 0,
+                ..cpu_rule()
+            },
+            AlertRule {
+                threshold: 50.0,
+                duration_secs: 0,
+                ..cpu_rule()
+            },
+        ]);
+        let mut engine = AlertEngine::new();
+        let now = Instant::now();
+        assert_eq!(engine.evaluate(&settings, &cpu(95.0), now).len(), 2);
+
+        // The unchanged rule keeps firing silently; the edited one is cleared.
+        settings.alert_rules[1].threshold = 99.0;
+        let events = engine.evaluate(&settings, &cpu(95.0), now);
+        assert_eq!(summary(&events), [(false, None)]);
+        assert_eq!(events[0].index, 1);
+        assert!((events[0].rule.threshold - 50.0).abs() < f64::EPSILON);
+        assert_eq!(engine.firing().count(), 1);
+
+        settings.alert_rules.clear();
+        let events = engine.evaluate(&settings, &cpu(95.0), now);
+        assert_eq!(summary(&events), [(false, None)]);
+        assert_eq!(events[0].index, 0);
+        assert_eq!(engine.firing().count(), 0);
+    }
+
+    #[test]
+    fn losing_the_metric_clears_a_firing_rule() {
+        let settings = settings_with(vec![AlertRule {
+            metric: AlertMetric::GpuUsage,
+            duration_secs: 0,
+            ..cpu_rule()
+        }]);
+        let mut engine = AlertEngine::new();
+        let now = Instant::now();
+        let mut busy = MonitorData::default();
+        busy.gpus.push(crate::gpu::GpuStats {
+            id: "card0".to_string(),
+            name: "GPU".to_string(),
+            driver: "amdgpu".to_string(),
+            busy_percent: Some(99.0),
+            vram_used: None,
+            vram_total: None,
+            core_clock_mhz: None,
+            memory_clock_mhz: None,
+            temperature_c: None,
+            power_watts: None,
+        });
+
+        assert_eq!(engine.evaluate(&settings, &busy, now).len(), 1);
+        let events = engine.evaluate(&settings, &MonitorData::default(), now);
+        assert_eq!(summary(&events), [(false, None)]);
+        assert!(engine
+            .evaluate(&settings, &MonitorData::default(), now)
+            .is_empty());
+    }
+}
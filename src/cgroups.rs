@@ -0,0 +1,368 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// What a reported cgroup belongs to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CgroupKind {
+    /// Docker, Podman or another runtime's container scope.
+    Container,
+    /// A systemd service or scope outside any container runtime.
+    Unit,
+}
+
+impl CgroupKind {
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Container => "container",
+            Self::Unit => "unit",
+        }
+    }
+}
+
+/// Resource use of one container or systemd unit. Rates are averaged over the sensor's last
+/// period and are zero on the first sample.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CgroupStats {
+    /// Short display name, e.g. `docker 3f2a9c1b7d4e` or `nginx.service`.
+    pub name: String,
+    /// Path below the cgroup root.
+    pub path: String,
+    pub kind: CgroupKind,
+    /// Percentage of one core, like the process list.
+    pub cpu_percent: f64,
+    pub memory_bytes: u64,
+    /// `memory.max`, when the group has a limit.
+    pub memory_limit: Option<u64>,
+    pub io_read_bytes_per_sec: f64,
+    pub io_write_bytes_per_sec: f64,
+}
+
+/// Cumulative counters kept between samples to turn into rates.
+#[derive(Clone, Copy)]
+struct Counters {
+    cpu_usec: u64,
+    read_bytes: u64,
+    write_bytes: u64,
+}
+
+/// Walks a cgroup v2 hierarchy (normally `/sys/fs/cgroup`) for containers and systemd units.
+/// Slices and `user@` managers are descended into; anything else that looks like a unit or a
+/// container is reported as a whole, without its children.
+pub struct CgroupReader {
+    root: PathBuf,
+    last: HashMap<String, Counters>,
+}
+
+impl CgroupReader {
+    pub const DEFAULT_ROOT: &'static str = "/sys/fs/cgroup";
+
+    /// How deep below the root to look; container scopes sit a few slices down at most.
+    const MAX_DEPTH: usize = 6;
+
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            last: HashMap::new(),
+        }
+    }
+
+    /// Reads every reported group. `elapsed` is the time since the previous call, `None` on
+    /// the first.
+    pub fn read(&mut self, elapsed: Option<Duration>) -> Vec<CgroupStats> {
+        let mut found = Vec::new();
+        collect_groups(&self.root, Path::new(""), 0, &mut found);
+
+        let seconds = elapsed.map(|e| e.as_secs_f64()).filter(|s| *s > 0.0);
+        let mut current = HashMap::new();
+        let stats = found
+            .into_iter()
+            .map(|(relative, kind)| {
+                let dir = self.root.join(&relative);
+                let path = relative.to_string_lossy().into_owned();
+                let (read_bytes, write_bytes) = read_io(&dir.join("io.stat"));
+                let counters = Counters {
+                    cpu_usec: read_cpu_usec(&dir.join("cpu.stat")),
+                    read_bytes,
+                    write_bytes,
+                };
+                #[allow(clippy::cast_precision_loss)]
+                let rate = |now: u64, then: u64, scale: f64| {
+                    seconds.map_or(0.0, |secs| now.saturating_sub(then) as f64 / scale / secs)
+                };
+                let previous = self.last.get(&path).copied().unwrap_or(counters);
+                current.insert(path.clone(), counters);
+                CgroupStats {
+                    name: display_name(&relative, kind),
+                    kind,
+                    cpu_percent: rate(counters.cpu_usec, previous.cpu_usec, 10_000.0),
+                    memory_bytes: read_number(&dir.join("memory.current")).unwrap_or(0),
+                    memory_limit: read_number(&dir.join("memory.max")),
+                    io_read_bytes_per_sec: rate(read_bytes, previous.read_bytes, 1.0),
+                    io_write_bytes_per_sec: rate(write_bytes, previous.write_bytes, 1.0),
+                    path,
+                }
+            })
+            .collect();
+        self.last = current;
+        stats
+    }
+}
+
+impl Default for CgroupReader {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_ROOT)
+    }
+}
+
+/// Classifies a cgroup directory name: `Some` when it is reported, `None` when it is only
+/// a container for other groups (or not interesting at all).
+fn classify(name: &str, parent: &str) -> Option<CgroupKind> {
+    let is_container = name.starts_with("docker-")
+        || (name.starts_with("libpod-") && !name.starts_with("libpod-conmon-"))
+        || name.starts_with("crio-")
+        || name.starts_with("cri-containerd-")
+        // cgroupfs driver: /docker/<id>, /lxc.payload.<name>
+        || parent == "docker"
+        || name.starts_with("lxc.payload.");
+    if is_container {
+        return Some(CgroupKind::Container);
+    }
+    let is_manager = name.starts_with("user@");
+    let is_unit = Path::new(name)
+        .extension()
+        .is_some_and(|ext| ext == "service" || ext == "scope");
+    (is_unit && !is_manager).then_some(CgroupKind::Unit)
+}
+
+fn collect_groups(
+    root: &Path,
+    relative: &Path,
+    depth: usize,
+    found: &mut Vec<(PathBuf, CgroupKind)>,
+) {
+    if depth > CgroupReader::MAX_DEPTH {
+        return;
+    }
+    let Ok(entries) = fs::read_dir(root.join(relative)) else {
+        return;
+    };
+    let parent = relative
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let mut children: Vec<_> = entries
+        .flatten()
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+        .map(|e| e.file_name().to_string_lossy().into_owned())
+        .collect();
+    children.sort();
+    for name in children {
+        let child = relative.join(&name);
+        match classify(&name, &parent) {
+            Some(kind) => found.push((child, kind)),
+            None => collect_groups(root, &child, depth + 1, found),
+        }
+    }
+}
+
+/// `docker-<id>.scope` becomes `docker <short id>`; units keep their own name.
+fn display_name(relative: &Path, kind: CgroupKind) -> String {
+    let name = relative
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    if kind == CgroupKind::Unit {
+        return name;
+    }
+    let trimmed = name.trim_end_matches(".scope");
+    for (prefix, runtime) in [
+        ("docker-", "docker"),
+        ("libpod-", "podman"),
+        ("crio-", "cri-o"),
+        ("cri-containerd-", "containerd"),
+        ("lxc.payload.", "lxc"),
+    ] {
+        if let Some(id) = trimmed.strip_prefix(prefix) {
+            return format!("{runtime} {}", short_id(id));
+        }
+    }
+    format!("docker {}", short_id(trimmed))
+}
+
+/// First 12 characters of a hex container id, as `docker ps` shows; other names are kept.
+fn short_id(id: &str) -> &str {
+    if id.len() > 12 && id.bytes().all(|b| b.is_ascii_hexdigit()) {
+        &id[..12]
+    } else {
+        id
+    }
+}
+
+fn read_number(path: &Path) -> Option<u64> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+fn read_cpu_usec(path: &Path) -> u64 {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| {
+            content
+                .lines()
+                .find_map(|line| line.strip_prefix("usage_usec "))
+                .and_then(|v| v.trim().parse().ok())
+        })
+        .unwrap_or(0)
+}
+
+/// Bytes read and written, summed over every device in `io.stat`.
+fn read_io(path: &Path) -> (u64, u64) {
+    let Ok(content) = fs::read_to_string(path) else {
+        return (0, 0);
+    };
+    let mut totals = (0, 0);
+    for field in content.split_whitespace() {
+        if let Some(value) = field.strip_prefix("rbytes=") {
+            totals.0 += value.parse::<u64>().unwrap_or(0);
+        } else if let Some(value) = field.strip_prefix("wbytes=") {
+            totals.1 += value.parse::<u64>().unwrap_or(0);
+        }
+    }
+    totals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn classifies_container_scopes() {
+        for name in [
+            "docker-3f2a9c1b7d4e5f60718293a4b5c6d7e8f90a1b2c3d4e5f60718293a4b5c6d7e8.scope",
+            "libpod-0123456789abcdef.scope",
+            "crio-0123456789abcdef.scope",
+            "cri-containerd-0123456789abcdef.scope",
+            "lxc.payload.web",
+        ] {
+            assert_eq!(
+                classify(name, "system.slice"),
+                Some(CgroupKind::Container),
+                "{name}"
+            );
+        }
+        // The cgroupfs driver names containers by bare id under `/docker`.
+        assert_eq!(
+            classify("3f2a9c1b7d4e", "docker"),
+            Some(CgroupKind::Container)
+        );
+        // Podman's monitor process is its own unit, not the container.
+        assert_eq!(
+            classify("libpod-conmon-0123456789abcdef.scope", "machine.slice"),
+            Some(CgroupKind::Unit)
+        );
+    }
+
+    #[test]
+    fn classifies_units_and_descends_into_the_rest() {
+        assert_eq!(
+            classify("nginx.service", "system.slice"),
+            Some(CgroupKind::Unit)
+        );
+        assert_eq!(
+            classify("session-2.scope", "user-1000.slice"),
+            Some(CgroupKind::Unit)
+        );
+        for name in [
+            "user@1000.service",
+            "system.slice",
+            "init.scope.d",
+            "docker",
+        ] {
+            assert_eq!(classify(name, ""), None, "{name}");
+        }
+    }
+
+    #[test]
+    fn names_containers_by_runtime_and_short_id() {
+        let name = |path: &str, kind| display_name(Path::new(path), kind);
+        assert_eq!(
+            name(
+                "system.slice/docker-3f2a9c1b7d4e5f60718293a4b5c6d7e8.scope",
+                CgroupKind::Container
+            ),
+            "docker 3f2a9c1b7d4e"
+        );
+        assert_eq!(
+            name("machine.slice/libpod-abc.scope", CgroupKind::Container),
+            "podman abc"
+        );
+        assert_eq!(
+            name("docker/3f2a9c1b7d4e5f60718293a4", CgroupKind::Container),
+            "docker 3f2a9c1b7d4e"
+        );
+        assert_eq!(
+            name("system.slice/nginx.service", CgroupKind::Unit),
+            "nginx.service"
+        );
+    }
+
+    #[test]
+    fn reads_rates_and_limits_from_a_cgroup_tree() {
+        const DOCKER: &str = "system.slice/docker-3f2a9c1b7d4e5f60718293a4.scope";
+        const NGINX: &str = "system.slice/nginx.service";
+        const APP: &str = "user.slice/user-1000.slice/user@1000.service/app.slice/editor.service";
+
+        let root = TempDir::new("cgroups");
+        let write_group = |group: &str, usage_usec: u64, io: &str, max: &str| {
+            root.write(
+                &format!("{group}/cpu.stat"),
+                &format!("usage_usec {usage_usec}\nuser_usec 0\n"),
+            );
+            root.write(&format!("{group}/io.stat"), io);
+            root.write(&format!("{group}/memory.current"), "4096\n");
+            root.write(&format!("{group}/memory.max"), max);
+        };
+        write_group(
+            DOCKER,
+            1_000_000,
+            "8:0 rbytes=1000 wbytes=2000 rios=1 wios=2\n259:0 rbytes=500 wbytes=0\n",
+            "max\n",
+        );
+        write_group(NGINX, 0, "", "1073741824\n");
+        write_group(APP, 0, "", "max\n");
+        // Slices themselves carry counters too; they must not be reported.
+        root.write("system.slice/cpu.stat", "usage_usec 99999999\n");
+
+        let mut reader = CgroupReader::new(root.path());
+        let first = reader.read(None);
+        let paths: Vec<&str> = first.iter().map(|group| group.path.as_str()).collect();
+        assert_eq!(paths, [DOCKER, NGINX, APP]);
+        assert!(first.iter().all(|group| group.cpu_percent == 0.0));
+
+        write_group(
+            DOCKER,
+            3_000_000,
+            "8:0 rbytes=5000 wbytes=2000\n259:0 rbytes=2500 wbytes=4000\n",
+            "max\n",
+        );
+        let second = reader.read(Some(Duration::from_secs(2)));
+        let docker = &second[0];
+        assert_eq!(docker.name, "docker 3f2a9c1b7d4e");
+        assert_eq!(docker.kind, CgroupKind::Container);
+        // Two seconds of CPU over two seconds is one full core.
+        assert!((docker.cpu_percent - 100.0).abs() < 1e-9);
+        // Read bytes grew by 4000 + 2000 and write bytes by 4000, across both devices.
+        assert!((docker.io_read_bytes_per_sec - 3000.0).abs() < 1e-9);
+        assert!((docker.io_write_bytes_per_sec - 2000.0).abs() < 1e-9);
+        assert_eq!(docker.memory_bytes, 4096);
+        assert_eq!(docker.memory_limit, None);
+
+        let nginx = &second[1];
+        assert_eq!(nginx.kind, CgroupKind::Unit);
+        assert_eq!(nginx.memory_limit, Some(1 << 30));
+        assert_eq!(second[2].name, "editor.service");
+    }
+}
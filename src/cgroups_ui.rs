@@ -0,0 +1,173 @@
+use gtk4::prelude::*;
+use gtk4::{Align, Box, Label, ListBox, ListBoxRow, Orientation, ToggleButton};
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+use crate::cgroups::CgroupStats;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CgroupSort {
+    Cpu,
+    Memory,
+    Io,
+    Name,
+}
+
+/// "Containers & units" list: per-cgroup CPU, memory and I/O, sortable by column.
+#[derive(Clone)]
+pub struct CgroupPanel {
+    pub widget: Box,
+    list: ListBox,
+    sort: Rc<Cell<CgroupSort>>,
+    latest: Rc<RefCell<Vec<CgroupStats>>>,
+    limit: Rc<Cell<usize>>,
+    empty: Label,
+}
+
+impl CgroupPanel {
+    pub fn new() -> Self {
+        let widget = Box::new(Orientation::Vertical, 6);
+        widget.add_css_class("process-panel");
+
+        let sort_row = Box::new(Orientation::Horizontal, 4);
+        sort_row.set_halign(Align::End);
+        let sort_label = Label::new(Some("Sort by"));
+        sort_label.add_css_class("card-title");
+        sort_row.append(&sort_label);
+
+        let list = ListBox::new();
+        list.add_css_class("process-list");
+        list.set_selection_mode(gtk4::SelectionMode::None);
+
+        let empty = Label::new(Some("No containers or units found under the cgroup root."));
+        empty.add_css_class("card-detail");
+        empty.set_wrap(true);
+        empty.set_visible(false);
+
+        let panel = Self {
+            widget,
+            list,
+            sort: Rc::new(Cell::new(CgroupSort::Cpu)),
+            latest: Rc::new(RefCell::new(Vec::new())),
+            limit: Rc::new(Cell::new(8)),
+            empty,
+        };
+
+        let mut group: Option<ToggleButton> = None;
+        for (label, sort) in [
+            ("CPU", CgroupSort::Cpu),
+            ("Memory", CgroupSort::Memory),
+            ("I/O", CgroupSort::Io),
+            ("Name", CgroupSort::Name),
+        ] {
+            let btn = ToggleButton::with_label(label);
+            btn.add_css_class("pill-btn");
+            btn.set_group(group.as_ref());
+            btn.set_active(sort == CgroupSort::Cpu);
+            let panel_clone = panel.clone();
+            btn.connect_toggled(move |btn| {
+                if btn.is_active() {
+                    panel_clone.sort.set(sort);
+                    panel_clone.render();
+                }
+            });
+            sort_row.append(&btn);
+            group.get_or_insert(btn);
+        }
+
+        panel.widget.append(&sort_row);
+        panel.widget.append(&panel.empty);
+        panel.widget.append(&panel.list);
+        panel
+    }
+
+    pub fn update(&self, groups: &[CgroupStats], limit: usize) {
+        self.latest.replace(groups.to_vec());
+        self.limit.set(limit);
+        self.render();
+    }
+
+    fn render(&self) {
+        while let Some(child) = self.list.first_child() {
+            self.list.remove(&child);
+        }
+
+        let mut rows = self.latest.borrow().clone();
+        self.empty.set_visible(rows.is_empty());
+        match self.sort.get() {
+            CgroupSort::Cpu => rows.sort_by(|a, b| b.cpu_percent.total_cmp(&a.cpu_percent)),
+            CgroupSort::Memory => rows.sort_by_key(|g| std::cmp::Reverse(g.memory_bytes)),
+            CgroupSort::Io => rows.sort_by(|a, b| io_rate(b).total_cmp(&io_rate(a))),
+            CgroupSort::Name => rows.sort_by_key(|g| g.name.to_lowercase()),
+        }
+        rows.truncate(self.limit.get());
+
+        for group in rows {
+            self.list.append(&build_row(&group));
+        }
+    }
+}
+
+fn io_rate(group: &CgroupStats) -> f64 {
+    group.io_read_bytes_per_sec + group.io_write_bytes_per_sec
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn mib(bytes: u64) -> f64 {
+    bytes as f64 / 1024.0 / 1024.0
+}
+
+fn build_row(group: &CgroupStats) -> ListBoxRow {
+    let row = ListBoxRow::new();
+    row.add_css_class("process-row");
+
+    let line = Box::new(Orientation::Horizontal, 8);
+
+    let kind = Label::new(Some(group.kind.label()));
+    kind.add_css_class("process-user");
+    kind.set_width_chars(9);
+    kind.set_xalign(0.0);
+
+    let name = Label::new(Some(&group.name));
+    name.add_css_class("process-name");
+    name.set_hexpand(true);
+    name.set_xalign(0.0);
+    name.set_ellipsize(gtk4::pango::EllipsizeMode::End);
+
+    let cpu = Label::new(Some(&format!("{:.1}%", group.cpu_percent)));
+    cpu.set_width_chars(7);
+    cpu.set_xalign(1.0);
+
+    let mem = Label::new(Some(&format!("{:.0} MB", mib(group.memory_bytes))));
+    mem.set_width_chars(8);
+    mem.set_xalign(1.0);
+
+    let io = Label::new(Some(&format!(
+        "R {:.1} W {:.1} MB/s",
+        mib_rate(group.io_read_bytes_per_sec),
+        mib_rate(group.io_write_bytes_per_sec)
+    )));
+    io.add_css_class("process-pid");
+    io.set_width_chars(18);
+    io.set_xalign(1.0);
+
+    line.append(&kind);
+    line.append(&name);
+    line.append(&cpu);
+    line.append(&mem);
+    line.append(&io);
+    row.set_child(Some(&line));
+
+    let limit = group
+        .memory_limit
+        .map(|limit| format!("\nMemory limit: {:.0} MB", mib(limit)))
+        .unwrap_or_default();
+    row.set_tooltip_text(Some(&format!("{}{limit}", group.path)));
+    row
+}
+
+fn mib_rate(bytes_per_sec: f64) -> f64 {
+    bytes_per_sec / 1024.0 / 1024.0
+}
@@ -0,0 +1,412 @@
+use gtk4::prelude::*;
+use gtk4::{cairo, glib, DrawingArea, EventControllerMotion};
+use std::{cell::RefCell, rc::Rc};
+
+use crate::settings::{ChartScale, Theme};
+
+/// One line on a chart: `(timestamp, value)` points, oldest first.
+#[derive(Clone, Debug, Default)]
+pub struct ChartSeries {
+    pub label: String,
+    pub points: Vec<(u64, f64)>,
+}
+
+struct ChartState {
+    series: Vec<ChartSeries>,
+    span_secs: u64,
+    scale: ChartScale,
+    /// Top of the scale in `ChartScale::Fixed` mode.
+    fixed_max: f64,
+    show_axes: bool,
+    unit: String,
+    palette: [(f64, f64, f64); 4],
+    /// Status colour of the card; replaces the colour of a lone series and tints the
+    /// background behind several.
+    level_colour: Option<(f64, f64, f64)>,
+    hover_x: Option<f64>,
+}
+
+/// Time-series chart for a monitor card: one or more series over a fixed time span, optional
+/// y-axis gridlines and a crosshair that reads out the values under the pointer.
+#[derive(Clone)]
+pub struct Chart {
+    pub area: DrawingArea,
+    state: Rc<RefCell<ChartState>>,
+}
+
+impl Chart {
+    pub fn new(fixed_max: f64) -> Self {
+        let area = DrawingArea::new();
+        area.add_css_class("monitor-chart");
+        let state = Rc::new(RefCell::new(ChartState {
+            series: Vec::new(),
+            span_secs: 300,
+            scale: ChartScale::Auto,
+            fixed_max,
+            show_axes: true,
+            unit: String::new(),
+            palette: palette(&Theme::Dark),
+            level_colour: None,
+            hover_x: None,
+        }));
+
+        let motion = EventControllerMotion::new();
+        {
+            let (state, area) = (state.clone(), area.clone());
+            motion.connect_motion(move |_, x, _| {
+                state.borrow_mut().hover_x = Some(x);
+                area.queue_draw();
+            });
+        }
+        {
+            let (state, area) = (state.clone(), area.clone());
+            motion.connect_leave(move |_| {
+                state.borrow_mut().hover_x = None;
+                area.queue_draw();
+            });
+        }
+        area.add_controller(motion);
+
+        {
+            let state = state.clone();
+            area.set_draw_func(move |area, cr, width, height| {
+                draw(
+                    &state.borrow(),
+                    area,
+                    cr,
+                    f64::from(width),
+                    f64::from(height),
+                );
+            });
+        }
+
+        Self { area, state }
+    }
+
+    pub fn set_series(&self, series: Vec<ChartSeries>) {
+        self.state.borrow_mut().series = series;
+        self.area.queue_draw();
+    }
+
+    pub fn series(&self) -> Vec<ChartSeries> {
+        self.state.borrow().series.clone()
+    }
+
+    pub fn set_span(&self, secs: u64) {
+        self.state.borrow_mut().span_secs = secs.max(1);
+        self.area.queue_draw();
+    }
+
+    pub fn set_unit(&self, unit: &str) {
+        unit.clone_into(&mut self.state.borrow_mut().unit);
+    }
+
+    pub fn set_options(&self, scale: ChartScale, show_axes: bool, theme: &Theme) {
+        let mut state = self.state.borrow_mut();
+        state.scale = scale;
+        state.show_axes = show_axes;
+        state.palette = palette(theme);
+        drop(state);
+        self.area.queue_draw();
+    }
+
+    pub fn set_level_colour(&self, colour: Option<(f64, f64, f64)>) {
+        let mut state = self.state.borrow_mut();
+        if state.level_colour == colour {
+            return;
+        }
+        state.level_colour = colour;
+        drop(state);
+        self.area.queue_draw();
+    }
+}
+
+/// Series colours per theme, starting with the theme's accent colours from `style.css`.
+fn palette(theme: &Theme) -> [(f64, f64, f64); 4] {
+    let rgb = |hex: u32| {
+        let channel = |shift: u32| f64::from((hex >> shift) & 0xff) / 255.0;
+        (channel(16), channel(8), channel(0))
+    };
+    let [a, b, c, d] = match theme {
+        Theme::Dark => [0x006d_d3ff, 0x007e_8cff, 0x007b_d88f, 0x00f0_c674],
+        Theme::Light => [0x0025_63eb, 0x0022_c55e, 0x00d9_7706, 0x00db_2777],
+        Theme::Solarized => [0x0026_8bd2, 0x00b5_8900, 0x0085_9900, 0x00d3_3682],
+        Theme::Tokyo => [0x007a_a2f7, 0x00e0_af68, 0x009e_ce6a, 0x00bb_9af7],
+    };
+    [rgb(a), rgb(b), rgb(c), rgb(d)]
+}
+
+/// Rounds up to 1, 2 or 5 times a power of ten so the auto scale does not twitch every frame.
+fn nice_ceiling(value: f64) -> f64 {
+    if value <= 0.0 {
+        return 1.0;
+    }
+    let magnitude = 10_f64.powf(value.log10().floor());
+    [1.0, 2.0, 5.0, 10.0]
+        .iter()
+        .map(|step| step * magnitude)
+        .find(|candidate| *candidate >= value)
+        .unwrap_or(10.0 * magnitude)
+}
+
+fn format_value(value: f64, unit: &str) -> String {
+    if value.abs() >= 100.0 || value.fract() == 0.0 {
+        format!("{value:.0}{unit}")
+    } else {
+        format!("{value:.1}{unit}")
+    }
+}
+
+/// Mapping from `(timestamp, value)` to chart coordinates for one frame.
+struct Frame {
+    newest: u64,
+    span: f64,
+    max: f64,
+    width: f64,
+    height: f64,
+}
+
+impl Frame {
+    #[allow(clippy::cast_precision_loss)]
+    fn x(&self, ts: u64) -> f64 {
+        self.width * (1.0 - self.newest.saturating_sub(ts) as f64 / self.span)
+    }
+
+    fn y(&self, value: f64) -> f64 {
+        (value / self.max)
+            .clamp(0.0, 1.0)
+            .mul_add(-self.height * 0.95, self.height)
+    }
+
+    /// Timestamp under horizontal position `x`.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn ts_at(&self, x: f64) -> u64 {
+        self.newest
+            .saturating_sub(((1.0 - x / self.width) * self.span).max(0.0) as u64)
+    }
+}
+
+impl ChartState {
+    const fn colour(&self, index: usize) -> (f64, f64, f64) {
+        match self.level_colour {
+            Some(colour) if self.series.len() == 1 => colour,
+            _ => self.palette[index % self.palette.len()],
+        }
+    }
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn draw(state: &ChartState, area: &DrawingArea, cr: &cairo::Context, width: f64, height: f64) {
+    let newest = state
+        .series
+        .iter()
+        .filter_map(|s| s.points.last().map(|(ts, _)| *ts))
+        .max();
+    let Some(newest) = newest else {
+        return;
+    };
+    let max = match state.scale {
+        ChartScale::Fixed => state.fixed_max.max(f64::EPSILON),
+        ChartScale::Auto => nice_ceiling(
+            state
+                .series
+                .iter()
+                .flat_map(|s| s.points.iter().map(|(_, v)| *v))
+                .fold(0.0, f64::max),
+        ),
+    };
+    let frame = Frame {
+        newest,
+        span: state.span_secs as f64,
+        max,
+        width,
+        height,
+    };
+
+    #[allow(deprecated)]
+    let text = area.style_context().color();
+    let text = (
+        f64::from(text.red()),
+        f64::from(text.green()),
+        f64::from(text.blue()),
+    );
+
+    if state.show_axes {
+        draw_gridlines(state, &frame, cr, text);
+    }
+    draw_series(state, &frame, cr);
+    if let Some(hover_x) = state.hover_x {
+        draw_crosshair(state, &frame, cr, text, hover_x);
+    }
+}
+
+fn draw_gridlines(state: &ChartState, frame: &Frame, cr: &cairo::Context, text: (f64, f64, f64)) {
+    let (r, g, b) = text;
+    cr.set_line_width(1.0);
+    cr.set_font_size(9.0);
+    for step in 1..=4 {
+        let value = frame.max * f64::from(step) / 4.0;
+        let y = frame.y(value).round() + 0.5;
+        cr.set_source_rgba(r, g, b, 0.12);
+        cr.move_to(0.0, y);
+        cr.line_to(frame.width, y);
+        let _ = cr.stroke();
+        if step % 2 == 0 {
+            cr.set_source_rgba(r, g, b, 0.6);
+            cr.move_to(2.0, y + 10.0);
+            let _ = cr.show_text(&format_value(value, &state.unit));
+        }
+    }
+}
+
+fn draw_series(state: &ChartState, frame: &Frame, cr: &cairo::Context) {
+    let single = state.series.len() == 1;
+    if let (false, Some((r, g, b))) = (single, state.level_colour) {
+        cr.set_source_rgba(r, g, b, 0.08);
+        cr.rectangle(0.0, 0.0, frame.width, frame.height);
+        let _ = cr.fill();
+    }
+    for (index, series) in state.series.iter().enumerate() {
+        let Some((first_ts, _)) = series.points.first() else {
+            continue;
+        };
+        if series.points.len() < 2 {
+            continue;
+        }
+        let (r, g, b) = state.colour(index);
+
+        // Only a single series is filled; overlapping fills would hide each other.
+        if single {
+            cr.set_source_rgba(r, g, b, 0.15);
+            cr.move_to(frame.x(*first_ts), frame.height);
+            for (ts, value) in &series.points {
+                cr.line_to(frame.x(*ts), frame.y(*value));
+            }
+            cr.line_to(frame.width, frame.height);
+            cr.close_path();
+            let _ = cr.fill();
+        }
+
+        cr.set_source_rgba(r, g, b, 0.85);
+        cr.set_line_width(if single { 2.0 } else { 1.5 });
+        cr.move_to(frame.x(*first_ts), frame.y(series.points[0].1));
+        for (ts, value) in &series.points[1..] {
+            cr.line_to(frame.x(*ts), frame.y(*value));
+        }
+        let _ = cr.stroke();
+    }
+}
+
+/// Vertical line under the pointer with a dot on each series and a readout of the time and
+/// the nearest values.
+fn draw_crosshair(
+    state: &ChartState,
+    frame: &Frame,
+    cr: &cairo::Context,
+    text: (f64, f64, f64),
+    hover_x: f64,
+) {
+    let hover_ts = frame.ts_at(hover_x);
+    let x = hover_x.round() + 0.5;
+    cr.set_source_rgba(text.0, text.1, text.2, 0.4);
+    cr.set_line_width(1.0);
+    cr.move_to(x, 0.0);
+    cr.line_to(x, frame.height);
+    let _ = cr.stroke();
+
+    let mut readout = Vec::new();
+    for (index, series) in state.series.iter().enumerate() {
+        let nearest = series
+            .points
+            .iter()
+            .min_by_key(|(ts, _)| ts.abs_diff(hover_ts));
+        let Some((ts, value)) = nearest else {
+            continue;
+        };
+        let (r, g, b) = state.colour(index);
+        cr.set_source_rgba(r, g, b, 1.0);
+        cr.arc(
+            frame.x(*ts),
+            frame.y(*value),
+            2.5,
+            0.0,
+            std::f64::consts::TAU,
+        );
+        let _ = cr.fill();
+        let value = format_value(*value, &state.unit);
+        readout.push(if state.series.len() == 1 {
+            value
+        } else {
+            format!("{} {value}", series.label)
+        });
+    }
+
+    let time = glib::DateTime::from_unix_local(i64::try_from(hover_ts).unwrap_or_default())
+        .ok()
+        .and_then(|t| t.format("%H:%M:%S").ok())
+        .map(|t| t.to_string())
+        .unwrap_or_default();
+    let label = format!("{time}  {}", readout.join(" · "));
+
+    cr.set_font_size(10.0);
+    let Ok(extents) = cr.text_extents(&label) else {
+        return;
+    };
+    // Keep the readout inside the chart, flipping to the left of the crosshair near the edge.
+    let text_x = if hover_x + extents.width() + 8.0 > frame.width {
+        (hover_x - extents.width() - 6.0).max(0.0)
+    } else {
+        hover_x + 6.0
+    };
+    cr.set_source_rgba(0.0, 0.0, 0.0, 0.55);
+    cr.rectangle(text_x - 3.0, 2.0, extents.width() + 6.0, 14.0);
+    let _ = cr.fill();
+    cr.set_source_rgba(1.0, 1.0, 1.0, 0.95);
+    cr.move_to(text_x, 12.5);
+    let _ = cr.show_text(&label);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ceiling_is(value: f64, expected: f64) -> bool {
+        (nice_ceiling(value) - expected).abs() <= expected * 1e-9
+    }
+
+    #[test]
+    fn empty_charts_get_a_unit_ceiling() {
+        assert!(ceiling_is(0.0, 1.0));
+        assert!(ceiling_is(-3.0, 1.0));
+    }
+
+    #[test]
+    fn ceilings_round_up_to_one_two_or_five() {
+        assert!(ceiling_is(0.03, 0.05));
+        assert!(ceiling_is(0.2, 0.2));
+        assert!(ceiling_is(0.7, 1.0));
+        assert!(ceiling_is(1.5, 2.0));
+        assert!(ceiling_is(3.0, 5.0));
+        assert!(ceiling_is(7.0, 10.0));
+        assert!(ceiling_is(12_345.0, 20_000.0));
+        assert!(ceiling_is(6.2e9, 1e10));
+    }
+
+    #[test]
+    fn powers_of_ten_are_their_own_ceiling() {
+        for exponent in -2..=9 {
+            let power = 10_f64.powi(exponent);
+            assert!(ceiling_is(power, power), "{power}");
+        }
+    }
+
+    #[test]
+    fn labels_drop_decimals_on_whole_and_large_values() {
+        assert_eq!(format_value(0.0, "%"), "0%");
+        assert_eq!(format_value(42.0, "%"), "42%");
+        assert_eq!(format_value(2.46, " MB/s"), "2.5 MB/s");
+        assert_eq!(format_value(-0.5, "°C"), "-0.5°C");
+        assert_eq!(format_value(150.6, ""), "151");
+        assert_eq!(format_value(-250.4, "W"), "-250W");
+    }
+}
@@ -0,0 +1,280 @@
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::process::{Output, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::monitor::{MonitorData, SamplingConfig, Sensor};
+use crate::platform::script_command;
+use crate::settings::CommandSensorConfig;
+
+/// Latest result of a command sensor.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CommandReading {
+    pub name: String,
+    pub value: Option<f64>,
+    /// Extra text from a JSON `text` field, shown under the value.
+    pub text: Option<String>,
+    /// Why the last run produced no value: spawn failure, timeout, non-zero exit or unparsable
+    /// output.
+    pub error: Option<String>,
+}
+
+/// Runs a user script on its own thread and publishes whatever it last printed. A run still in
+/// flight when the sensor comes due again is left alone rather than stacked, so a slow script
+/// simply reports less often; one that outlasts its interval and [`Self::MIN_TIMEOUT`] is
+/// killed and reported as timed out.
+pub struct CommandSensor {
+    id: String,
+    config: CommandSensorConfig,
+    latest: Arc<Mutex<Option<CommandReading>>>,
+    running: Arc<Mutex<bool>>,
+}
+
+impl CommandSensor {
+    pub const ID_PREFIX: &'static str = "command:";
+
+    /// Shortest time a run is given before it is killed, however short its interval.
+    const MIN_TIMEOUT: Duration = Duration::from_secs(10);
+
+    pub fn new(config: CommandSensorConfig) -> Self {
+        Self {
+            id: format!("{}{}", Self::ID_PREFIX, config.name),
+            config,
+            latest: Arc::new(Mutex::new(None)),
+            running: Arc::new(Mutex::new(false)),
+        }
+    }
+
+    fn start_run(&self) {
+        if let Ok(mut running) = self.running.lock() {
+            if *running {
+                return;
+            }
+            *running = true;
+        }
+
+        let config = self.config.clone();
+        let timeout = self.period().max(Self::MIN_TIMEOUT);
+        let latest = self.latest.clone();
+        let running = self.running.clone();
+        thread::spawn(move || {
+            let reading = run_once(&config, timeout);
+            if let Ok(mut slot) = latest.lock() {
+                *slot = Some(reading);
+            }
+            if let Ok(mut running) = running.lock() {
+                *running = false;
+            }
+        });
+    }
+
+    fn period(&self) -> Duration {
+        Duration::from_millis(self.config.interval_ms.max(250))
+    }
+}
+
+impl Sensor for CommandSensor {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn interval(&self, _sampling: &SamplingConfig) -> Duration {
+        self.period()
+    }
+
+    fn sample(&mut self, data: &mut MonitorData, _elapsed: Option<Duration>) {
+        let finished = self.latest.lock().ok().and_then(|mut slot| slot.take());
+        if let Some(reading) = finished {
+            match data.commands.iter_mut().find(|r| r.name == reading.name) {
+                Some(existing) => *existing = reading,
+                None => data.commands.push(reading),
+            }
+        }
+        self.start_run();
+    }
+}
+
+fn run_once(config: &CommandSensorConfig, timeout: Duration) -> CommandReading {
+    let mut reading = CommandReading {
+        name: config.name.clone(),
+        ..CommandReading::default()
+    };
+
+    match output_within(&config.command, timeout) {
+        Err(e) => reading.error = Some(e),
+        Ok(output) if !output.status.success() => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            reading.error = Some(
+                stderr
+                    .lines()
+                    .next()
+                    .map_or_else(|| format!("exited with {}", output.status), str::to_string),
+            );
+        }
+        Ok(output) => {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            match parse_output(stdout.trim(), config.json_path.as_deref()) {
+                Some((value, text)) => {
+                    reading.value = Some(value);
+                    reading.text = text;
+                }
+                None => reading.error = Some("output is not a number".to_string()),
+            }
+        }
+    }
+    reading
+}
+
+/// Like `Command::output` for `script`, but kills it once `timeout` has passed.
+fn output_within(script: &str, timeout: Duration) -> Result<Output, String> {
+    let mut child = script_command(script)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("could not run: {e}"))?;
+    // Drained on their own threads so a chatty script cannot stall on a full pipe.
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() < deadline => thread::sleep(Duration::from_millis(50)),
+            Ok(None) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!("timed out after {}s", timeout.as_secs_f64()));
+            }
+            Err(e) => return Err(format!("could not run: {e}")),
+        }
+    };
+    Ok(Output {
+        status,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    })
+}
+
+fn drain(pipe: Option<impl Read + Send + 'static>) -> JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut bytes = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut bytes);
+        }
+        bytes
+    })
+}
+
+/// Reads a bare number, or the value at `json_path` (default `value`) from a JSON document
+/// along with an optional top-level `text` field.
+fn parse_output(stdout: &str, json_path: Option<&str>) -> Option<(f64, Option<String>)> {
+    if let Ok(value) = stdout.parse::<f64>() {
+        return Some((value, None));
+    }
+
+    let document: serde_json::Value = serde_json::from_str(stdout).ok()?;
+    let mut node = &document;
+    for key in json_path.unwrap_or("value").split('.') {
+        node = match node {
+            serde_json::Value::Array(items) => items.get(key.parse::<usize>().ok()?)?,
+            _ => node.get(key)?,
+        };
+    }
+    let value = match node {
+        serde_json::Value::Number(n) => n.as_f64()?,
+        serde_json::Value::String(s) => s.trim().parse().ok()?,
+        serde_json::Value::Bool(b) => f64::from(u8::from(*b)),
+        _ => return None,
+    };
+    let text = document
+        .get("text")
+        .and_then(serde_json::Value::as_str)
+        .map(str::to_string);
+    Some((value, text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(stdout: &str, json_path: Option<&str>) -> Option<f64> {
+        parse_output(stdout, json_path).map(|(value, _)| value)
+    }
+
+    #[test]
+    fn parses_bare_numbers() {
+        assert_eq!(parse_output("42", None), Some((42.0, None)));
+        assert_eq!(value("-1.5e3", None), Some(-1500.0));
+        assert_eq!(value("", None), None);
+        assert_eq!(value("12 jobs", None), None);
+    }
+
+    #[test]
+    fn follows_dotted_json_paths() {
+        let document =
+            r#"{"value": 3, "queue": {"depth": 7, "workers": [{"busy": 2}, {"busy": 5}]}}"#;
+        assert_eq!(value(document, None), Some(3.0));
+        assert_eq!(value(document, Some("queue.depth")), Some(7.0));
+        assert_eq!(value(document, Some("queue.workers.1.busy")), Some(5.0));
+        assert_eq!(value(document, Some("queue.workers.2.busy")), None);
+        assert_eq!(value(document, Some("queue.workers.first")), None);
+        assert_eq!(value(document, Some("queue.missing")), None);
+        assert_eq!(value(document, Some("queue")), None);
+        assert_eq!(value("[4, 9]", Some("1")), Some(9.0));
+    }
+
+    #[test]
+    fn converts_string_and_bool_values() {
+        assert_eq!(value(r#"{"value": " 12.5 "}"#, None), Some(12.5));
+        assert_eq!(value(r#"{"value": "n/a"}"#, None), None);
+        assert_eq!(value(r#"{"value": true}"#, None), Some(1.0));
+        assert_eq!(value(r#"{"value": false}"#, None), Some(0.0));
+        assert_eq!(value(r#"{"value": null}"#, None), None);
+    }
+
+    #[test]
+    fn keeps_the_top_level_text() {
+        assert_eq!(
+            parse_output(r#"{"value": 1, "text": "3 queued"}"#, None),
+            Some((1.0, Some("3 queued".to_string())))
+        );
+        assert_eq!(
+            parse_output(r#"{"value": 1, "text": 3}"#, None),
+            Some((1.0, None))
+        );
+        assert_eq!(value(r#"{"text": "no value"}"#, None), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn reports_runs_that_fail_or_time_out() {
+        let config = |command: &str| CommandSensorConfig {
+            name: "probe".to_string(),
+            command: command.to_string(),
+            ..CommandSensorConfig::default()
+        };
+        let timeout = Duration::from_millis(300);
+
+        let reading = run_once(&config(r#"echo '{"value": 4, "text": "ok"}'"#), timeout);
+        assert_eq!(reading.value, Some(4.0));
+        assert_eq!(reading.text.as_deref(), Some("ok"));
+        assert_eq!(reading.error, None);
+
+        let reading = run_once(&config("echo 'no such queue' >&2; exit 3"), timeout);
+        assert_eq!(reading.value, None);
+        assert_eq!(reading.error.as_deref(), Some("no such queue"));
+
+        let reading = run_once(&config("echo soon"), timeout);
+        assert_eq!(reading.error.as_deref(), Some("output is not a number"));
+
+        let started = Instant::now();
+        let reading = run_once(&config("exec sleep 5"), timeout);
+        assert!(started.elapsed() < Duration::from_secs(2));
+        assert_eq!(reading.value, None);
+        assert_eq!(reading.error.as_deref(), Some("timed out after 0.3s"));
+    }
+}
@@ -0,0 +1,501 @@
+use std::fmt::Write as _;
+use std::io::{Read, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use crate::monitor::MonitorData;
+use crate::power::ChargeState;
+
+/// Serves the latest sample in the Prometheus text format on `127.0.0.1:<port>/metrics`.
+///
+/// The monitoring thread publishes each sample here, so scrapes never trigger a reading of
+/// their own. Requests are answered one at a time on a dedicated thread.
+pub struct MetricsExporter {
+    port: Option<u16>,
+    page: Arc<Mutex<String>>,
+    stop: Option<Arc<AtomicBool>>,
+}
+
+impl MetricsExporter {
+    /// Starts listening on `port`; `None` creates a disabled exporter.
+    pub fn new(port: Option<u16>) -> Self {
+        let mut exporter = Self {
+            port: None,
+            page: Arc::new(Mutex::new(String::new())),
+            stop: None,
+        };
+        exporter.set_port(port);
+        exporter
+    }
+
+    /// Rebinds to `port`, or stops serving when it is `None`.
+    pub fn set_port(&mut self, port: Option<u16>) {
+        if port == self.port {
+            return;
+        }
+        self.shutdown();
+        // Only a port we are listening on counts, so applying the same one again retries.
+        self.port = None;
+        let Some(port) = port else {
+            return;
+        };
+
+        let listener = match TcpListener::bind((Ipv4Addr::LOCALHOST, port)) {
+            Ok(listener) => listener,
+            Err(e) => {
+                eprintln!("Metrics exporter could not listen on port {port}: {e}");
+                return;
+            }
+        };
+        self.port = Some(port);
+        let stop = Arc::new(AtomicBool::new(false));
+        self.stop = Some(stop.clone());
+        let page = self.page.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                if stop.load(Ordering::Relaxed) {
+                    break;
+                }
+                match stream {
+                    Ok(stream) => {
+                        if let Err(e) = serve(stream, &page) {
+                            eprintln!("Metrics exporter request failed: {e}");
+                        }
+                    }
+                    Err(e) => eprintln!("Metrics exporter accept failed: {e}"),
+                }
+            }
+        });
+    }
+
+    pub fn publish(&self, data: &MonitorData) {
+        if self.stop.is_none() {
+            return;
+        }
+        if let Ok(mut page) = self.page.lock() {
+            *page = render(data);
+        }
+    }
+
+    fn shutdown(&mut self) {
+        let Some(stop) = self.stop.take() else {
+            return;
+        };
+        stop.store(true, Ordering::Relaxed);
+        // `incoming` blocks until the next connection, so make one to let the thread exit.
+        if let Some(port) = self.port {
+            let _ = TcpStream::connect((Ipv4Addr::LOCALHOST, port));
+        }
+    }
+}
+
+impl Drop for MetricsExporter {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+fn serve(mut stream: TcpStream, page: &Mutex<String>) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(2)))?;
+    let mut request = Vec::new();
+    let mut buf = [0_u8; 1024];
+    while !request.windows(4).any(|w| w == b"\r\n\r\n") && request.len() < 8192 {
+        let read = stream.read(&mut buf)?;
+        if read == 0 {
+            break;
+        }
+        request.extend_from_slice(&buf[..read]);
+    }
+
+    let request = String::from_utf8_lossy(&request);
+    let mut parts = request.split_whitespace();
+    let (status, body) = match (parts.next(), parts.next()) {
+        (Some("GET"), Some("/metrics" | "/")) => (
+            "200 OK",
+            page.lock().map(|page| page.clone()).unwrap_or_default(),
+        ),
+        (Some("GET"), _) => ("404 Not Found", "Not found\n".to_string()),
+        _ => (
+            "405 Method Not Allowed",
+            "Only GET is supported\n".to_string(),
+        ),
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\n\
+         Content-Type: text/plain; version=0.0.4; charset=utf-8\r\n\
+         Content-Length: {}\r\n\
+         Connection: close\r\n\r\n{body}",
+        body.len()
+    )
+}
+
+/// Text exposition builder; families are written in full before the next one starts.
+struct Exposition {
+    out: String,
+}
+
+impl Exposition {
+    fn family(&mut self, name: &str, kind: &str, help: &str) {
+        let _ = writeln!(self.out, "# HELP vitray_{name} {help}");
+        let _ = writeln!(self.out, "# TYPE vitray_{name} {kind}");
+    }
+
+    fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: f64) {
+        let _ = write!(self.out, "vitray_{name}");
+        if !labels.is_empty() {
+            let labels: Vec<String> = labels
+                .iter()
+                .map(|(key, value)| format!("{key}=\"{}\"", escape_label(value)))
+                .collect();
+            let _ = write!(self.out, "{{{}}}", labels.join(","));
+        }
+        let _ = writeln!(self.out, " {value}");
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Renders `data` as Prometheus text exposition, with every metric prefixed `vitray_`.
+#[allow(clippy::cast_precision_loss, clippy::too_many_lines)]
+pub fn render(data: &MonitorData) -> String {
+    let mut m = Exposition { out: String::new() };
+
+    m.family("cpu_usage_percent", "gauge", "Overall CPU usage.");
+    m.sample("cpu_usage_percent", &[], f64::from(data.cpu_usage));
+    m.family(
+        "cpu_core_usage_percent",
+        "gauge",
+        "Usage of each logical core.",
+    );
+    for (index, core) in data.cores.iter().enumerate() {
+        let core_id = index.to_string();
+        m.sample(
+            "cpu_core_usage_percent",
+            &[("core", &core_id)],
+            f64::from(core.usage),
+        );
+    }
+    m.family(
+        "cpu_core_frequency_hertz",
+        "gauge",
+        "Clock of each logical core.",
+    );
+    for (index, core) in data.cores.iter().enumerate() {
+        let core_id = index.to_string();
+        let hertz = core.frequency_mhz as f64 * 1e6;
+        m.sample("cpu_core_frequency_hertz", &[("core", &core_id)], hertz);
+    }
+    m.family("load_average", "gauge", "System load average.");
+    for (period, value) in [
+        ("1m", data.load_average.one),
+        ("5m", data.load_average.five),
+        ("15m", data.load_average.fifteen),
+    ] {
+        m.sample("load_average", &[("period", period)], value);
+    }
+    m.family("uptime_seconds", "gauge", "Time since boot.");
+    m.sample("uptime_seconds", &[], data.uptime_secs as f64);
+
+    for (name, help, value) in [
+        ("memory_used_bytes", "Memory in use.", data.ram_used),
+        ("memory_total_bytes", "Installed memory.", data.ram_total),
+        (
+            "memory_available_bytes",
+            "Memory available to new work.",
+            data.ram_available,
+        ),
+        (
+            "memory_cached_bytes",
+            "Page cache and reclaimable slab.",
+            data.ram_cached,
+        ),
+        (
+            "memory_buffers_bytes",
+            "Block device buffers.",
+            data.ram_buffers,
+        ),
+        ("swap_used_bytes", "Swap in use.", data.swap_used),
+        ("swap_total_bytes", "Swap configured.", data.swap_total),
+    ] {
+        m.family(name, "gauge", help);
+        m.sample(name, &[], value as f64);
+    }
+
+    m.family(
+        "network_receive_bytes_total",
+        "counter",
+        "Bytes received since boot.",
+    );
+    for iface in &data.interfaces {
+        let labels = [("interface", iface.name.as_str())];
+        m.sample(
+            "network_receive_bytes_total",
+            &labels,
+            iface.rx_bytes as f64,
+        );
+    }
+    m.family(
+        "network_transmit_bytes_total",
+        "counter",
+        "Bytes sent since boot.",
+    );
+    for iface in &data.interfaces {
+        let labels = [("interface", iface.name.as_str())];
+        m.sample(
+            "network_transmit_bytes_total",
+            &labels,
+            iface.tx_bytes as f64,
+        );
+    }
+    m.family("network_receive_bytes_per_second", "gauge", "Receive rate.");
+    for iface in &data.interfaces {
+        let labels = [("interface", iface.name.as_str())];
+        m.sample(
+            "network_receive_bytes_per_second",
+            &labels,
+            iface.rx_bytes_per_sec,
+        );
+    }
+    m.family(
+        "network_transmit_bytes_per_second",
+        "gauge",
+        "Transmit rate.",
+    );
+    for iface in &data.interfaces {
+        let labels = [("interface", iface.name.as_str())];
+        m.sample(
+            "network_transmit_bytes_per_second",
+            &labels,
+            iface.tx_bytes_per_sec,
+        );
+    }
+    m.family(
+        "network_errors_total",
+        "counter",
+        "Receive and transmit errors since boot.",
+    );
+    for iface in &data.interfaces {
+        let labels = [("interface", iface.name.as_str())];
+        let errors = iface.rx_errors + iface.tx_errors;
+        m.sample("network_errors_total", &labels, errors as f64);
+    }
+
+    m.family(
+        "disk_read_bytes_per_second",
+        "gauge",
+        "Block device read rate.",
+    );
+    for disk in &data.disk_io {
+        let labels = [("device", disk.device.as_str())];
+        m.sample(
+            "disk_read_bytes_per_second",
+            &labels,
+            disk.read_bytes_per_sec,
+        );
+    }
+    m.family(
+        "disk_write_bytes_per_second",
+        "gauge",
+        "Block device write rate.",
+    );
+    for disk in &data.disk_io {
+        let labels = [("device", disk.device.as_str())];
+        m.sample(
+            "disk_write_bytes_per_second",
+            &labels,
+            disk.write_bytes_per_sec,
+        );
+    }
+    m.family(
+        "filesystem_used_bytes",
+        "gauge",
+        "Space used on each mount.",
+    );
+    for mount in &data.mounts {
+        let labels = [
+            ("mountpoint", mount.mount_point.as_str()),
+            ("device", &mount.device),
+        ];
+        m.sample("filesystem_used_bytes", &labels, mount.used as f64);
+    }
+    m.family("filesystem_size_bytes", "gauge", "Size of each mount.");
+    for mount in &data.mounts {
+        let labels = [
+            ("mountpoint", mount.mount_point.as_str()),
+            ("device", &mount.device),
+        ];
+        m.sample("filesystem_size_bytes", &labels, mount.total as f64);
+    }
+
+    m.family(
+        "temperature_celsius",
+        "gauge",
+        "Hardware temperature sensors.",
+    );
+    for reading in &data.temperatures {
+        let labels = [("chip", reading.chip.as_str()), ("sensor", &reading.label)];
+        m.sample("temperature_celsius", &labels, f64::from(reading.celsius));
+    }
+    m.family("fan_rpm", "gauge", "Fan speeds.");
+    for fan in &data.fans {
+        let labels = [("chip", fan.chip.as_str()), ("sensor", &fan.label)];
+        m.sample("fan_rpm", &labels, f64::from(fan.rpm));
+    }
+
+    m.family("gpu_busy_percent", "gauge", "GPU utilisation.");
+    for gpu in &data.gpus {
+        if let Some(busy) = gpu.busy_percent {
+            let labels = [("gpu", gpu.id.as_str()), ("name", &gpu.name)];
+            m.sample("gpu_busy_percent", &labels, f64::from(busy));
+        }
+    }
+    m.family("gpu_memory_used_bytes", "gauge", "GPU memory in use.");
+    for gpu in &data.gpus {
+        if let Some(used) = gpu.vram_used {
+            let labels = [("gpu", gpu.id.as_str()), ("name", &gpu.name)];
+            m.sample("gpu_memory_used_bytes", &labels, used as f64);
+        }
+    }
+    m.family("gpu_temperature_celsius", "gauge", "GPU temperature.");
+    for gpu in &data.gpus {
+        if let Some(temp) = gpu.temperature_c {
+            let labels = [("gpu", gpu.id.as_str()), ("name", &gpu.name)];
+            m.sample("gpu_temperature_celsius", &labels, f64::from(temp));
+        }
+    }
+
+    if let Some(on_ac) = data.power.on_ac {
+        m.family("power_on_ac", "gauge", "1 when running on mains power.");
+        m.sample("power_on_ac", &[], f64::from(u8::from(on_ac)));
+    }
+    m.family("battery_capacity_percent", "gauge", "Battery charge.");
+    for battery in &data.power.batteries {
+        m.sample(
+            "battery_capacity_percent",
+            &[("battery", battery.name.as_str())],
+            f64::from(battery.capacity_percent),
+        );
+    }
+    // One series per state, so a change of state does not start a new capacity series.
+    m.family(
+        "battery_state",
+        "gauge",
+        "1 for the battery's current charge state, 0 for the others.",
+    );
+    for battery in &data.power.batteries {
+        for state in ChargeState::ALL {
+            let labels = [("battery", battery.name.as_str()), ("state", state.label())];
+            m.sample(
+                "battery_state",
+                &labels,
+                f64::from(u8::from(state == battery.state)),
+            );
+        }
+    }
+
+    m.family(
+        "command_value",
+        "gauge",
+        "Latest value of each command sensor.",
+    );
+    for reading in &data.commands {
+        if let Some(value) = reading.value {
+            m.sample("command_value", &[("name", reading.name.as_str())], value);
+        }
+    }
+
+    m.out
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::command_sensor::CommandReading;
+    use crate::power::{BatteryStats, PowerStatus};
+
+    #[test]
+    fn retries_a_port_that_failed_to_bind() {
+        let blocker = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let port = blocker.local_addr().unwrap().port();
+
+        let mut exporter = MetricsExporter::new(Some(port));
+        assert_eq!(exporter.port, None);
+        assert!(exporter.stop.is_none());
+
+        drop(blocker);
+        exporter.set_port(Some(port));
+        assert_eq!(exporter.port, Some(port));
+        assert!(exporter.stop.is_some());
+
+        exporter.set_port(None);
+        assert_eq!(exporter.port, None);
+    }
+
+    #[test]
+    fn escapes_quotes_backslashes_and_newlines_in_labels() {
+        assert_eq!(escape_label("plain"), "plain");
+        assert_eq!(
+            escape_label("say \"hi\"\\now\nthen"),
+            "say \\\"hi\\\"\\\\now\\nthen"
+        );
+    }
+
+    #[test]
+    fn renders_a_sample_as_text_exposition() {
+        let data = MonitorData {
+            cpu_usage: 12.5,
+            ram_used: 3 << 30,
+            power: PowerStatus {
+                on_ac: Some(false),
+                batteries: vec![BatteryStats {
+                    name: "BAT0".to_string(),
+                    capacity_percent: 81.0,
+                    state: ChargeState::Discharging,
+                    power_watts: None,
+                    time_to_empty_secs: None,
+                    time_to_full_secs: None,
+                }],
+            },
+            commands: vec![CommandReading {
+                name: "queue \"main\"\\eu\n2".to_string(),
+                value: Some(7.0),
+                ..CommandReading::default()
+            }],
+            ..MonitorData::default()
+        };
+        let text = render(&data);
+
+        assert!(text.starts_with(
+            "# HELP vitray_cpu_usage_percent Overall CPU usage.\n\
+             # TYPE vitray_cpu_usage_percent gauge\n\
+             vitray_cpu_usage_percent 12.5\n"
+        ));
+        for expected in [
+            "# TYPE vitray_memory_used_bytes gauge\nvitray_memory_used_bytes 3221225472\n",
+            "# TYPE vitray_network_receive_bytes_total counter\n",
+            "vitray_power_on_ac 0\n",
+            "vitray_battery_capacity_percent{battery=\"BAT0\"} 81\n",
+            "vitray_battery_state{battery=\"BAT0\",state=\"Charging\"} 0\n",
+            "vitray_battery_state{battery=\"BAT0\",state=\"Discharging\"} 1\n",
+            "vitray_battery_state{battery=\"BAT0\",state=\"Not charging\"} 0\n",
+            "vitray_command_value{name=\"queue \\\"main\\\"\\\\eu\\n2\"} 7\n",
+        ] {
+            assert!(text.contains(expected), "missing {expected:?} in\n{text}");
+        }
+        // Every family is announced once, right before its samples.
+        let help = text.lines().filter(|l| l.starts_with("# HELP")).count();
+        let kind = text.lines().filter(|l| l.starts_with("# TYPE")).count();
+        assert_eq!(help, kind);
+        assert!(!text.contains("vitray_gpu_busy_percent{"));
+    }
+}
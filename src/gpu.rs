@@ -1,38 +1,499 @@
-use std::process::Command;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
 
-pub struct GpuMonitor;
+/// Snapshot of one adapter. Fields a driver does not expose stay `None`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GpuStats {
+    /// Stable identifier such as `card1` or `nvidia0`.
+    pub id: String,
+    pub name: String,
+    pub driver: String,
+    pub busy_percent: Option<f32>,
+    pub vram_used: Option<u64>,
+    pub vram_total: Option<u64>,
+    pub core_clock_mhz: Option<u32>,
+    pub memory_clock_mhz: Option<u32>,
+    pub temperature_c: Option<f32>,
+    pub power_watts: Option<f32>,
+}
 
-impl GpuMonitor {
-    pub fn get_usage() -> Option<f32> {
-        // Try nvidia-smi first
-        if let Ok(output) = Command::new("nvidia-smi")
-            .args([
-                "--query-gpu=utilization.gpu",
-                "--format=csv,noheader,nounits",
-            ])
-            .output()
-        {
-            if let Ok(stdout) = String::from_utf8(output.stdout) {
-                if let Ok(val) = stdout.trim().parse::<f32>() {
-                    return Some(val);
+/// A source of GPU statistics for one family of drivers.
+pub trait GpuBackend: Send {
+    fn name(&self) -> &'static str;
+
+    /// Reads every adapter this backend knows about. An `Err` means the backend is unusable on
+    /// this machine and should not be polled again.
+    fn poll(&mut self) -> Result<Vec<GpuStats>, String>;
+}
+
+/// Latest reading per adapter with when it was printed, plus the first line `nvidia-smi`
+/// printed that was not data, which is usually its explanation for exiting.
+#[derive(Default)]
+struct NvidiaStream {
+    latest: BTreeMap<String, (Instant, GpuStats)>,
+    last_error: Option<String>,
+}
+
+/// Proprietary NVIDIA driver, read from a single long-lived `nvidia-smi -l 1` process whose
+/// CSV output is parsed line by line on a reader thread.
+pub struct NvidiaSmiBackend {
+    child: Option<Child>,
+    stream: Arc<Mutex<NvidiaStream>>,
+}
+
+impl NvidiaSmiBackend {
+    /// Adapters missing from the output for this long, a couple of `-l 1` rounds, are dropped.
+    const STALE: Duration = Duration::from_secs(3);
+
+    pub fn new() -> Self {
+        Self {
+            child: None,
+            stream: Arc::new(Mutex::new(NvidiaStream::default())),
+        }
+    }
+
+    /// Starts `nvidia-smi` (resolved through `PATH`) and the thread that consumes its output.
+    fn spawn(&mut self) -> Result<(), String> {
+        let mut child = Command::new("nvidia-smi")
+            .args([Self::QUERY, "--format=csv,noheader,nounits", "-l", "1"])
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| format!("could not start nvidia-smi: {e}"))?;
+
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| "nvidia-smi stdout unavailable".to_string())?;
+        let stream = self.stream.clone();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                let Ok(mut shared) = stream.lock() else {
+                    break;
+                };
+                match Self::parse_line(&line) {
+                    Some(stats) => {
+                        shared
+                            .latest
+                            .insert(stats.id.clone(), (Instant::now(), stats));
+                    }
+                    None if !line.trim().is_empty() && shared.last_error.is_none() => {
+                        shared.last_error = Some(line.trim().to_string());
+                    }
+                    None => {}
                 }
             }
+        });
+
+        self.child = Some(child);
+        Ok(())
+    }
+
+    const QUERY: &'static str = "--query-gpu=index,name,utilization.gpu,memory.used,memory.total,\
+                                 clocks.sm,clocks.mem,temperature.gpu,power.draw";
+
+    /// Parses one `--format=csv,noheader,nounits` line of [`Self::QUERY`].
+    fn parse_line(line: &str) -> Option<GpuStats> {
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        if fields.len() < 9 {
+            return None;
         }
+        let mib = |v: &str| parse_field::<u64>(v).map(|m| m * 1024 * 1024);
+        Some(GpuStats {
+            id: format!("nvidia{}", fields[0]),
+            name: fields[1].to_string(),
+            driver: "nvidia".to_string(),
+            busy_percent: parse_field(fields[2]),
+            vram_used: mib(fields[3]),
+            vram_total: mib(fields[4]),
+            core_clock_mhz: parse_field(fields[5]),
+            memory_clock_mhz: parse_field(fields[6]),
+            temperature_c: parse_field(fields[7]),
+            power_watts: parse_field(fields[8]),
+        })
+    }
+}
+
+impl Default for NvidiaSmiBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GpuBackend for NvidiaSmiBackend {
+    fn name(&self) -> &'static str {
+        "nvidia-smi"
+    }
 
-        #[cfg(target_os = "linux")]
-        {
-            // Try intel_gpu_top (requires root usually, so maybe just check sysfs for some cards)
-            // For now, let's check /sys/class/drm/card0/device/gpu_busy_percent if it exists (Intel)
-            if let Ok(content) = std::fs::read_to_string("/sys/class/drm/card0/device/gpu_busy_percent") {
-                 if let Ok(val) = content.trim().parse::<f32>() {
-                     return Some(val);
-                 }
+    fn poll(&mut self) -> Result<Vec<GpuStats>, String> {
+        let Some(child) = self.child.as_mut() else {
+            self.spawn()?;
+            return Ok(Vec::new());
+        };
+
+        if let Ok(Some(status)) = child.try_wait() {
+            self.child = None;
+            let reason = self
+                .stream
+                .lock()
+                .ok()
+                .and_then(|state| state.last_error.clone())
+                .unwrap_or_else(|| "no output".to_string());
+            return Err(format!("nvidia-smi exited ({status}): {reason}"));
+        }
+
+        // Evict by age rather than per round, so a poll landing between the lines of one round
+        // still sees every adapter while ones nvidia-smi stops listing drop out.
+        let Ok(mut state) = self.stream.lock() else {
+            return Ok(Vec::new());
+        };
+        state
+            .latest
+            .retain(|_, (seen, _)| seen.elapsed() < Self::STALE);
+        Ok(state
+            .latest
+            .values()
+            .map(|(_, stats)| stats.clone())
+            .collect())
+    }
+}
+
+impl Drop for NvidiaSmiBackend {
+    fn drop(&mut self) {
+        if let Some(child) = self.child.as_mut() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+/// Kernel DRM drivers (amdgpu, i915, xe, nouveau) read from `/sys/class/drm`.
+pub struct DrmBackend {
+    root: PathBuf,
+}
+
+impl DrmBackend {
+    pub const DEFAULT_ROOT: &'static str = "/sys/class/drm";
+
+    /// Reads from `root` instead of the live sysfs tree, e.g. a fixture directory.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn read_card(card_dir: &Path, id: &str) -> Option<GpuStats> {
+        let device = card_dir.join("device");
+        let driver = fs::read_link(device.join("driver"))
+            .ok()?
+            .file_name()?
+            .to_string_lossy()
+            .into_owned();
+        // The proprietary driver is covered by the nvidia-smi backend.
+        if driver == "nvidia" {
+            return None;
+        }
+
+        let vendor = match read_value::<String>(&device.join("vendor")).as_deref() {
+            Some("0x1002") => "AMD",
+            Some("0x8086") => "Intel",
+            Some("0x10de") => "NVIDIA",
+            _ => "GPU",
+        };
+
+        let hwmon = first_subdir(&device.join("hwmon"));
+        let hwmon_value =
+            |file: &str| hwmon.as_ref().and_then(|h| read_value::<f64>(&h.join(file)));
+
+        #[allow(clippy::cast_possible_truncation)]
+        Some(GpuStats {
+            id: id.to_string(),
+            name: vendor.to_string(),
+            busy_percent: read_value(&device.join("gpu_busy_percent")),
+            vram_used: read_value(&device.join("mem_info_vram_used")),
+            vram_total: read_value(&device.join("mem_info_vram_total")),
+            core_clock_mhz: active_dpm_clock(&device.join("pp_dpm_sclk"))
+                .or_else(|| read_value(&card_dir.join("gt_act_freq_mhz")))
+                .or_else(|| read_value(&device.join("tile0/gt0/freq0/act_freq"))),
+            memory_clock_mhz: active_dpm_clock(&device.join("pp_dpm_mclk")),
+            temperature_c: hwmon_value("temp1_input").map(|milli| (milli / 1000.0) as f32),
+            power_watts: hwmon_value("power1_average")
+                .or_else(|| hwmon_value("power1_input"))
+                .map(|micro| (micro / 1_000_000.0) as f32),
+            driver,
+        })
+    }
+}
+
+impl Default for DrmBackend {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_ROOT)
+    }
+}
+
+impl GpuBackend for DrmBackend {
+    fn name(&self) -> &'static str {
+        "drm"
+    }
+
+    fn poll(&mut self) -> Result<Vec<GpuStats>, String> {
+        // Machines without any DRM device simply have no adapters here.
+        let Ok(entries) = fs::read_dir(&self.root) else {
+            return Ok(Vec::new());
+        };
+        // Only `cardN` itself; connectors show up as `cardN-DP-1` and friends.
+        let mut cards: Vec<(u32, PathBuf)> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                let index = name.strip_prefix("card")?.parse().ok()?;
+                Some((index, entry.path()))
+            })
+            .collect();
+        cards.sort_by_key(|(index, _)| *index);
+
+        Ok(cards
+            .iter()
+            .filter_map(|(index, path)| Self::read_card(path, &format!("card{index}")))
+            .collect())
+    }
+}
+
+/// Runs every configured backend and concatenates their adapters. A backend that fails is
+/// reported once and then dropped, so missing drivers cost nothing on later ticks.
+pub struct GpuMonitor {
+    backends: Vec<Box<dyn GpuBackend>>,
+}
+
+impl GpuMonitor {
+    pub fn new() -> Self {
+        Self::with_backends(vec![
+            Box::new(NvidiaSmiBackend::new()),
+            Box::new(DrmBackend::default()),
+        ])
+    }
+
+    pub fn with_backends(backends: Vec<Box<dyn GpuBackend>>) -> Self {
+        Self { backends }
+    }
+
+    pub fn poll(&mut self) -> Vec<GpuStats> {
+        let mut stats = Vec::new();
+        self.backends.retain_mut(|backend| match backend.poll() {
+            Ok(adapters) => {
+                stats.extend(adapters);
+                true
+            }
+            Err(e) => {
+                eprintln!("GPU backend {} disabled: {e}", backend.name());
+                false
             }
-            
-            // AMD: /sys/class/drm/card0/device/gpu_busy_percent (amdgpu)
-            // Some kernels expose it differently.
+        });
+        stats
+    }
+}
+
+fn parse_field<T: FromStr>(value: &str) -> Option<T> {
+    value.trim().parse().ok()
+}
+
+fn read_value<T: FromStr>(path: &Path) -> Option<T> {
+    parse_field(&fs::read_to_string(path).ok()?)
+}
+
+fn first_subdir(dir: &Path) -> Option<PathBuf> {
+    let mut entries: Vec<PathBuf> = fs::read_dir(dir).ok()?.flatten().map(|e| e.path()).collect();
+    entries.sort();
+    entries.into_iter().next()
+}
+
+/// Current level of an amdgpu `pp_dpm_*` table, e.g. the `1: 1200Mhz *` line.
+fn active_dpm_clock(path: &Path) -> Option<u32> {
+    fs::read_to_string(path)
+        .ok()?
+        .lines()
+        .find(|line| line.trim_end().ends_with('*'))?
+        .split_whitespace()
+        .nth(1)?
+        .trim_end_matches(|c: char| c.is_ascii_alphabetic())
+        .parse()
+        .ok()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::test_support::{wait_for, with_path, TempDir};
+
+    #[test]
+    fn parses_nvidia_smi_line() {
+        let stats = NvidiaSmiBackend::parse_line(
+            "1, NVIDIA GeForce RTX 3080, 37, 1024, 10240, 1710, 9501, 48, 112.34",
+        )
+        .unwrap();
+        assert_eq!(stats.id, "nvidia1");
+        assert_eq!(stats.name, "NVIDIA GeForce RTX 3080");
+        assert_eq!(stats.driver, "nvidia");
+        assert_eq!(stats.busy_percent, Some(37.0));
+        assert_eq!(stats.vram_used, Some(1024 * 1024 * 1024));
+        assert_eq!(stats.vram_total, Some(10240 * 1024 * 1024));
+        assert_eq!(stats.core_clock_mhz, Some(1710));
+        assert_eq!(stats.memory_clock_mhz, Some(9501));
+        assert_eq!(stats.temperature_c, Some(48.0));
+        assert_eq!(stats.power_watts, Some(112.34));
+    }
+
+    #[test]
+    fn unsupported_nvidia_fields_are_none() {
+        let stats = NvidiaSmiBackend::parse_line(
+            "0, Tesla K80, [N/A], 0, 11441, [N/A], [N/A], 30, [Not Supported]",
+        )
+        .unwrap();
+        assert_eq!(stats.busy_percent, None);
+        assert_eq!(stats.vram_used, Some(0));
+        assert_eq!(stats.core_clock_mhz, None);
+        assert_eq!(stats.power_watts, None);
+    }
+
+    #[test]
+    fn rejects_non_data_nvidia_lines() {
+        assert!(NvidiaSmiBackend::parse_line("").is_none());
+        assert!(NvidiaSmiBackend::parse_line(
+            "NVIDIA-SMI has failed because it couldn't communicate with the NVIDIA driver."
+        )
+        .is_none());
+        assert!(NvidiaSmiBackend::parse_line("0, Tesla K80, 12, 0").is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn reads_drm_cards_from_sysfs_tree() {
+        use std::os::unix::fs::symlink;
+
+        let root = TempDir::new("drm");
+        let link_driver = |card: &str, driver: &str| {
+            let link = root.path().join(card).join("device/driver");
+            symlink(format!("../../bus/pci/drivers/{driver}"), link).unwrap();
+        };
+        root.write("card0/device/vendor", "0x1002\n");
+        root.write("card0/device/gpu_busy_percent", "37\n");
+        root.write("card0/device/mem_info_vram_used", "536870912\n");
+        root.write("card0/device/mem_info_vram_total", "8589934592\n");
+        root.write("card0/device/pp_dpm_sclk", "0: 500Mhz\n1: 1200Mhz *\n");
+        root.write("card0/device/pp_dpm_mclk", "0: 96Mhz\n1: 1000Mhz *\n");
+        root.write("card0/device/hwmon/hwmon4/temp1_input", "55000\n");
+        root.write("card0/device/hwmon/hwmon4/power1_average", "31000000\n");
+        link_driver("card0", "amdgpu");
+
+        root.write("card0-DP-1/status", "connected\n");
+
+        root.write("card1/gt_act_freq_mhz", "900\n");
+        root.write("card1/device/vendor", "0x8086\n");
+        link_driver("card1", "i915");
+
+        root.write("card2/device/vendor", "0x10de\n");
+        link_driver("card2", "nvidia");
+
+        // No driver link: not a usable adapter.
+        root.write("card3/device/vendor", "0x1002\n");
+
+        let cards = DrmBackend::new(root.path()).poll().unwrap();
+        assert_eq!(cards.len(), 2);
+
+        let amd = &cards[0];
+        assert_eq!(amd.id, "card0");
+        assert_eq!(amd.name, "AMD");
+        assert_eq!(amd.driver, "amdgpu");
+        assert_eq!(amd.busy_percent, Some(37.0));
+        assert_eq!(amd.vram_used, Some(536_870_912));
+        assert_eq!(amd.vram_total, Some(8_589_934_592));
+        assert_eq!(amd.core_clock_mhz, Some(1200));
+        assert_eq!(amd.memory_clock_mhz, Some(1000));
+        assert_eq!(amd.temperature_c, Some(55.0));
+        assert_eq!(amd.power_watts, Some(31.0));
+
+        let intel = &cards[1];
+        assert_eq!(intel.id, "card1");
+        assert_eq!(intel.name, "Intel");
+        assert_eq!(intel.driver, "i915");
+        assert_eq!(intel.core_clock_mhz, Some(900));
+        assert_eq!(intel.busy_percent, None);
+        assert_eq!(intel.temperature_c, None);
+    }
+
+    #[test]
+    fn missing_drm_root_has_no_adapters() {
+        let root = TempDir::new("drm-missing");
+        let cards = DrmBackend::new(root.path().join("absent")).poll().unwrap();
+        assert!(cards.is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn streams_from_nvidia_smi_on_path() {
+        let bin = TempDir::new("fake-nvidia-smi");
+        bin.script(
+            "nvidia-smi",
+            "echo '0, Fake A, 10, 100, 1000, 1500, 5000, 40, 50.0'\n\
+             echo '1, Fake B, 20, 200, 2000, 1500, 5000, 41, 60.0'\n\
+             sleep 1\n\
+             echo '0, Fake A, 11, 100, 1000, 1500, 5000, 40, 50.0'\n\
+             sleep 1\n\
+             echo '1, Fake B, 21, 200, 2000, 1500, 5000, 41, 60.0'\n\
+             for round in 1 2 3 4 5; do\n\
+                 echo '0, Fake A, 12, 100, 1000, 1500, 5000, 40, 50.0'\n\
+                 sleep 1\n\
+             done\n\
+             echo 'NVIDIA-SMI has failed because it could not talk to the driver.'\n\
+             sleep 1\n\
+             exit 9\n",
+        );
+
+        let mut backend = NvidiaSmiBackend::new();
+        let first = with_path(bin.path(), || backend.poll()).unwrap();
+        assert!(first.is_empty());
+
+        let ids = |adapters: &[GpuStats]| -> String {
+            adapters
+                .iter()
+                .map(|gpu| gpu.id.as_str())
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        assert!(wait_for(|| backend
+            .poll()
+            .is_ok_and(|adapters| ids(&adapters) == "nvidia0,nvidia1")));
+        // Adapter 1 prints late in the second round; polls in between must still list it.
+        let until = Instant::now() + Duration::from_millis(1500);
+        while Instant::now() < until {
+            assert_eq!(ids(&backend.poll().unwrap()), "nvidia0,nvidia1");
+            thread::sleep(Duration::from_millis(50));
         }
-        
-        None
+        // Later rounds only list adapter 0, so adapter 1 ages out.
+        let mut adapters = Vec::new();
+        assert!(wait_for(|| {
+            adapters = backend.poll().unwrap();
+            ids(&adapters) == "nvidia0"
+        }));
+        assert_eq!(adapters[0].busy_percent, Some(12.0));
+
+        let mut error = None;
+        assert!(wait_for(|| {
+            error = backend.poll().err();
+            error.is_some()
+        }));
+        let error = error.unwrap();
+        assert!(error.contains("could not talk to the driver"), "{error}");
     }
 }
@@ -0,0 +1,462 @@
+use directories::ProjectDirs;
+use std::collections::{HashMap, VecDeque};
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::settings::ChartRange;
+
+/// Resolution in seconds and number of points kept for each tier: ten minutes at 1 s and a day
+/// at 1 min. A chart reads the finest tier that spans its whole range, so the 5 minute view uses
+/// the first tier and the hour and day views the second.
+const TIERS: [(u64, usize); 2] = [(1, 600), (60, 1440)];
+
+/// Seconds since the Unix epoch.
+pub fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// Fixed-size ring of `(timestamp, value)` records on disk.
+///
+/// Layout: magic `VTRH`, then capacity, next slot and length as little-endian `u32`, then
+/// `capacity` records of a `u64` timestamp and an `f32` value.
+struct RingFile {
+    file: File,
+    capacity: u32,
+    next: u32,
+    len: u32,
+}
+
+impl RingFile {
+    const MAGIC: &'static [u8; 4] = b"VTRH";
+    const HEADER: u64 = 16;
+    const RECORD: u64 = 12;
+
+    /// Opens (or creates) the ring at `path` and returns it with its records, oldest first. A
+    /// file with another capacity or a damaged header is started afresh.
+    fn open(path: &Path, capacity: usize) -> std::io::Result<(Self, Vec<(u64, f32)>)> {
+        let capacity = u32::try_from(capacity).unwrap_or(u32::MAX);
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)?;
+        let header = |at: usize| {
+            bytes
+                .get(at..at + 4)
+                .and_then(|b| b.try_into().ok())
+                .map(u32::from_le_bytes)
+        };
+        let valid = bytes.get(..4) == Some(Self::MAGIC.as_slice())
+            && header(4) == Some(capacity)
+            && header(8).is_some_and(|next| next < capacity)
+            && header(12).is_some_and(|len| len <= capacity);
+
+        let mut ring = Self {
+            file,
+            capacity,
+            next: 0,
+            len: 0,
+        };
+        if !valid {
+            ring.file.set_len(0)?;
+            ring.write_header()?;
+            return Ok((ring, Vec::new()));
+        }
+
+        ring.next = header(8).unwrap_or(0);
+        ring.len = header(12).unwrap_or(0);
+        let start = (ring.next + capacity - ring.len) % capacity;
+        let records = (0..ring.len)
+            .filter_map(|i| {
+                let slot = u64::from((start + i) % capacity);
+                let at = usize::try_from(Self::HEADER + slot * Self::RECORD).ok()?;
+                let record = bytes.get(at..at + 12)?;
+                let ts = u64::from_le_bytes(record[..8].try_into().ok()?);
+                let value = f32::from_le_bytes(record[8..].try_into().ok()?);
+                Some((ts, value))
+            })
+            .collect();
+        Ok((ring, records))
+    }
+
+    fn write_header(&mut self) -> std::io::Result<()> {
+        let mut header = Vec::with_capacity(16);
+        header.extend_from_slice(Self::MAGIC);
+        header.extend_from_slice(&self.capacity.to_le_bytes());
+        header.extend_from_slice(&self.next.to_le_bytes());
+        header.extend_from_slice(&self.len.to_le_bytes());
+        self.file.seek(SeekFrom::Start(0))?;
+        self.file.write_all(&header)
+    }
+
+    fn push(&mut self, ts: u64, value: f32) -> std::io::Result<()> {
+        self.write_record(self.next, ts, value)?;
+        self.next = (self.next + 1) % self.capacity;
+        self.len = (self.len + 1).min(self.capacity);
+        self.write_header()
+    }
+
+    /// Overwrites the newest record, or adds one to an empty ring.
+    fn replace_last(&mut self, ts: u64, value: f32) -> std::io::Result<()> {
+        if self.len == 0 {
+            return self.push(ts, value);
+        }
+        self.write_record((self.next + self.capacity - 1) % self.capacity, ts, value)
+    }
+
+    fn write_record(&mut self, slot: u32, ts: u64, value: f32) -> std::io::Result<()> {
+        let mut record = [0_u8; 12];
+        record[..8].copy_from_slice(&ts.to_le_bytes());
+        record[8..].copy_from_slice(&value.to_le_bytes());
+        let offset = Self::HEADER + u64::from(slot) * Self::RECORD;
+        self.file.seek(SeekFrom::Start(offset))?;
+        self.file.write_all(&record)
+    }
+}
+
+/// Samples being averaged into the current bucket of a tier.
+struct Bucket {
+    start: u64,
+    sum: f64,
+    count: u32,
+}
+
+impl Bucket {
+    #[allow(clippy::cast_possible_truncation)]
+    fn average(&self) -> f32 {
+        (self.sum / f64::from(self.count)) as f32
+    }
+}
+
+/// One resolution of a series: averaged points in memory, mirrored to a ring file.
+struct Tier {
+    resolution: u64,
+    capacity: usize,
+    points: VecDeque<(u64, f32)>,
+    pending: Option<Bucket>,
+    file: Option<RingFile>,
+}
+
+impl Tier {
+    fn open(dir: Option<&Path>, key: &str, resolution: u64, capacity: usize) -> Self {
+        let mut tier = Self {
+            resolution,
+            capacity,
+            points: VecDeque::with_capacity(capacity),
+            pending: None,
+            file: None,
+        };
+        let Some(dir) = dir else {
+            return tier;
+        };
+        let path = dir.join(format!("{key}.{resolution}s.ring"));
+        match RingFile::open(&path, capacity) {
+            Ok((file, records)) => {
+                tier.points.extend(records);
+                tier.file = Some(file);
+            }
+            Err(e) => eprintln!("Failed to open history file {}: {e}", path.display()),
+        }
+        tier
+    }
+
+    fn record(&mut self, ts: u64, value: f64) {
+        let start = ts - ts % self.resolution;
+        match &mut self.pending {
+            Some(bucket) if bucket.start == start => {
+                bucket.sum += value;
+                bucket.count += 1;
+                return;
+            }
+            _ => {}
+        }
+        if let Some(done) = self.pending.take() {
+            self.push(done.start, done.average());
+        }
+        // The bucket flushed when the app last quit may still be open; carry on averaging into
+        // it, its stored value counting as one sample, rather than storing its start twice.
+        let (sum, count) = match self.points.back() {
+            Some(&(last, average)) if last == start => (f64::from(average) + value, 2),
+            _ => (value, 1),
+        };
+        self.pending = Some(Bucket { start, sum, count });
+    }
+
+    /// Appends a finished bucket, replacing the stored one when it resumed that bucket.
+    fn push(&mut self, ts: u64, value: f32) {
+        let resumed = self.points.back().is_some_and(|(last, _)| *last == ts);
+        if resumed {
+            self.points.pop_back();
+        } else if self.points.len() == self.capacity {
+            self.points.pop_front();
+        }
+        self.points.push_back((ts, value));
+        if let Some(file) = &mut self.file {
+            let written = if resumed {
+                file.replace_last(ts, value)
+            } else {
+                file.push(ts, value)
+            };
+            if let Err(e) = written {
+                eprintln!("Failed to write history: {e}");
+                self.file = None;
+            }
+        }
+    }
+
+    /// Writes out the bucket still being filled, e.g. on shutdown. Samples recorded in the same
+    /// bucket afterwards resume it.
+    fn flush(&mut self) {
+        if let Some(done) = self.pending.take() {
+            self.push(done.start, done.average());
+        }
+    }
+
+    /// Points newer than `since`, including the bucket still being filled.
+    fn points_since(&self, since: u64) -> Vec<(u64, f64)> {
+        let pending = self.pending.as_ref().map(|b| (b.start, b.average()));
+        self.points
+            .iter()
+            .copied()
+            // A resumed bucket is stored too, until the pending one replaces it.
+            .filter(|(ts, _)| pending.is_none_or(|(start, _)| start != *ts))
+            .chain(pending)
+            .filter(|(ts, _)| *ts >= since)
+            .map(|(ts, value)| (ts, f64::from(value)))
+            .collect()
+    }
+}
+
+impl Drop for Tier {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+
+struct Series {
+    tiers: Vec<Tier>,
+}
+
+/// Per-metric history at 1 s and 1 min resolution, persisted under the data dir so
+/// charts survive restarts.
+pub struct HistoryStore {
+    dir: Option<PathBuf>,
+    series: HashMap<String, Series>,
+}
+
+impl HistoryStore {
+    /// Store under the project data directory, or in memory only when it cannot be created.
+    pub fn open_default() -> Self {
+        let dir = ProjectDirs::from("com", "moebius", "vitray-widget")
+            .map(|dirs| dirs.data_dir().join("history"));
+        Self::new(dir)
+    }
+
+    /// Store in `dir` (e.g. a scratch directory); `None` keeps history in memory only.
+    pub fn new(dir: Option<PathBuf>) -> Self {
+        let dir = dir.filter(|dir| match fs::create_dir_all(dir) {
+            Ok(()) => true,
+            Err(e) => {
+                eprintln!("History disabled on disk ({}): {e}", dir.display());
+                false
+            }
+        });
+        Self {
+            dir,
+            series: HashMap::new(),
+        }
+    }
+
+    pub fn record(&mut self, key: &str, ts: u64, value: f64) {
+        let dir = self.dir.as_deref();
+        let series = self
+            .series
+            .entry(key.to_string())
+            .or_insert_with(|| Series {
+                tiers: TIERS
+                    .iter()
+                    .map(|(resolution, capacity)| {
+                        Tier::open(dir, &file_key(key), *resolution, *capacity)
+                    })
+                    .collect(),
+            });
+        for tier in &mut series.tiers {
+            tier.record(ts, value);
+        }
+    }
+
+    /// Points covering `range` up to `now`, oldest first, from the tier matching the range.
+    pub fn points(&self, key: &str, range: ChartRange, now: u64) -> Vec<(u64, f64)> {
+        let tier = TIERS
+            .iter()
+            .position(|(resolution, capacity)| resolution * *capacity as u64 >= range.seconds())
+            .unwrap_or(TIERS.len() - 1);
+        self.series
+            .get(key)
+            .and_then(|series| series.tiers.get(tier))
+            .map(|tier| tier.points_since(now.saturating_sub(range.seconds())))
+            .unwrap_or_default()
+    }
+
+    /// Persists the partly filled buckets of every series, e.g. when the app quits.
+    pub fn flush(&mut self) {
+        for series in self.series.values_mut() {
+            series.tiers.iter_mut().for_each(Tier::flush);
+        }
+    }
+}
+
+/// Series key made safe for use as a file name.
+fn file_key(key: &str) -> String {
+    key.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn ring_round_trips_records() {
+        let dir = TempDir::new("ring-round-trip");
+        let path = dir.path().join("cpu.1s.ring");
+        let (mut ring, records) = RingFile::open(&path, 4).unwrap();
+        assert!(records.is_empty());
+        ring.push(100, 1.5).unwrap();
+        ring.push(101, 2.5).unwrap();
+        drop(ring);
+
+        let (_, records) = RingFile::open(&path, 4).unwrap();
+        assert_eq!(records, [(100, 1.5), (101, 2.5)]);
+        let size = fs::metadata(&path).unwrap().len();
+        assert_eq!(size, RingFile::HEADER + 2 * RingFile::RECORD);
+    }
+
+    #[test]
+    fn ring_wraps_around_keeping_the_newest() {
+        let dir = TempDir::new("ring-wrap");
+        let path = dir.path().join("cpu.1s.ring");
+        let (mut ring, _) = RingFile::open(&path, 3).unwrap();
+        for ts in 0..5_u16 {
+            ring.push(u64::from(ts), f32::from(ts)).unwrap();
+        }
+        drop(ring);
+
+        let (mut ring, records) = RingFile::open(&path, 3).unwrap();
+        assert_eq!(records, [(2, 2.0), (3, 3.0), (4, 4.0)]);
+        ring.push(5, 5.0).unwrap();
+        drop(ring);
+        let (_, records) = RingFile::open(&path, 3).unwrap();
+        assert_eq!(records, [(3, 3.0), (4, 4.0), (5, 5.0)]);
+    }
+
+    #[test]
+    fn ring_with_another_capacity_starts_afresh() {
+        let dir = TempDir::new("ring-capacity");
+        let path = dir.path().join("cpu.1s.ring");
+        let (mut ring, _) = RingFile::open(&path, 4).unwrap();
+        ring.push(1, 1.0).unwrap();
+        drop(ring);
+
+        let (mut ring, records) = RingFile::open(&path, 8).unwrap();
+        assert!(records.is_empty());
+        ring.push(2, 2.0).unwrap();
+        drop(ring);
+        let (_, records) = RingFile::open(&path, 8).unwrap();
+        assert_eq!(records, [(2, 2.0)]);
+    }
+
+    #[test]
+    fn ring_with_corrupt_header_starts_afresh() {
+        let dir = TempDir::new("ring-corrupt");
+        let garbage = dir.write("garbage.ring", "not a ring file at all");
+        let (_, records) = RingFile::open(&garbage, 4).unwrap();
+        assert!(records.is_empty());
+        assert_eq!(fs::metadata(&garbage).unwrap().len(), RingFile::HEADER);
+
+        // Right magic and capacity, but the next slot points past the end.
+        let mut header = RingFile::MAGIC.to_vec();
+        for field in [4_u32, 9, 1] {
+            header.extend_from_slice(&field.to_le_bytes());
+        }
+        let bad_slot = dir.path().join("bad-slot.ring");
+        fs::write(&bad_slot, header).unwrap();
+        let (_, records) = RingFile::open(&bad_slot, 4).unwrap();
+        assert!(records.is_empty());
+
+        let truncated = dir.write("truncated.ring", "VTR");
+        let (_, records) = RingFile::open(&truncated, 4).unwrap();
+        assert!(records.is_empty());
+    }
+
+    #[test]
+    fn ranges_read_the_tier_that_spans_them() {
+        let mut store = HistoryStore::new(None);
+        let now = 1_000_020_u64;
+        for ts in now - 7200..now {
+            store.record("cpu", ts, 1.0);
+        }
+        let five_minutes = store.points("cpu", ChartRange::FiveMinutes, now);
+        assert_eq!(five_minutes.len(), 300);
+        let hour = store.points("cpu", ChartRange::OneHour, now);
+        assert_eq!(hour.len(), 60);
+        assert!(hour.windows(2).all(|w| w[1].0 - w[0].0 == 60));
+        assert_eq!(store.points("cpu", ChartRange::Day, now).len(), 120);
+    }
+
+    #[test]
+    fn pending_buckets_are_flushed_on_drop() {
+        let dir = TempDir::new("history-flush");
+        let mut store = HistoryStore::new(Some(dir.path().to_path_buf()));
+        store.record("cpu", 120, 10.0);
+        store.record("cpu", 121, 30.0);
+        drop(store);
+
+        let (_, records) = RingFile::open(&dir.path().join("cpu.60s.ring"), 1440).unwrap();
+        assert_eq!(records, [(120, 20.0)]);
+        let (_, records) = RingFile::open(&dir.path().join("cpu.1s.ring"), 600).unwrap();
+        assert_eq!(records, [(120, 10.0), (121, 30.0)]);
+    }
+
+    #[test]
+    fn a_bucket_flushed_at_shutdown_is_resumed_after_restart() {
+        let dir = TempDir::new("history-resume");
+        let ring = dir.path().join("cpu.60s.ring");
+        let mut store = HistoryStore::new(Some(dir.path().to_path_buf()));
+        store.record("cpu", 60, 10.0);
+        store.record("cpu", 120, 20.0);
+        store.record("cpu", 121, 20.0);
+        drop(store);
+        let (_, records) = RingFile::open(&ring, 1440).unwrap();
+        assert_eq!(records, [(60, 10.0), (120, 20.0)]);
+
+        let mut store = HistoryStore::new(Some(dir.path().to_path_buf()));
+        store.record("cpu", 150, 40.0);
+        assert_eq!(
+            store.points("cpu", ChartRange::OneHour, 200),
+            [(60, 10.0), (120, 30.0)]
+        );
+        store.record("cpu", 180, 50.0);
+        drop(store);
+
+        let (_, records) = RingFile::open(&ring, 1440).unwrap();
+        assert_eq!(records, [(60, 10.0), (120, 30.0), (180, 50.0)]);
+    }
+}
@@ -0,0 +1,251 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// What a temperature sensor is attached to, derived from the hwmon chip name.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SensorKind {
+    Cpu,
+    Nvme,
+    Gpu,
+    Other,
+}
+
+impl SensorKind {
+    fn from_chip(chip: &str) -> Self {
+        match chip {
+            "coretemp" | "k10temp" | "zenpower" | "cpu_thermal" => Self::Cpu,
+            "nvme" => Self::Nvme,
+            "amdgpu" | "radeon" | "nouveau" | "i915" | "xe" => Self::Gpu,
+            _ => Self::Other,
+        }
+    }
+
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Cpu => "CPU",
+            Self::Nvme => "NVMe",
+            Self::Gpu => "GPU",
+            Self::Other => "Other",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TemperatureReading {
+    pub chip: String,
+    pub label: String,
+    pub kind: SensorKind,
+    pub celsius: f32,
+    /// Vendor "max" threshold, when the driver exposes one.
+    pub high: Option<f32>,
+    pub critical: Option<f32>,
+}
+
+impl TemperatureReading {
+    /// Whether this is the headline reading for its chip (CPU package, AMD `Tctl`, `NVMe` composite).
+    pub fn is_package(&self) -> bool {
+        let label = self.label.to_lowercase();
+        label.starts_with("package") || label == "tctl" || label == "tdie" || label == "composite"
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FanReading {
+    pub chip: String,
+    pub label: String,
+    pub rpm: u32,
+}
+
+/// Walks a hwmon class directory (normally `/sys/class/hwmon`) for temperature and fan inputs.
+pub struct HwmonReader {
+    root: PathBuf,
+}
+
+impl HwmonReader {
+    pub const DEFAULT_ROOT: &'static str = "/sys/class/hwmon";
+
+    /// Reads from `root` instead of the live sysfs tree, e.g. a fixture directory.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    pub fn read(&self) -> (Vec<TemperatureReading>, Vec<FanReading>) {
+        let mut temperatures = Vec::new();
+        let mut fans = Vec::new();
+
+        let Ok(entries) = fs::read_dir(&self.root) else {
+            return (temperatures, fans);
+        };
+        let mut chips: Vec<PathBuf> = entries.flatten().map(|e| e.path()).collect();
+        chips.sort();
+
+        for chip_dir in chips {
+            let chip = read_trimmed(&chip_dir.join("name")).unwrap_or_else(|| {
+                chip_dir
+                    .file_name()
+                    .map(|n| n.to_string_lossy().into_owned())
+                    .unwrap_or_default()
+            });
+            let kind = SensorKind::from_chip(&chip);
+
+            for index in channel_indices(&chip_dir, "temp") {
+                let prefix = chip_dir.join(format!("temp{index}"));
+                let Some(celsius) = read_millidegrees(&suffixed(&prefix, "_input")) else {
+                    continue;
+                };
+                temperatures.push(TemperatureReading {
+                    chip: chip.clone(),
+                    label: read_trimmed(&suffixed(&prefix, "_label"))
+                        .unwrap_or_else(|| format!("temp{index}")),
+                    kind,
+                    celsius,
+                    high: read_millidegrees(&suffixed(&prefix, "_max")),
+                    critical: read_millidegrees(&suffixed(&prefix, "_crit")),
+                });
+            }
+
+            for index in channel_indices(&chip_dir, "fan") {
+                let prefix = chip_dir.join(format!("fan{index}"));
+                let Some(rpm) = read_trimmed(&suffixed(&prefix, "_input"))
+                    .and_then(|v| v.parse::<u32>().ok())
+                else {
+                    continue;
+                };
+                fans.push(FanReading {
+                    chip: chip.clone(),
+                    label: read_trimmed(&suffixed(&prefix, "_label"))
+                        .unwrap_or_else(|| format!("fan{index}")),
+                    rpm,
+                });
+            }
+        }
+
+        (temperatures, fans)
+    }
+}
+
+impl Default for HwmonReader {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_ROOT)
+    }
+}
+
+/// Sorted channel numbers for which `<kind><N>_input` exists in `dir`.
+fn channel_indices(dir: &Path, kind: &str) -> Vec<u32> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut indices: Vec<u32> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            name.strip_prefix(kind)?
+                .strip_suffix("_input")?
+                .parse()
+                .ok()
+        })
+        .collect();
+    indices.sort_unstable();
+    indices
+}
+
+fn suffixed(prefix: &Path, suffix: &str) -> PathBuf {
+    let mut path = prefix.as_os_str().to_owned();
+    path.push(suffix);
+    PathBuf::from(path)
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path)
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+fn read_millidegrees(path: &Path) -> Option<f32> {
+    #[allow(clippy::cast_precision_loss)]
+    read_trimmed(path)?
+        .parse::<i64>()
+        .ok()
+        .map(|milli| milli as f32 / 1000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn reads_labelled_and_unlabelled_temperatures() {
+        let root = TempDir::new("hwmon-temp");
+        root.write("hwmon0/name", "coretemp\n");
+        root.write("hwmon0/temp1_input", "45000\n");
+        root.write("hwmon0/temp1_label", "Package id 0\n");
+        root.write("hwmon0/temp1_max", "80000\n");
+        root.write("hwmon0/temp1_crit", "100000\n");
+        root.write("hwmon0/temp2_input", "40500\n");
+
+        let (temperatures, fans) = HwmonReader::new(root.path()).read();
+        assert!(fans.is_empty());
+        assert_eq!(temperatures.len(), 2);
+
+        let package = &temperatures[0];
+        assert_eq!(package.chip, "coretemp");
+        assert_eq!(package.kind, SensorKind::Cpu);
+        assert_eq!(package.label, "Package id 0");
+        assert!(package.is_package());
+        assert!((package.celsius - 45.0).abs() < f32::EPSILON);
+        assert_eq!(package.high, Some(80.0));
+        assert_eq!(package.critical, Some(100.0));
+
+        let core = &temperatures[1];
+        assert_eq!(core.label, "temp2");
+        assert!(!core.is_package());
+        assert!((core.celsius - 40.5).abs() < f32::EPSILON);
+        assert_eq!(core.high, None);
+    }
+
+    #[test]
+    fn reads_fans_in_channel_order() {
+        let root = TempDir::new("hwmon-fan");
+        root.write("hwmon3/name", "nct6775");
+        root.write("hwmon3/fan10_input", "650");
+        root.write("hwmon3/fan2_input", "1200\n");
+        root.write("hwmon3/fan2_label", "CPU fan\n");
+
+        let (temperatures, fans) = HwmonReader::new(root.path()).read();
+        assert!(temperatures.is_empty());
+        let fans: Vec<_> = fans.iter().map(|f| (f.label.as_str(), f.rpm)).collect();
+        assert_eq!(fans, [("CPU fan", 1200), ("fan10", 650)]);
+    }
+
+    #[test]
+    fn missing_root_reads_nothing() {
+        let root = TempDir::new("hwmon-missing");
+        let (temperatures, fans) = HwmonReader::new(root.path().join("absent")).read();
+        assert!(temperatures.is_empty());
+        assert!(fans.is_empty());
+    }
+
+    #[test]
+    fn skips_garbage_and_falls_back_to_directory_name() {
+        let root = TempDir::new("hwmon-garbage");
+        root.write("hwmon1/temp1_input", "not a number\n");
+        root.write("hwmon1/temp2_input", "51000\n");
+        root.write("hwmon1/temp2_max", "n/a\n");
+        root.write("hwmon1/temp2_label", "   \n");
+        root.write("hwmon1/fan1_input", "-1\n");
+        root.write("hwmon1/fan1_label", "Chassis\n");
+        root.write("hwmon1/temp3_label", "no input file\n");
+
+        let (temperatures, fans) = HwmonReader::new(root.path()).read();
+        assert!(fans.is_empty());
+        assert_eq!(temperatures.len(), 1);
+        let reading = &temperatures[0];
+        assert_eq!(reading.chip, "hwmon1");
+        assert_eq!(reading.kind, SensorKind::Other);
+        assert_eq!(reading.label, "temp2");
+        assert_eq!(reading.high, None);
+    }
+}
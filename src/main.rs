@@ -3,20 +3,38 @@
 //! This crate provides a desktop widget with system monitoring, terminal, and shortcuts.
 
 use crate::shortcuts::Shortcuts;
+use crate::stats::StatsArgs;
 use crate::ui::build_ui;
-use clap::{ArgAction, Parser};
+use clap::{ArgAction, Parser, Subcommand};
 use gtk4::prelude::*;
 use gtk4::Application;
+use std::path::PathBuf;
 use std::process::Command;
 
+mod alerts;
+mod cgroups;
+mod cgroups_ui;
+mod chart;
+mod command_sensor;
+mod exporter;
 mod gpu;
+mod history;
+mod hwmon;
 mod monitor;
 mod platform;
+mod power;
+mod processes_ui;
+mod recorder;
+mod remote;
+mod session;
 mod settings;
 mod settings_ui;
 mod shortcuts;
 mod shortcuts_ui;
+mod stats;
 mod terminal;
+#[cfg(test)]
+mod test_support;
 mod ui;
 
 #[derive(Parser, Debug)]
@@ -25,7 +43,7 @@ mod ui;
     version,
     about = "Vitray widget: glassy terminal + performance HUD.",
     long_about = "Vitray widget: a glassy terminal with performance monitoring, shortcuts, and themes.",
-    after_help = "Examples:\n  vitray --shortcut \"htop\" \"Monitor\"\n  vitray --remove-shortcut \"Monitor\"\n  vitray --list-shortcuts\n  vitray deploy   # runs saved shortcut named 'deploy'"
+    after_help = "Examples:\n  vitray --shortcut \"htop\" \"Monitor\"\n  vitray --remove-shortcut \"Monitor\"\n  vitray --list-shortcuts\n  vitray deploy   # runs saved shortcut named 'deploy'\n  vitray stats --format ndjson --watch --interval 2000"
 )]
 struct Args {
     /// Add a new shortcut: vitray --shortcut "command" "name"
@@ -47,12 +65,29 @@ struct Args {
     /// Run a saved shortcut directly: vitray <name>
     #[arg(value_name = "SHORTCUT")]
     shortcut_name: Option<String>,
+
+    /// Record every sample to FILE while the widget runs (NDJSON for .ndjson/.jsonl, else CSV)
+    #[arg(long, value_name = "FILE")]
+    record: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Option<CliCommand>,
+}
+
+#[derive(Subcommand, Debug)]
+enum CliCommand {
+    /// Print system statistics without opening the widget
+    Stats(StatsArgs),
 }
 
 #[allow(clippy::print_stdout)]
 fn main() {
     let args = Args::parse();
 
+    if let Some(CliCommand::Stats(stats_args)) = &args.command {
+        std::process::exit(stats::run(stats_args));
+    }
+
     if let Some(shortcut_args) = args.shortcut {
         if shortcut_args.len() == 2 {
             let command = &shortcut_args[0];
@@ -118,9 +153,12 @@ fn main() {
     // TODO(senior-ui): Register a single-instance DBus name and raise the existing window when
     // invoked again instead of spawning duplicate widgets.
 
-    app.connect_activate(|app| {
-        build_ui(app);
+    let record = args.record;
+    app.connect_activate(move |app| {
+        build_ui(app, record.as_deref());
     });
 
-    app.run();
+    // Our flags were parsed by clap above; GApplication would reject them as unknown options.
+    let program: Vec<String> = std::env::args().take(1).collect();
+    app.run_with_args(&program);
 }
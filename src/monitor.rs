@@ -1,75 +1,912 @@
-use crate::gpu::GpuMonitor;
+use crate::cgroups::{CgroupReader, CgroupStats};
+use crate::command_sensor::{CommandReading, CommandSensor};
+use crate::exporter::MetricsExporter;
+use crate::gpu::{GpuMonitor, GpuStats};
+use crate::history::now_secs;
+use crate::hwmon::{FanReading, HwmonReader, SensorKind, TemperatureReading};
+use crate::power::{PowerStatus, PowerSupplyReader};
+use crate::recorder::{RecordTarget, Recorder};
+use crate::settings::{CommandSensorConfig, InterfaceFilter, Settings};
 
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 use std::thread;
-use std::time::Duration;
-use sysinfo::{Networks, System};
+use std::time::{Duration, Instant};
+use sysinfo::{Disks, Networks, Pid, Signal, System, Users};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CoreStats {
+    pub usage: f32,
+    pub frequency_mhz: u64,
+}
+
+/// Counters for a single network interface. Totals are cumulative since boot; rates are
+/// averaged over the network sensor's last period.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct InterfaceStats {
+    pub name: String,
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+    pub rx_bytes_per_sec: f64,
+    pub tx_bytes_per_sec: f64,
+    #[serde(default)]
+    pub rx_packets_per_sec: f64,
+    #[serde(default)]
+    pub tx_packets_per_sec: f64,
+    /// Receive and transmit errors together.
+    #[serde(default)]
+    pub errors_per_sec: f64,
+    pub rx_packets: u64,
+    pub tx_packets: u64,
+    pub rx_errors: u64,
+    pub tx_errors: u64,
+}
+
+/// Throughput of a whole block device, averaged over the last sampling period.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DiskIoStats {
+    pub device: String,
+    pub read_bytes_per_sec: f64,
+    pub write_bytes_per_sec: f64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MountStats {
+    pub mount_point: String,
+    pub device: String,
+    pub used: u64,
+    pub total: u64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub name: String,
+    pub user: String,
+    pub command: String,
+    /// Percentage of one core, so multi-threaded processes can exceed 100.
+    pub cpu_usage: f32,
+    pub memory: u64,
+}
+
+/// Signals the process panel can send.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProcessSignal {
+    Term,
+    Kill,
+}
+
+impl ProcessSignal {
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Term => "SIGTERM",
+            Self::Kill => "SIGKILL",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct LoadAverage {
+    pub one: f64,
+    pub five: f64,
+    pub fifteen: f64,
+}
+
+/// One merged sample. Missing fields default when deserializing, so a remote sampler only
+/// has to send the readings it has.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct MonitorData {
     pub cpu_usage: f32,
+    pub cores: Vec<CoreStats>,
     pub ram_used: u64,
     pub ram_total: u64,
-    pub rx_bytes: u64,
-    pub tx_bytes: u64,
-    pub gpu_usage: Option<f32>,
+    pub ram_available: u64,
+    /// Page cache plus reclaimable slab, as `free` reports it.
+    pub ram_cached: u64,
+    pub ram_buffers: u64,
+    pub swap_used: u64,
+    pub swap_total: u64,
+    pub load_average: LoadAverage,
+    pub uptime_secs: u64,
+    pub interfaces: Vec<InterfaceStats>,
+    pub disk_io: Vec<DiskIoStats>,
+    pub mounts: Vec<MountStats>,
+    pub temperatures: Vec<TemperatureReading>,
+    pub fans: Vec<FanReading>,
+    pub gpus: Vec<GpuStats>,
+    pub power: PowerStatus,
+    /// Latest result of each configured command sensor, in settings order.
+    pub commands: Vec<CommandReading>,
+    /// Union of the busiest processes by CPU and by memory, at most
+    /// [`TOP_PROCESSES`] of each.
+    pub processes: Vec<ProcessInfo>,
+    /// Containers and systemd units under the configured cgroup root.
+    pub cgroups: Vec<CgroupStats>,
+}
+
+/// How many processes per ranking (CPU, memory) each sample carries.
+pub const TOP_PROCESSES: usize = 25;
+
+/// Reads per-device sector counters from `/proc/diskstats` and turns them into rates.
+struct DiskIoReader {
+    last: HashMap<String, (u64, u64)>,
+    last_at: Instant,
+}
+
+impl DiskIoReader {
+    const SECTOR_BYTES: u64 = 512;
+
+    fn new() -> Self {
+        let mut reader = Self {
+            last: HashMap::new(),
+            last_at: Instant::now(),
+        };
+        reader.last = Self::read_counters();
+        reader
+    }
+
+    fn refresh(&mut self) -> Vec<DiskIoStats> {
+        let counters = Self::read_counters();
+        let elapsed = self.last_at.elapsed().as_secs_f64().max(0.001);
+        self.last_at = Instant::now();
+
+        let mut stats: Vec<DiskIoStats> = counters
+            .iter()
+            .map(|(device, (read, written))| {
+                let (prev_read, prev_written) =
+                    self.last.get(device).copied().unwrap_or((*read, *written));
+                #[allow(clippy::cast_precision_loss)]
+                DiskIoStats {
+                    device: device.clone(),
+                    read_bytes_per_sec: read.saturating_sub(prev_read) as f64 / elapsed,
+                    write_bytes_per_sec: written.saturating_sub(prev_written) as f64 / elapsed,
+                }
+            })
+            .collect();
+        stats.sort_by(|a, b| a.device.cmp(&b.device));
+
+        self.last = counters;
+        stats
+    }
+
+    /// Bytes read and written per whole device. Partitions, loop and ram devices are skipped
+    /// so throughput is not counted twice.
+    #[cfg(target_os = "linux")]
+    fn read_counters() -> HashMap<String, (u64, u64)> {
+        let Ok(content) = std::fs::read_to_string("/proc/diskstats") else {
+            return HashMap::new();
+        };
+
+        content
+            .lines()
+            .filter_map(|line| {
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                let name = *fields.get(2)?;
+                if name.starts_with("loop")
+                    || name.starts_with("ram")
+                    || !std::path::Path::new("/sys/block").join(name).exists()
+                {
+                    return None;
+                }
+                let sectors_read: u64 = fields.get(5)?.parse().ok()?;
+                let sectors_written: u64 = fields.get(9)?.parse().ok()?;
+                Some((
+                    name.to_string(),
+                    (
+                        sectors_read * Self::SECTOR_BYTES,
+                        sectors_written * Self::SECTOR_BYTES,
+                    ),
+                ))
+            })
+            .collect()
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn read_counters() -> HashMap<String, (u64, u64)> {
+        HashMap::new()
+    }
 }
 
-struct SystemMonitor {
+/// How often each group of readings is refreshed. Expensive sensors can be given a longer
+/// period than the base tick.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SamplingConfig {
+    pub cpu: Duration,
+    pub memory: Duration,
+    pub network: Duration,
+    pub disk: Duration,
+    pub thermals: Duration,
+    pub gpu: Duration,
+    pub processes: Duration,
+    pub power: Duration,
+    pub cgroups: Duration,
+}
+
+impl SamplingConfig {
+    /// Shortest period accepted, to keep a typo from pinning a core.
+    const MIN_INTERVAL_MS: u64 = 250;
+
+    pub fn from_settings(settings: &Settings) -> Self {
+        let base = settings.refresh_interval_ms;
+        let pick = |ms: Option<u64>| {
+            Duration::from_millis(ms.unwrap_or(base).max(Self::MIN_INTERVAL_MS))
+        };
+        let overrides = &settings.sensor_intervals;
+        Self {
+            cpu: pick(overrides.cpu_ms),
+            memory: pick(overrides.memory_ms),
+            network: pick(overrides.network_ms),
+            disk: pick(overrides.disk_ms),
+            thermals: pick(overrides.thermals_ms),
+            gpu: pick(overrides.gpu_ms),
+            processes: pick(overrides.processes_ms),
+            power: pick(overrides.power_ms),
+            cgroups: pick(overrides.cgroups_ms),
+        }
+    }
+
+    /// Every sensor on the same period, for one-shot and scripted sampling.
+    pub const fn uniform(period: Duration) -> Self {
+        Self {
+            cpu: period,
+            memory: period,
+            network: period,
+            disk: period,
+            thermals: period,
+            gpu: period,
+            processes: period,
+            power: period,
+            cgroups: period,
+        }
+    }
+}
+
+impl Default for SamplingConfig {
+    fn default() -> Self {
+        Self::from_settings(&Settings::default())
+    }
+}
+
+/// Messages the UI sends to the running monitoring thread.
+#[derive(Clone, Debug)]
+pub enum MonitorControl {
+    SetSampling(SamplingConfig),
+    SignalProcess { pid: u32, signal: ProcessSignal },
+    /// Turns process collection on or off; the UI only wants it while the panel is open.
+    SetProcessesEnabled(bool),
+    SetCommandSensors(Vec<CommandSensorConfig>),
+    /// Starts, moves or (with `None`) stops the metrics exporter.
+    SetMetricsPort(Option<u16>),
+    /// Starts appending every sample to a file, or stops with `None`.
+    SetRecording(Option<RecordTarget>),
+    /// Moves the cgroup sensor to another v2 hierarchy; see [`SystemMonitor::set_cgroup_root`].
+    SetCgroupRoot(PathBuf),
+    /// Turns the cgroup walk on or off, like [`Self::SetProcessesEnabled`].
+    SetCgroupsEnabled(bool),
+}
+
+/// Handle to the monitoring thread: samples come out of `receiver`, configuration changes go in
+/// through `control`.
+pub struct MonitorService {
+    pub receiver: async_channel::Receiver<MonitorData>,
+    pub control: async_channel::Sender<MonitorControl>,
+}
+
+/// One source of readings polled by the monitoring thread. A sensor owns whatever state it
+/// needs between samples and writes its results into the shared snapshot.
+pub trait Sensor: Send {
+    /// Unique key the scheduler tracks the sensor's last run under.
+    fn id(&self) -> &str;
+
+    fn interval(&self, sampling: &SamplingConfig) -> Duration;
+
+    /// Takes a reading. `elapsed` is the time since this sensor last ran, `None` on the first
+    /// sample.
+    fn sample(&mut self, data: &mut MonitorData, elapsed: Option<Duration>);
+}
+
+struct CpuSensor {
     sys: System,
-    networks: Networks,
 }
 
-impl SystemMonitor {
+impl CpuSensor {
     fn new() -> Self {
-        let mut sys = System::new_all();
-        sys.refresh_all();
-        let networks = Networks::new_with_refreshed_list();
-        Self { sys, networks }
+        let mut sys = System::new();
+        sys.refresh_cpu();
+        Self { sys }
+    }
+}
+
+impl Sensor for CpuSensor {
+    fn id(&self) -> &'static str {
+        "cpu"
+    }
+
+    fn interval(&self, sampling: &SamplingConfig) -> Duration {
+        sampling.cpu
     }
 
-    fn refresh(&mut self) -> MonitorData {
+    fn sample(&mut self, data: &mut MonitorData, _elapsed: Option<Duration>) {
         self.sys.refresh_cpu();
+        data.cpu_usage = self.sys.global_cpu_info().cpu_usage();
+        data.cores = self
+            .sys
+            .cpus()
+            .iter()
+            .map(|cpu| CoreStats {
+                usage: cpu.cpu_usage(),
+                frequency_mhz: cpu.frequency(),
+            })
+            .collect();
+    }
+}
+
+/// RAM and swap. Load and uptime are cheap and change on the same scale, so they ride along.
+struct MemorySensor {
+    sys: System,
+}
+
+impl Sensor for MemorySensor {
+    fn id(&self) -> &'static str {
+        "memory"
+    }
+
+    fn interval(&self, sampling: &SamplingConfig) -> Duration {
+        sampling.memory
+    }
+
+    fn sample(&mut self, data: &mut MonitorData, _elapsed: Option<Duration>) {
         self.sys.refresh_memory();
+        data.ram_used = self.sys.used_memory();
+        data.ram_total = self.sys.total_memory();
+        data.ram_available = self.sys.available_memory();
+        data.swap_used = self.sys.used_swap();
+        data.swap_total = self.sys.total_swap();
+        let (cached, buffers) = read_page_cache();
+        data.ram_cached = cached;
+        data.ram_buffers = buffers;
+
+        let load = System::load_average();
+        data.load_average = LoadAverage {
+            one: load.one,
+            five: load.five,
+            fifteen: load.fifteen,
+        };
+        data.uptime_secs = System::uptime();
+    }
+}
+
+struct NetworkSensor {
+    networks: Networks,
+}
+
+impl Sensor for NetworkSensor {
+    fn id(&self) -> &'static str {
+        "network"
+    }
+
+    fn interval(&self, sampling: &SamplingConfig) -> Duration {
+        sampling.network
+    }
+
+    fn sample(&mut self, data: &mut MonitorData, elapsed: Option<Duration>) {
         self.networks.refresh();
+        let secs = elapsed.map_or(0.0, |e| e.as_secs_f64().max(0.001));
+        #[allow(clippy::cast_precision_loss)]
+        let rate = |delta: u64| if secs > 0.0 { delta as f64 / secs } else { 0.0 };
+        let mut interfaces: Vec<InterfaceStats> = self
+            .networks
+            .iter()
+            .map(|(name, stats)| InterfaceStats {
+                name: name.clone(),
+                rx_bytes: stats.total_received(),
+                tx_bytes: stats.total_transmitted(),
+                rx_bytes_per_sec: rate(stats.received()),
+                tx_bytes_per_sec: rate(stats.transmitted()),
+                rx_packets_per_sec: rate(stats.packets_received()),
+                tx_packets_per_sec: rate(stats.packets_transmitted()),
+                errors_per_sec: rate(stats.errors_on_received() + stats.errors_on_transmitted()),
+                rx_packets: stats.total_packets_received(),
+                tx_packets: stats.total_packets_transmitted(),
+                rx_errors: stats.total_errors_on_received(),
+                tx_errors: stats.total_errors_on_transmitted(),
+            })
+            .collect();
+        interfaces.sort_by(|a, b| a.name.cmp(&b.name));
+        data.interfaces = interfaces;
+    }
+}
+
+struct DiskSensor {
+    disks: Disks,
+    io: DiskIoReader,
+}
 
-        let cpu_usage = self.sys.global_cpu_info().cpu_usage();
-        let (ram_used, ram_total) = (self.sys.used_memory(), self.sys.total_memory());
+impl Sensor for DiskSensor {
+    fn id(&self) -> &'static str {
+        "disk"
+    }
+
+    fn interval(&self, sampling: &SamplingConfig) -> Duration {
+        sampling.disk
+    }
+
+    fn sample(&mut self, data: &mut MonitorData, _elapsed: Option<Duration>) {
+        // Re-list so mounts that come and go (USB sticks, docker volumes) are picked up.
+        self.disks.refresh_list();
+        data.mounts = self
+            .disks
+            .list()
+            .iter()
+            .map(|disk| MountStats {
+                mount_point: disk.mount_point().to_string_lossy().into_owned(),
+                device: disk.name().to_string_lossy().into_owned(),
+                used: disk.total_space().saturating_sub(disk.available_space()),
+                total: disk.total_space(),
+            })
+            .collect();
+        data.disk_io = self.io.refresh();
+    }
+}
 
-        let mut rx_bytes = 0;
-        let mut tx_bytes = 0;
-        for (_interface_name, data) in &self.networks {
-            rx_bytes += data.received();
-            tx_bytes += data.transmitted();
+impl Sensor for HwmonReader {
+    fn id(&self) -> &'static str {
+        "thermals"
+    }
+
+    fn interval(&self, sampling: &SamplingConfig) -> Duration {
+        sampling.thermals
+    }
+
+    fn sample(&mut self, data: &mut MonitorData, _elapsed: Option<Duration>) {
+        let (temperatures, fans) = self.read();
+        data.temperatures = temperatures;
+        data.fans = fans;
+    }
+}
+
+impl Sensor for GpuMonitor {
+    fn id(&self) -> &'static str {
+        "gpu"
+    }
+
+    fn interval(&self, sampling: &SamplingConfig) -> Duration {
+        sampling.gpu
+    }
+
+    fn sample(&mut self, data: &mut MonitorData, _elapsed: Option<Duration>) {
+        data.gpus = self.poll();
+    }
+}
+
+impl Sensor for PowerSupplyReader {
+    fn id(&self) -> &'static str {
+        "power"
+    }
+
+    fn interval(&self, sampling: &SamplingConfig) -> Duration {
+        sampling.power
+    }
+
+    fn sample(&mut self, data: &mut MonitorData, _elapsed: Option<Duration>) {
+        data.power = self.read();
+    }
+}
+
+impl Sensor for CgroupReader {
+    fn id(&self) -> &'static str {
+        "cgroups"
+    }
+
+    fn interval(&self, sampling: &SamplingConfig) -> Duration {
+        sampling.cgroups
+    }
+
+    fn sample(&mut self, data: &mut MonitorData, elapsed: Option<Duration>) {
+        data.cgroups = self.read(elapsed);
+    }
+}
+
+struct ProcessSensor {
+    sys: System,
+    users: Users,
+}
+
+impl ProcessSensor {
+    fn new() -> Self {
+        let mut sys = System::new();
+        sys.refresh_processes();
+        Self {
+            sys,
+            users: Users::new_with_refreshed_list(),
+        }
+    }
+
+    fn top_processes(&self) -> Vec<ProcessInfo> {
+        let mut all: Vec<_> = self.sys.processes().values().collect();
+
+        all.sort_by(|a, b| b.cpu_usage().total_cmp(&a.cpu_usage()));
+        let mut picked: Vec<Pid> = all.iter().take(TOP_PROCESSES).map(|p| p.pid()).collect();
+        all.sort_by_key(|p| std::cmp::Reverse(p.memory()));
+        for process in all.iter().take(TOP_PROCESSES) {
+            if !picked.contains(&process.pid()) {
+                picked.push(process.pid());
+            }
+        }
+
+        picked
+            .iter()
+            .filter_map(|pid| self.sys.process(*pid))
+            .map(|process| ProcessInfo {
+                pid: process.pid().as_u32(),
+                name: process.name().to_string(),
+                user: process
+                    .user_id()
+                    .and_then(|uid| self.users.get_user_by_id(uid))
+                    .map(|user| user.name().to_string())
+                    .unwrap_or_default(),
+                command: process.cmd().join(" "),
+                cpu_usage: process.cpu_usage(),
+                memory: process.memory(),
+            })
+            .collect()
+    }
+}
+
+impl Sensor for ProcessSensor {
+    fn id(&self) -> &'static str {
+        "processes"
+    }
+
+    fn interval(&self, sampling: &SamplingConfig) -> Duration {
+        sampling.processes
+    }
+
+    fn sample(&mut self, data: &mut MonitorData, _elapsed: Option<Duration>) {
+        self.sys.refresh_processes();
+        data.processes = self.top_processes();
+    }
+}
+
+/// Schedules the built-in sensors plus the user's command sensors on one thread.
+pub struct SystemMonitor {
+    builtin: Vec<Box<dyn Sensor>>,
+    /// Replaced wholesale whenever the configured command sensors change.
+    user: Vec<Box<dyn Sensor>>,
+    sampling: SamplingConfig,
+    last_run: HashMap<String, Instant>,
+    /// Sensors that are skipped until resumed.
+    paused: HashSet<String>,
+    data: MonitorData,
+    cgroup_root: PathBuf,
+}
+
+impl SystemMonitor {
+    pub fn new(sampling: SamplingConfig, commands: &[CommandSensorConfig]) -> Self {
+        let builtin: Vec<Box<dyn Sensor>> = vec![
+            Box::new(CpuSensor::new()),
+            Box::new(MemorySensor { sys: System::new() }),
+            Box::new(NetworkSensor {
+                networks: Networks::new_with_refreshed_list(),
+            }),
+            Box::new(DiskSensor {
+                disks: Disks::new_with_refreshed_list(),
+                io: DiskIoReader::new(),
+            }),
+            Box::new(HwmonReader::default()),
+            Box::new(GpuMonitor::new()),
+            Box::new(ProcessSensor::new()),
+            Box::new(PowerSupplyReader::default()),
+            Box::new(CgroupReader::default()),
+        ];
+        let mut monitor = Self {
+            builtin,
+            user: Vec::new(),
+            sampling,
+            last_run: HashMap::new(),
+            paused: HashSet::new(),
+            data: MonitorData::default(),
+            cgroup_root: PathBuf::from(CgroupReader::DEFAULT_ROOT),
+        };
+        monitor.set_command_sensors(commands);
+        monitor
+    }
+
+    fn set_command_sensors(&mut self, commands: &[CommandSensorConfig]) {
+        self.user = commands
+            .iter()
+            .map(|config| Box::new(CommandSensor::new(config.clone())) as Box<dyn Sensor>)
+            .collect();
+        // Restart the schedule for command sensors so an edited script runs straight away.
+        self.last_run.retain(|id, _| !id.starts_with(CommandSensor::ID_PREFIX));
+        self.data
+            .commands
+            .retain(|reading| commands.iter().any(|c| c.name == reading.name));
+    }
+
+    /// Points the cgroup sensor at another hierarchy, e.g. a container host's mount.
+    pub fn set_cgroup_root(&mut self, root: PathBuf) {
+        if root == self.cgroup_root {
+            return;
         }
+        let reader = CgroupReader::new(root.clone());
+        let Some(slot) = self.builtin.iter_mut().find(|s| s.id() == reader.id()) else {
+            return;
+        };
+        *slot = Box::new(reader);
+        self.cgroup_root = root;
+        self.last_run.remove("cgroups");
+        self.data.cgroups.clear();
+    }
 
-        let gpu_usage = GpuMonitor::get_usage();
+    /// Starts or stops walking the process table, the most expensive sample we take.
+    pub fn set_processes_enabled(&mut self, enabled: bool) {
+        self.set_paused("processes", !enabled);
+        if !enabled {
+            self.data.processes.clear();
+        }
+    }
+
+    /// Starts or stops walking the cgroup hierarchy for the containers and units panel.
+    pub fn set_cgroups_enabled(&mut self, enabled: bool) {
+        self.set_paused("cgroups", !enabled);
+        if !enabled {
+            self.data.cgroups.clear();
+        }
+    }
+
+    /// A paused sensor is not sampled at all; once resumed it runs on the next refresh.
+    fn set_paused(&mut self, id: &str, paused: bool) {
+        if paused {
+            self.paused.insert(id.to_string());
+            self.last_run.remove(id);
+        } else {
+            self.paused.remove(id);
+        }
+    }
 
-        MonitorData {
-            cpu_usage,
-            ram_used,
-            ram_total,
-            rx_bytes,
-            tx_bytes,
-            gpu_usage,
+    /// Refreshes every sensor whose period has elapsed and returns the merged snapshot; readings
+    /// that were not due keep their previous values.
+    pub fn refresh(&mut self) -> MonitorData {
+        let now = Instant::now();
+        for sensor in self.builtin.iter_mut().chain(self.user.iter_mut()) {
+            if self.paused.contains(sensor.id()) {
+                continue;
+            }
+            let due = self.last_run.get(sensor.id()).is_none_or(|last| {
+                now.duration_since(*last) >= sensor.interval(&self.sampling)
+            });
+            if !due {
+                continue;
+            }
+            let elapsed = self
+                .last_run
+                .insert(sensor.id().to_string(), now)
+                .map(|last| now.duration_since(last));
+            sensor.sample(&mut self.data, elapsed);
         }
+        self.data.clone()
+    }
+
+    /// The service wakes at the fastest sensor period and refreshes whatever is due.
+    fn tick(&self) -> Duration {
+        self.builtin
+            .iter()
+            .chain(self.user.iter())
+            .filter(|sensor| !self.paused.contains(sensor.id()))
+            .map(|sensor| sensor.interval(&self.sampling))
+            .min()
+            .unwrap_or(Duration::from_secs(1))
     }
 }
 
-pub fn start_monitoring_service() -> async_channel::Receiver<MonitorData> {
+fn signal_process(pid: u32, signal: ProcessSignal) {
+    let pid = Pid::from_u32(pid);
+    let mut sys = System::new();
+    sys.refresh_process(pid);
+    let sent = sys.process(pid).and_then(|process| {
+        process.kill_with(match signal {
+            ProcessSignal::Term => Signal::Term,
+            ProcessSignal::Kill => Signal::Kill,
+        })
+    });
+    if sent != Some(true) {
+        eprintln!("Failed to send {} to process {}", signal.label(), pid.as_u32());
+    }
+}
+
+impl MonitorData {
+    /// Average clock across all cores in MHz, or `None` when the platform reports no frequency.
+    pub fn average_frequency_mhz(&self) -> Option<u64> {
+        let reporting: Vec<u64> = self
+            .cores
+            .iter()
+            .map(|c| c.frequency_mhz)
+            .filter(|f| *f > 0)
+            .collect();
+        if reporting.is_empty() {
+            return None;
+        }
+        Some(reporting.iter().sum::<u64>() / reporting.len() as u64)
+    }
+
+    /// Utilisation of the busiest adapter, if any adapter reports one.
+    pub fn gpu_usage(&self) -> Option<f32> {
+        self.gpus
+            .iter()
+            .filter_map(|g| g.busy_percent)
+            .max_by(f32::total_cmp)
+    }
+
+    /// Headline temperature for a class of sensor: the package reading when the chip has
+    /// one, otherwise the hottest input of that kind.
+    pub fn temperature_of(&self, kind: SensorKind) -> Option<&TemperatureReading> {
+        let of_kind = self.temperatures.iter().filter(|t| t.kind == kind);
+        of_kind
+            .clone()
+            .find(|t| t.is_package())
+            .or_else(|| of_kind.max_by(|a, b| a.celsius.total_cmp(&b.celsius)))
+    }
+
+    /// Mounts feeding the Disk card. An empty selection means every mount.
+    pub fn selected_mounts<'a>(
+        &'a self,
+        selection: &'a [String],
+    ) -> impl Iterator<Item = &'a MountStats> + 'a {
+        self.mounts
+            .iter()
+            .filter(move |m| selection.is_empty() || selection.contains(&m.mount_point))
+    }
+
+    /// Interfaces feeding the Network card: the selected one when set, otherwise every
+    /// interface the filter lets through.
+    pub fn network_interfaces<'a>(
+        &'a self,
+        filter: &'a InterfaceFilter,
+        selected: Option<&'a str>,
+    ) -> impl Iterator<Item = &'a InterfaceStats> + 'a {
+        self.interfaces.iter().filter(move |iface| {
+            selected.map_or_else(|| filter.matches(&iface.name), |name| iface.name == name)
+        })
+    }
+}
+
+/// Byte rate for display, in KB/s or, above 1 MB/s, MB/s.
+pub fn format_rate(bytes_per_sec: f64) -> String {
+    let kb = bytes_per_sec / 1024.0;
+    if kb > 1024.0 {
+        format!("{:.1} MB/s", kb / 1024.0)
+    } else {
+        format!("{kb:.0} KB/s")
+    }
+}
+
+/// Cached and buffer memory in bytes from `/proc/meminfo`; sysinfo only reports totals.
+#[cfg(target_os = "linux")]
+fn read_page_cache() -> (u64, u64) {
+    let Ok(content) = std::fs::read_to_string("/proc/meminfo") else {
+        return (0, 0);
+    };
+    let field = |key: &str| -> u64 {
+        content
+            .lines()
+            .find_map(|line| line.strip_prefix(key)?.strip_prefix(':'))
+            .and_then(|rest| rest.split_whitespace().next()?.parse::<u64>().ok())
+            .map_or(0, |kib| kib * 1024)
+    };
+    (field("Cached") + field("SReclaimable"), field("Buffers"))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_page_cache() -> (u64, u64) {
+    (0, 0)
+}
+
+pub fn start_monitoring_service(
+    sampling: SamplingConfig,
+    commands: Vec<CommandSensorConfig>,
+    metrics_port: Option<u16>,
+) -> MonitorService {
     let (sender, receiver) = async_channel::unbounded();
+    let (control, control_rx) = async_channel::unbounded::<MonitorControl>();
 
     thread::spawn(move || {
         smol::block_on(async {
-            let mut monitor = SystemMonitor::new();
+            let mut monitor = SystemMonitor::new(sampling, &commands);
+            let mut exporter = MetricsExporter::new(metrics_port);
+            let mut recorder: Option<Recorder> = None;
             loop {
                 let data = monitor.refresh();
+                exporter.publish(&data);
+                if let Some(active) = &mut recorder {
+                    if let Err(e) = active.append(now_secs(), &data) {
+                        eprintln!("Recording to {} stopped: {e}", active.path().display());
+                        recorder = None;
+                    }
+                }
                 if sender.send(data).await.is_err() {
                     break; // Channel closed
                 }
-                smol::Timer::after(Duration::from_secs(1)).await;
+
+                // Sleep until the next tick. Configuration changes are applied as they arrive
+                // but do not end the wait, so a burst of them costs no extra samples.
+                let sampled_at = Instant::now();
+                while let Some(message) = smol::future::or(
+                    async {
+                        smol::Timer::at(sampled_at + monitor.tick()).await;
+                        None
+                    },
+                    async {
+                        // With every sender gone only the timer can end the wait; returning
+                        // at once would resample in a busy loop.
+                        match control_rx.recv().await {
+                            Ok(message) => Some(message),
+                            Err(_) => smol::future::pending().await,
+                        }
+                    },
+                )
+                .await
+                {
+                    match message {
+                        MonitorControl::SetSampling(config) => monitor.sampling = config,
+                        MonitorControl::SignalProcess { pid, signal } => {
+                            signal_process(pid, signal);
+                        }
+                        MonitorControl::SetProcessesEnabled(enabled) => {
+                            monitor.set_processes_enabled(enabled);
+                        }
+                        MonitorControl::SetCommandSensors(commands) => {
+                            monitor.set_command_sensors(&commands);
+                        }
+                        MonitorControl::SetMetricsPort(port) => exporter.set_port(port),
+                        MonitorControl::SetCgroupRoot(root) => monitor.set_cgroup_root(root),
+                        MonitorControl::SetCgroupsEnabled(enabled) => {
+                            monitor.set_cgroups_enabled(enabled);
+                        }
+                        MonitorControl::SetRecording(target) => {
+                            recorder = target.and_then(|target| {
+                                let path = target.path.clone();
+                                Recorder::open(target)
+                                    .map_err(|e| {
+                                        eprintln!("Failed to record to {}: {e}", path.display());
+                                    })
+                                    .ok()
+                            });
+                        }
+                    }
+                }
             }
         });
     });
 
-    receiver
+    MonitorService { receiver, control }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_its_pace_after_the_control_senders_are_dropped() {
+        let settings = Settings {
+            refresh_interval_ms: 500,
+            ..Settings::default()
+        };
+        let service =
+            start_monitoring_service(SamplingConfig::from_settings(&settings), Vec::new(), None);
+        drop(service.control);
+
+        let started = Instant::now();
+        let mut samples = 0;
+        while started.elapsed() < Duration::from_millis(1200) {
+            if service.receiver.try_recv().is_ok() {
+                samples += 1;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        // One sample straight away and one per 500 ms tick, not one per loop turn.
+        assert!((1..=4).contains(&samples), "{samples} samples");
+    }
 }
@@ -51,6 +51,22 @@ pub fn get_default_shell() -> String {
     "bash".to_string()
 }
 
+/// Command that runs `script` through the platform shell.
+#[cfg(target_os = "windows")]
+pub fn script_command(script: &str) -> Command {
+    let mut command = Command::new("cmd");
+    command.args(["/C", script]);
+    command
+}
+
+/// Command that runs `script` through the platform shell.
+#[cfg(not(target_os = "windows"))]
+pub fn script_command(script: &str) -> Command {
+    let mut command = Command::new("sh");
+    command.args(["-c", script]);
+    command
+}
+
 pub fn open_external_terminal(command: Option<&str>) {
     #[cfg(target_os = "windows")]
     {
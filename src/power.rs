@@ -0,0 +1,283 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChargeState {
+    Charging,
+    Discharging,
+    Full,
+    NotCharging,
+    #[default]
+    Unknown,
+}
+
+impl ChargeState {
+    pub const ALL: [Self; 5] = [
+        Self::Charging,
+        Self::Discharging,
+        Self::Full,
+        Self::NotCharging,
+        Self::Unknown,
+    ];
+
+    fn parse(status: &str) -> Self {
+        match status {
+            "Charging" => Self::Charging,
+            "Discharging" => Self::Discharging,
+            "Full" => Self::Full,
+            "Not charging" => Self::NotCharging,
+            _ => Self::Unknown,
+        }
+    }
+
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Charging => "Charging",
+            Self::Discharging => "Discharging",
+            Self::Full => "Full",
+            Self::NotCharging => "Not charging",
+            Self::Unknown => "Unknown",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BatteryStats {
+    pub name: String,
+    pub capacity_percent: f32,
+    pub state: ChargeState,
+    /// Current draw (or charge rate) in watts, when the driver reports it.
+    pub power_watts: Option<f32>,
+    pub time_to_empty_secs: Option<u64>,
+    pub time_to_full_secs: Option<u64>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct PowerStatus {
+    /// `None` when the machine exposes neither a mains supply nor a battery.
+    pub on_ac: Option<bool>,
+    pub batteries: Vec<BatteryStats>,
+}
+
+/// Reads system batteries and AC adapters from a power supply class directory (normally
+/// `/sys/class/power_supply`).
+pub struct PowerSupplyReader {
+    root: PathBuf,
+}
+
+impl PowerSupplyReader {
+    pub const DEFAULT_ROOT: &'static str = "/sys/class/power_supply";
+
+    /// Reads from `root` instead of the live sysfs tree, e.g. a fixture directory.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    pub fn read(&self) -> PowerStatus {
+        let mut status = PowerStatus::default();
+        let Ok(entries) = fs::read_dir(&self.root) else {
+            return status;
+        };
+        let mut supplies: Vec<PathBuf> = entries.flatten().map(|e| e.path()).collect();
+        supplies.sort();
+
+        let mut mains_online: Option<bool> = None;
+        for dir in supplies {
+            // Mice, keyboards and headsets report their own batteries with a "Device" scope.
+            if read_string(&dir.join("scope")).as_deref() == Some("Device") {
+                continue;
+            }
+            match read_string(&dir.join("type")).as_deref() {
+                Some("Mains") => {
+                    let online = read_number(&dir.join("online")).is_some_and(|v| v > 0.0);
+                    mains_online = Some(mains_online.unwrap_or(false) || online);
+                }
+                Some("Battery") => {
+                    if read_number(&dir.join("present")) == Some(0.0) {
+                        continue;
+                    }
+                    if let Some(battery) = Self::read_battery(&dir) {
+                        status.batteries.push(battery);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        status.on_ac = mains_online.or_else(|| {
+            let first = status.batteries.first()?;
+            Some(first.state != ChargeState::Discharging)
+        });
+        status
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn read_battery(dir: &Path) -> Option<BatteryStats> {
+        let name = dir.file_name()?.to_string_lossy().into_owned();
+        let state = read_string(&dir.join("status")).map_or(ChargeState::Unknown, |s| {
+            ChargeState::parse(&s)
+        });
+        let value = |file: &str| read_number(&dir.join(file));
+
+        // Drivers report either energy (µWh, µW) or charge (µAh, µA); both give hours as now/rate.
+        let (now, full, rate_micro, power_watts) = value("energy_now").map_or_else(
+            || {
+                let current = value("current_now").map(f64::abs);
+                let watts = current
+                    .zip(value("voltage_now"))
+                    .map(|(amps, volts)| amps * volts / 1e12);
+                (value("charge_now"), value("charge_full"), current, watts)
+            },
+            |energy_now| {
+                let power = value("power_now").map(f64::abs);
+                (Some(energy_now), value("energy_full"), power, power.map(|p| p / 1e6))
+            },
+        );
+
+        let capacity = value("capacity").or_else(|| {
+            let (now, full) = (now?, full?);
+            (full > 0.0).then(|| now / full * 100.0)
+        })?;
+
+        let hours_to_secs = |hours: f64| (hours * 3600.0) as u64;
+        let rate = rate_micro.filter(|r| *r > 0.0);
+        let time_to_empty_secs = match (state, now, rate) {
+            (ChargeState::Discharging, Some(now), Some(rate)) => Some(hours_to_secs(now / rate)),
+            _ => None,
+        };
+        let time_to_full_secs = match (state, now, full, rate) {
+            (ChargeState::Charging, Some(now), Some(full), Some(rate)) => {
+                Some(hours_to_secs((full - now).max(0.0) / rate))
+            }
+            _ => None,
+        };
+
+        Some(BatteryStats {
+            name,
+            capacity_percent: capacity as f32,
+            state,
+            power_watts: power_watts.map(|w| w as f32),
+            time_to_empty_secs,
+            time_to_full_secs,
+        })
+    }
+}
+
+impl Default for PowerSupplyReader {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_ROOT)
+    }
+}
+
+fn read_string(path: &Path) -> Option<String> {
+    fs::read_to_string(path)
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+fn read_number(path: &Path) -> Option<f64> {
+    read_string(path)?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn discharging_energy_battery_on_battery_power() {
+        let root = TempDir::new("power-discharging");
+        root.write("AC/type", "Mains\n");
+        root.write("AC/online", "0\n");
+        root.write("BAT0/type", "Battery\n");
+        root.write("BAT0/status", "Discharging\n");
+        root.write("BAT0/capacity", "50\n");
+        root.write("BAT0/energy_now", "25000000\n");
+        root.write("BAT0/energy_full", "50000000\n");
+        root.write("BAT0/power_now", "10000000\n");
+
+        let status = PowerSupplyReader::new(root.path()).read();
+        assert_eq!(status.on_ac, Some(false));
+        let battery = &status.batteries[0];
+        assert_eq!(battery.name, "BAT0");
+        assert_eq!(battery.state, ChargeState::Discharging);
+        assert!((battery.capacity_percent - 50.0).abs() < f32::EPSILON);
+        assert_eq!(battery.power_watts, Some(10.0));
+        assert_eq!(battery.time_to_empty_secs, Some(9000));
+        assert_eq!(battery.time_to_full_secs, None);
+    }
+
+    #[test]
+    fn charging_charge_battery_on_ac() {
+        let root = TempDir::new("power-charging");
+        root.write("ADP1/type", "Mains\n");
+        root.write("ADP1/online", "1\n");
+        root.write("BAT1/type", "Battery\n");
+        root.write("BAT1/status", "Charging\n");
+        root.write("BAT1/charge_now", "1000000\n");
+        root.write("BAT1/charge_full", "4000000\n");
+        root.write("BAT1/current_now", "-2000000\n");
+        root.write("BAT1/voltage_now", "12000000\n");
+
+        let status = PowerSupplyReader::new(root.path()).read();
+        assert_eq!(status.on_ac, Some(true));
+        let battery = &status.batteries[0];
+        assert_eq!(battery.state, ChargeState::Charging);
+        // No `capacity` file: derived from charge_now / charge_full.
+        assert!((battery.capacity_percent - 25.0).abs() < f32::EPSILON);
+        assert_eq!(battery.power_watts, Some(24.0));
+        assert_eq!(battery.time_to_full_secs, Some(5400));
+        assert_eq!(battery.time_to_empty_secs, None);
+    }
+
+    #[test]
+    fn full_battery_without_mains_counts_as_ac() {
+        let root = TempDir::new("power-full");
+        root.write("BAT0/type", "Battery\n");
+        root.write("BAT0/status", "Full\n");
+        root.write("BAT0/capacity", "100\n");
+
+        let status = PowerSupplyReader::new(root.path()).read();
+        assert_eq!(status.on_ac, Some(true));
+        assert_eq!(status.batteries[0].state, ChargeState::Full);
+        assert_eq!(status.batteries[0].power_watts, None);
+    }
+
+    #[test]
+    fn skips_device_and_absent_batteries() {
+        let root = TempDir::new("power-skip");
+        root.write("hidpp_battery_0/type", "Battery\n");
+        root.write("hidpp_battery_0/scope", "Device\n");
+        root.write("hidpp_battery_0/capacity", "80\n");
+        root.write("BAT1/type", "Battery\n");
+        root.write("BAT1/present", "0\n");
+        root.write("BAT1/capacity", "0\n");
+        root.write("BAT2/type", "Battery\n");
+        root.write("BAT2/status", "garbage\n");
+
+        let status = PowerSupplyReader::new(root.path()).read();
+        assert!(status.batteries.is_empty());
+        assert_eq!(status.on_ac, None);
+    }
+
+    #[test]
+    fn desktop_with_mains_only() {
+        let root = TempDir::new("power-desktop");
+        root.write("AC0/type", "Mains\n");
+        root.write("AC0/online", "1\n");
+        root.write("AC1/type", "Mains\n");
+        root.write("AC1/online", "0\n");
+
+        let status = PowerSupplyReader::new(root.path()).read();
+        assert_eq!(status.on_ac, Some(true));
+        assert!(status.batteries.is_empty());
+        assert_eq!(
+            PowerSupplyReader::new(root.path().join("absent"))
+                .read()
+                .on_ac,
+            None
+        );
+    }
+}
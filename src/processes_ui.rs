@@ -0,0 +1,275 @@
+use async_channel::Sender;
+use gtk4::prelude::*;
+use gtk4::{
+    glib, Align, Box, Button, GestureClick, Label, ListBox, ListBoxRow, Orientation, Popover,
+    ToggleButton,
+};
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+use crate::monitor::{MonitorControl, ProcessInfo, ProcessSignal};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ProcessSort {
+    Cpu,
+    Memory,
+    Name,
+    Pid,
+}
+
+/// "Top processes" list fed from the monitoring thread. Rows can be sorted by column and
+/// offer SIGTERM/SIGKILL from a right-click menu after confirmation.
+#[derive(Clone)]
+pub struct ProcessPanel {
+    pub widget: Box,
+    list: ListBox,
+    sort: Rc<Cell<ProcessSort>>,
+    latest: Rc<RefCell<Vec<ProcessInfo>>>,
+    limit: Rc<Cell<usize>>,
+    // Rows are rebuilt on every sample; hold off while a row menu is open so it is not torn down.
+    menu_open: Rc<Cell<bool>>,
+    control: Sender<MonitorControl>,
+}
+
+impl ProcessPanel {
+    pub fn new(control: Sender<MonitorControl>) -> Self {
+        let widget = Box::new(Orientation::Vertical, 6);
+        widget.add_css_class("process-panel");
+
+        let sort_row = Box::new(Orientation::Horizontal, 4);
+        sort_row.set_halign(Align::End);
+        let sort_label = Label::new(Some("Sort by"));
+        sort_label.add_css_class("card-title");
+        sort_row.append(&sort_label);
+
+        let list = ListBox::new();
+        list.add_css_class("process-list");
+        list.set_selection_mode(gtk4::SelectionMode::None);
+
+        let panel = Self {
+            widget,
+            list,
+            sort: Rc::new(Cell::new(ProcessSort::Cpu)),
+            latest: Rc::new(RefCell::new(Vec::new())),
+            limit: Rc::new(Cell::new(8)),
+            menu_open: Rc::new(Cell::new(false)),
+            control,
+        };
+
+        let mut group: Option<ToggleButton> = None;
+        for (label, sort) in [
+            ("CPU", ProcessSort::Cpu),
+            ("Memory", ProcessSort::Memory),
+            ("Name", ProcessSort::Name),
+            ("PID", ProcessSort::Pid),
+        ] {
+            let btn = ToggleButton::with_label(label);
+            btn.add_css_class("pill-btn");
+            btn.set_group(group.as_ref());
+            btn.set_active(sort == ProcessSort::Cpu);
+            let panel_clone = panel.clone();
+            btn.connect_toggled(move |btn| {
+                if btn.is_active() {
+                    panel_clone.sort.set(sort);
+                    panel_clone.render();
+                }
+            });
+            sort_row.append(&btn);
+            group.get_or_insert(btn);
+        }
+
+        panel.widget.append(&sort_row);
+        panel.widget.append(&panel.list);
+        panel
+    }
+
+    pub fn update(&self, processes: &[ProcessInfo], limit: usize) {
+        self.latest.replace(processes.to_vec());
+        self.limit.set(limit);
+        if !self.menu_open.get() {
+            self.render();
+        }
+    }
+
+    fn render(&self) {
+        while let Some(child) = self.list.first_child() {
+            self.list.remove(&child);
+        }
+
+        let mut rows = self.latest.borrow().clone();
+        // Rank by load first so the name and PID orderings still show the busiest processes.
+        rows.sort_by(|a, b| b.cpu_usage.total_cmp(&a.cpu_usage));
+        match self.sort.get() {
+            ProcessSort::Cpu => {}
+            ProcessSort::Memory => rows.sort_by_key(|p| std::cmp::Reverse(p.memory)),
+            ProcessSort::Name | ProcessSort::Pid => rows.truncate(self.limit.get()),
+        }
+        match self.sort.get() {
+            ProcessSort::Name => rows.sort_by_key(|p| p.name.to_lowercase()),
+            ProcessSort::Pid => rows.sort_by_key(|p| p.pid),
+            ProcessSort::Cpu | ProcessSort::Memory => {}
+        }
+        rows.truncate(self.limit.get());
+
+        for process in rows {
+            self.list.append(&build_row(self, process));
+        }
+    }
+}
+
+fn build_row(panel: &ProcessPanel, process: ProcessInfo) -> ListBoxRow {
+    let row = ListBoxRow::new();
+    row.add_css_class("process-row");
+
+    let column = Box::new(Orientation::Vertical, 2);
+    let line = Box::new(Orientation::Horizontal, 8);
+
+    let pid = Label::new(Some(&process.pid.to_string()));
+    pid.add_css_class("process-pid");
+    pid.set_width_chars(7);
+    pid.set_xalign(0.0);
+
+    let name = Label::new(Some(&process.name));
+    name.add_css_class("process-name");
+    name.set_hexpand(true);
+    name.set_xalign(0.0);
+    name.set_ellipsize(gtk4::pango::EllipsizeMode::End);
+
+    let user = Label::new(Some(&process.user));
+    user.add_css_class("process-user");
+
+    let cpu = Label::new(Some(&format!("{:.1}%", process.cpu_usage)));
+    cpu.set_width_chars(7);
+    cpu.set_xalign(1.0);
+
+    #[allow(clippy::cast_precision_loss)]
+    let mem = Label::new(Some(&format!("{:.0} MB", process.memory as f64 / 1024.0 / 1024.0)));
+    mem.set_width_chars(8);
+    mem.set_xalign(1.0);
+
+    line.append(&pid);
+    line.append(&name);
+    line.append(&user);
+    line.append(&cpu);
+    line.append(&mem);
+    column.append(&line);
+
+    if !process.command.is_empty() {
+        let command = Label::new(Some(&process.command));
+        command.add_css_class("process-command");
+        command.set_xalign(0.0);
+        command.set_ellipsize(gtk4::pango::EllipsizeMode::End);
+        column.append(&command);
+        row.set_tooltip_text(Some(&process.command));
+    }
+
+    row.set_child(Some(&column));
+
+    let gesture = GestureClick::new();
+    gesture.set_button(3);
+    {
+        let panel_clone = panel.clone();
+        // Weak, so the rows discarded on every refresh are not kept alive by their own handler.
+        let row_weak = row.downgrade();
+        gesture.connect_pressed(move |_, _, x, y| {
+            let Some(row) = row_weak.upgrade() else {
+                return;
+            };
+            let popover = build_signal_popover(&panel_clone, &process, &row);
+            #[allow(clippy::cast_possible_truncation)]
+            popover.set_pointing_to(Some(&gtk4::gdk::Rectangle::new(x as i32, y as i32, 1, 1)));
+            panel_clone.menu_open.set(true);
+            popover.popup();
+        });
+    }
+    row.add_controller(gesture);
+
+    row
+}
+
+fn build_signal_popover(
+    panel: &ProcessPanel,
+    process: &ProcessInfo,
+    anchor: &ListBoxRow,
+) -> Popover {
+    let popover = Popover::builder().has_arrow(true).build();
+    popover.set_parent(anchor);
+
+    let column = Box::new(Orientation::Vertical, 6);
+    column.set_margin_top(8);
+    column.set_margin_bottom(8);
+    column.set_margin_start(8);
+    column.set_margin_end(8);
+
+    let choices = Box::new(Orientation::Vertical, 6);
+    for (label, signal) in [
+        ("Terminate (SIGTERM)", ProcessSignal::Term),
+        ("Kill (SIGKILL)", ProcessSignal::Kill),
+    ] {
+        let btn = Button::with_label(label);
+        btn.add_css_class("flat");
+        let column_clone = column.clone();
+        let choices_clone = choices.clone();
+        let panel_clone = panel.clone();
+        let pop = popover.clone();
+        let (pid, name) = (process.pid, process.name.clone());
+        btn.connect_clicked(move |_| {
+            column_clone.remove(&choices_clone);
+            column_clone.append(&build_confirmation(&panel_clone, &pop, pid, &name, signal));
+        });
+        choices.append(&btn);
+    }
+    column.append(&choices);
+    popover.set_child(Some(&column));
+
+    {
+        let panel_clone = panel.clone();
+        popover.connect_closed(move |pop| {
+            panel_clone.menu_open.set(false);
+            let pop = pop.clone();
+            glib::idle_add_local_once(move || pop.unparent());
+        });
+    }
+
+    popover
+}
+
+fn build_confirmation(
+    panel: &ProcessPanel,
+    popover: &Popover,
+    pid: u32,
+    name: &str,
+    signal: ProcessSignal,
+) -> Box {
+    let column = Box::new(Orientation::Vertical, 6);
+
+    let prompt = Label::new(Some(&format!("Send {} to {name} ({pid})?", signal.label())));
+    prompt.add_css_class("popover-label");
+    column.append(&prompt);
+
+    let actions = Box::new(Orientation::Horizontal, 6);
+    let cancel = Button::with_label("Cancel");
+    let confirm = Button::with_label("Send");
+    confirm.add_css_class("danger");
+    actions.append(&cancel);
+    actions.append(&confirm);
+    column.append(&actions);
+
+    {
+        let pop = popover.clone();
+        cancel.connect_clicked(move |_| pop.popdown());
+    }
+
+    {
+        let pop = popover.clone();
+        let control = panel.control.clone();
+        confirm.connect_clicked(move |_| {
+            let _ = control.try_send(MonitorControl::SignalProcess { pid, signal });
+            pop.popdown();
+        });
+    }
+
+    column
+}
@@ -0,0 +1,266 @@
+use std::borrow::Cow;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::monitor::MonitorData;
+use crate::settings::{InterfaceFilter, RecordFormat};
+use crate::stats::TimedSample;
+
+const CSV_HEADER: &str = "timestamp,cpu_percent,ram_used_bytes,ram_total_bytes,\
+swap_used_bytes,swap_total_bytes,load_1m,net_rx_bytes_per_sec,net_tx_bytes_per_sec,\
+disk_read_bytes_per_sec,disk_write_bytes_per_sec,gpu_percent,max_temp_c,battery_percent";
+
+/// Where a recording goes and when it rotates.
+#[derive(Clone, Debug)]
+pub struct RecordTarget {
+    pub path: PathBuf,
+    pub format: RecordFormat,
+    pub max_bytes: u64,
+    /// Rotated files kept as `path.1` (newest) to `path.<keep>`.
+    pub keep: usize,
+    /// Interfaces summed into the CSV network columns.
+    pub network_filter: InterfaceFilter,
+}
+
+/// Appends every sample to a CSV or NDJSON file, rotating it once it reaches `max_bytes`.
+pub struct Recorder {
+    target: RecordTarget,
+    file: File,
+    written: u64,
+}
+
+impl Recorder {
+    /// Opens `target.path` for appending, creating it (and its directory) when missing.
+    pub fn open(target: RecordTarget) -> io::Result<Self> {
+        if let Some(dir) = target
+            .path
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+        {
+            fs::create_dir_all(dir)?;
+        }
+        let (file, written) = Self::open_file(&target)?;
+        Ok(Self {
+            target,
+            file,
+            written,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.target.path
+    }
+
+    fn open_file(target: &RecordTarget) -> io::Result<(File, u64)> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&target.path)?;
+        let mut written = file.metadata()?.len();
+        if written == 0 && target.format == RecordFormat::Csv {
+            writeln!(file, "{CSV_HEADER}")?;
+            written = CSV_HEADER.len() as u64 + 1;
+        }
+        Ok((file, written))
+    }
+
+    pub fn append(&mut self, timestamp: u64, data: &MonitorData) -> io::Result<()> {
+        let line = match self.target.format {
+            RecordFormat::Csv => csv_row(timestamp, data, &self.target.network_filter),
+            RecordFormat::Ndjson => serde_json::to_string(&TimedSample { timestamp, data })?,
+        };
+        let len = line.len() as u64 + 1;
+        if self.written > 0 && self.written + len > self.target.max_bytes {
+            self.rotate()?;
+        }
+        writeln!(self.file, "{line}")?;
+        self.written += len;
+        Ok(())
+    }
+
+    /// Shifts `path.N` to `path.N+1` (dropping the oldest), moves the live file to `path.1`
+    /// and starts a fresh one.
+    fn rotate(&mut self) -> io::Result<()> {
+        let numbered = |n: usize| {
+            let mut name = self.target.path.clone().into_os_string();
+            name.push(format!(".{n}"));
+            PathBuf::from(name)
+        };
+        if self.target.keep == 0 {
+            fs::remove_file(&self.target.path)?;
+        } else {
+            let _ = fs::remove_file(numbered(self.target.keep));
+            for n in (1..self.target.keep).rev() {
+                let from = numbered(n);
+                if from.exists() {
+                    fs::rename(&from, numbered(n + 1))?;
+                }
+            }
+            fs::rename(&self.target.path, numbered(1))?;
+        }
+        let (file, written) = Self::open_file(&self.target)?;
+        self.file = file;
+        self.written = written;
+        Ok(())
+    }
+}
+
+fn csv_row(timestamp: u64, data: &MonitorData, filter: &InterfaceFilter) -> String {
+    let optional = |value: Option<f64>| value.map_or_else(String::new, |v| format!("{v:.1}"));
+    let (rx, tx) = data
+        .network_interfaces(filter, None)
+        .fold((0.0, 0.0), |(rx, tx), iface| {
+            (rx + iface.rx_bytes_per_sec, tx + iface.tx_bytes_per_sec)
+        });
+    let (read, write) = data.disk_io.iter().fold((0.0, 0.0), |(read, write), disk| {
+        (
+            read + disk.read_bytes_per_sec,
+            write + disk.write_bytes_per_sec,
+        )
+    });
+    let hottest = data
+        .temperatures
+        .iter()
+        .map(|t| f64::from(t.celsius))
+        .reduce(f64::max);
+    let battery = data
+        .power
+        .batteries
+        .first()
+        .map(|b| f64::from(b.capacity_percent));
+
+    [
+        timestamp.to_string(),
+        format!("{:.1}", data.cpu_usage),
+        data.ram_used.to_string(),
+        data.ram_total.to_string(),
+        data.swap_used.to_string(),
+        data.swap_total.to_string(),
+        format!("{:.2}", data.load_average.one),
+        format!("{rx:.0}"),
+        format!("{tx:.0}"),
+        format!("{read:.0}"),
+        format!("{write:.0}"),
+        optional(data.gpu_usage().map(f64::from)),
+        optional(hottest),
+        optional(battery),
+    ]
+    .join(",")
+}
+
+/// Writes chart series as `timestamp,series,value` rows, or one JSON object per point when
+/// `path` names an NDJSON file.
+pub fn export_points(path: &Path, series: &[(&str, &[(u64, f64)])]) -> io::Result<()> {
+    let mut out = String::new();
+    let format = RecordFormat::from_path(path);
+    if format == RecordFormat::Csv {
+        out.push_str("timestamp,series,value\n");
+    }
+    for (label, points) in series {
+        for (timestamp, value) in *points {
+            let line = match format {
+                RecordFormat::Csv => format!("{timestamp},{},{value}\n", csv_field(label)),
+                RecordFormat::Ndjson => format!(
+                    "{}\n",
+                    serde_json::json!({ "timestamp": timestamp, "series": label, "value": value })
+                ),
+            };
+            out.push_str(&line);
+        }
+    }
+    fs::write(path, out)
+}
+
+/// Quotes `field` per RFC 4180 when it holds a comma, quote or line break.
+fn csv_field(field: &str) -> Cow<'_, str> {
+    if field.contains([',', '"', '\r', '\n']) {
+        Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(field)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    fn target(dir: &TempDir, format: RecordFormat, max_bytes: u64, keep: usize) -> RecordTarget {
+        RecordTarget {
+            path: dir.path().join("rec").join("samples.log"),
+            format,
+            max_bytes,
+            keep,
+            network_filter: InterfaceFilter::default(),
+        }
+    }
+
+    fn timestamps(path: &Path) -> Vec<String> {
+        fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .filter_map(|line| Some(line.split_once(',')?.0.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn rotates_and_keeps_the_newest_files() {
+        let dir = TempDir::new("recorder-rotate");
+        let data = MonitorData::default();
+        let row = csv_row(0, &data, &InterfaceFilter::default()).len() as u64 + 1;
+        let header = CSV_HEADER.len() as u64 + 1;
+        // Room for the header and exactly two rows per file.
+        let mut recorder =
+            Recorder::open(target(&dir, RecordFormat::Csv, header + 2 * row, 2)).unwrap();
+        for timestamp in 1..=7 {
+            recorder.append(timestamp, &data).unwrap();
+        }
+
+        let path = recorder.path().to_path_buf();
+        let numbered = |n: u8| PathBuf::from(format!("{}.{n}", path.display()));
+        assert_eq!(timestamps(&path), ["timestamp", "7"]);
+        assert_eq!(timestamps(&numbered(1)), ["timestamp", "5", "6"]);
+        assert_eq!(timestamps(&numbered(2)), ["timestamp", "3", "4"]);
+        assert!(!numbered(3).exists());
+    }
+
+    #[test]
+    fn rotating_with_keep_zero_discards_the_old_file() {
+        let dir = TempDir::new("recorder-keep-zero");
+        let data = MonitorData::default();
+        let mut recorder = Recorder::open(target(&dir, RecordFormat::Ndjson, 1, 0)).unwrap();
+        recorder.append(1, &data).unwrap();
+        recorder.append(2, &data).unwrap();
+
+        let content = fs::read_to_string(recorder.path()).unwrap();
+        assert_eq!(content.lines().count(), 1);
+        assert!(content.starts_with("{\"timestamp\":2,"));
+        assert!(!PathBuf::from(format!("{}.1", recorder.path().display())).exists());
+    }
+
+    #[test]
+    fn export_quotes_csv_labels() {
+        let dir = TempDir::new("recorder-export");
+        let path = dir.path().join("history.csv");
+        let points = [(10, 1.5)];
+        export_points(
+            &path,
+            &[
+                ("cpu", &points),
+                ("disk, \"root\"", &points),
+                ("two\nlines", &points),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "timestamp,series,value\n\
+             10,cpu,1.5\n\
+             10,\"disk, \"\"root\"\"\",1.5\n\
+             10,\"two\nlines\",1.5\n"
+        );
+    }
+}
@@ -0,0 +1,300 @@
+use std::io::{BufRead, BufReader, Read};
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use crate::monitor::MonitorData;
+use crate::settings::RemoteHost;
+
+/// Pause before reconnecting after the session drops.
+const RETRY: Duration = Duration::from_secs(10);
+/// Shortest silence after which a session is treated as hung and restarted. Longer sampling
+/// intervals get five of their own intervals instead.
+const MIN_STALL: Duration = Duration::from_secs(30);
+
+/// What a remote session reports to the UI.
+#[derive(Debug)]
+pub enum RemoteEvent {
+    Sample(Box<MonitorData>),
+    /// Connection state worth showing in place of readings, e.g. why `ssh` exited.
+    Status(String),
+}
+
+/// Streams samples from another machine by running the host's sampling command through
+/// `ssh`, which is resolved through `PATH` and reads the user's own ssh config. The command
+/// prints one JSON `MonitorData` per line. A session that drops, or goes quiet for longer
+/// than [`MIN_STALL`], is retried after [`RETRY`]; dropping the handle ends it for good.
+pub struct RemoteMonitor {
+    pub receiver: async_channel::Receiver<RemoteEvent>,
+    child: Arc<Mutex<Option<Child>>>,
+    stopped: Arc<AtomicBool>,
+}
+
+impl RemoteMonitor {
+    pub fn start(host: &RemoteHost) -> Self {
+        let interval = Duration::from_millis(host.interval_ms.max(250));
+        Self::with_stall(host, interval.saturating_mul(5).max(MIN_STALL))
+    }
+
+    fn with_stall(host: &RemoteHost, stall: Duration) -> Self {
+        let (sender, receiver) = async_channel::unbounded();
+        let child = Arc::new(Mutex::new(None));
+        let stopped = Arc::new(AtomicBool::new(false));
+
+        let destination = host.host.clone();
+        let command = host.command();
+        let (slot, stop) = (child.clone(), stopped.clone());
+        thread::spawn(move || {
+            while !stop.load(Ordering::Relaxed) {
+                let Some(reason) =
+                    run_session(&destination, &command, stall, &slot, &stop, &sender)
+                else {
+                    break;
+                };
+                if stop.load(Ordering::Relaxed)
+                    || sender
+                        .send_blocking(RemoteEvent::Status(format!("Disconnected: {reason}")))
+                        .is_err()
+                {
+                    break;
+                }
+                // Sleep in short steps so dropping the handle is not held up by the retry.
+                for _ in 0..RETRY.as_millis() / 250 {
+                    if stop.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    thread::sleep(Duration::from_millis(250));
+                }
+            }
+        });
+
+        Self {
+            receiver,
+            child,
+            stopped,
+        }
+    }
+}
+
+impl Drop for RemoteMonitor {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::Relaxed);
+        kill(&self.child);
+    }
+}
+
+/// Runs one `ssh` session until it exits and returns why it ended, or `None` when the session
+/// should not be retried because the handle was dropped or the UI went away. Going `stall`
+/// without a line of output kills the session.
+fn run_session(
+    destination: &str,
+    command: &str,
+    stall: Duration,
+    slot: &Arc<Mutex<Option<Child>>>,
+    stop: &AtomicBool,
+    sender: &async_channel::Sender<RemoteEvent>,
+) -> Option<String> {
+    let status = format!("Connecting to {destination}…");
+    sender.send_blocking(RemoteEvent::Status(status)).ok()?;
+
+    let spawned = Command::new("ssh")
+        .args([
+            "-T",
+            "-o",
+            "BatchMode=yes",
+            "-o",
+            "ServerAliveInterval=15",
+            "--",
+        ])
+        .args([destination, command])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn();
+    let mut child = match spawned {
+        Ok(child) => child,
+        Err(e) => return Some(format!("could not start ssh: {e}")),
+    };
+    let (Some(stdout), Some(mut stderr)) = (child.stdout.take(), child.stderr.take()) else {
+        let _ = child.kill();
+        let _ = child.wait();
+        return Some("ssh output unavailable".to_string());
+    };
+    if let Ok(mut slot) = slot.lock() {
+        *slot = Some(child);
+    }
+    // The handle may have been dropped before the child was stored for it to kill.
+    if stop.load(Ordering::Relaxed) {
+        kill(slot);
+        return None;
+    }
+
+    // Drained on its own thread so a chatty remote cannot stall the sample stream.
+    let errors = thread::spawn(move || {
+        let mut text = String::new();
+        let _ = stderr.read_to_string(&mut text);
+        text.lines()
+            .rev()
+            .find(|l| !l.trim().is_empty())
+            .map(str::to_string)
+    });
+
+    // Read on its own thread too, so a remote that stops printing can be noticed and cut off.
+    let (lines_tx, lines) = mpsc::channel();
+    thread::spawn(move || {
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            if lines_tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut bad_output = None;
+    loop {
+        let line = match lines.recv_timeout(stall) {
+            Ok(line) => line,
+            Err(RecvTimeoutError::Disconnected) => break,
+            Err(RecvTimeoutError::Timeout) => {
+                kill(slot);
+                if stop.load(Ordering::Relaxed) {
+                    return None;
+                }
+                return Some(format!("no data for {}s", stall.as_secs()));
+            }
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<MonitorData>(&line) {
+            Ok(data) => {
+                if sender
+                    .send_blocking(RemoteEvent::Sample(Box::new(data)))
+                    .is_err()
+                {
+                    kill(slot);
+                    return None;
+                }
+            }
+            Err(e) => bad_output = Some(format!("unexpected output ({e})")),
+        }
+    }
+
+    let exit = slot
+        .lock()
+        .ok()
+        .and_then(|mut slot| slot.take())
+        .and_then(|mut child| child.wait().ok());
+    if stop.load(Ordering::Relaxed) {
+        return None;
+    }
+    let stderr = errors.join().ok().flatten();
+    Some(
+        stderr
+            .or(bad_output)
+            .or_else(|| exit.map(|status| format!("ssh exited ({status})")))
+            .unwrap_or_else(|| "ssh exited".to_string()),
+    )
+}
+
+fn kill(slot: &Mutex<Option<Child>>) {
+    if let Some(mut child) = slot.lock().ok().and_then(|mut slot| slot.take()) {
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::test_support::{wait_for, with_path, TempDir};
+
+    /// Starts a session against the fake `ssh` in `bin` and collects its first `count` events.
+    fn events(bin: &TempDir, stall: Duration, count: usize) -> Vec<RemoteEvent> {
+        let host = RemoteHost {
+            host: "builder".to_string(),
+            command: Some("sample".to_string()),
+            ..RemoteHost::default()
+        };
+        // `ssh` is spawned from the session thread, so `PATH` has to stay set while it runs.
+        with_path(bin.path(), || {
+            let monitor = RemoteMonitor::with_stall(&host, stall);
+            let mut events = Vec::new();
+            assert!(wait_for(|| {
+                while let Ok(event) = monitor.receiver.try_recv() {
+                    events.push(event);
+                }
+                events.len() >= count
+            }));
+            events
+        })
+    }
+
+    fn status(event: &RemoteEvent) -> Option<&str> {
+        match event {
+            RemoteEvent::Status(status) => Some(status),
+            RemoteEvent::Sample(_) => None,
+        }
+    }
+
+    #[test]
+    fn streams_samples_until_ssh_exits() {
+        let bin = TempDir::new("fake-ssh");
+        bin.script(
+            "ssh",
+            "if [ \"$6 $7 $8\" != '-- builder sample' ]; then\n\
+                 echo \"bad arguments: $*\" >&2\n\
+                 exit 1\n\
+             fi\n\
+             echo '{\"cpu_usage\": 12.5}'\n\
+             echo\n\
+             echo '{\"cpu_usage\": 25.0}'\n",
+        );
+
+        let events = events(&bin, MIN_STALL, 4);
+        assert_eq!(status(&events[0]), Some("Connecting to builder…"));
+        let usage: Vec<f32> = events[1..3]
+            .iter()
+            .filter_map(|event| match event {
+                RemoteEvent::Sample(data) => Some(data.cpu_usage),
+                RemoteEvent::Status(_) => None,
+            })
+            .collect();
+        assert_eq!(usage.len(), 2);
+        assert!((usage[0] - 12.5).abs() < f32::EPSILON);
+        assert!((usage[1] - 25.0).abs() < f32::EPSILON);
+        assert_eq!(
+            status(&events[3]),
+            Some("Disconnected: ssh exited (exit status: 0)")
+        );
+    }
+
+    #[test]
+    fn reports_why_ssh_failed() {
+        let bin = TempDir::new("fake-ssh");
+        bin.script(
+            "ssh",
+            "echo 'Warning: Permanently added the host key.' >&2\n\
+             echo 'builder: Permission denied (publickey).' >&2\n\
+             exit 255\n",
+        );
+
+        let events = events(&bin, MIN_STALL, 2);
+        assert_eq!(
+            status(&events[1]),
+            Some("Disconnected: builder: Permission denied (publickey).")
+        );
+    }
+
+    #[test]
+    fn restarts_a_silent_session() {
+        let bin = TempDir::new("fake-ssh");
+        // `exec` so killing the session also closes the pipes the reader threads wait on.
+        bin.script("ssh", "exec sleep 60\n");
+
+        let events = events(&bin, Duration::from_secs(1), 2);
+        assert_eq!(status(&events[1]), Some("Disconnected: no data for 1s"));
+    }
+}
@@ -0,0 +1,161 @@
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+/// How a split divides its area.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum SplitDirection {
+    SideBySide,
+    Stacked,
+}
+
+/// A tab's pane tree: a single terminal, or a split holding two more trees.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub enum PaneSession {
+    Terminal {
+        /// Last directory the shell reported through OSC 7, or the one it was opened in.
+        cwd: Option<String>,
+        /// Typed into the shell once it has started.
+        command: Option<String>,
+    },
+    Split {
+        direction: SplitDirection,
+        /// Divider position in pixels from the left or top edge.
+        position: i32,
+        start: Box<Self>,
+        end: Box<Self>,
+    },
+}
+
+impl Default for PaneSession {
+    fn default() -> Self {
+        Self::Terminal {
+            cwd: None,
+            command: None,
+        }
+    }
+}
+
+/// One terminal tab as it is reopened on the next launch.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct TabSession {
+    pub title: String,
+    /// Terminal profile by name; `None` follows the default profile.
+    pub profile: Option<String>,
+    pub panes: PaneSession,
+}
+
+/// The terminal notebook layout, saved to `terminal-session.json` as tabs change.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct TerminalSession {
+    pub tabs: Vec<TabSession>,
+    /// Position of the tab that was shown.
+    pub active: u32,
+}
+
+impl TerminalSession {
+    pub fn load() -> Self {
+        if let Some(proj_dirs) = ProjectDirs::from("com", "moebius", "vitray-widget") {
+            let file_path = proj_dirs.config_dir().join("terminal-session.json");
+
+            if file_path.exists() {
+                match fs::read_to_string(&file_path).map(|content| serde_json::from_str(&content)) {
+                    Ok(Ok(session)) => return session,
+                    Ok(Err(e)) => {
+                        eprintln!("Ignoring terminal session {}: {e}", file_path.display());
+                    }
+                    Err(e) => eprintln!("Failed to read {}: {e}", file_path.display()),
+                }
+            }
+        }
+        Self::default()
+    }
+
+    pub fn save(&self) {
+        if let Some(proj_dirs) = ProjectDirs::from("com", "moebius", "vitray-widget") {
+            let config_dir = proj_dirs.config_dir();
+            if !config_dir.exists() {
+                let _ = fs::create_dir_all(config_dir);
+            }
+            let file_path = config_dir.join("terminal-session.json");
+            let json = serde_json::to_string_pretty(self).unwrap_or_default();
+            if let Err(e) = fs::write(&file_path, json) {
+                eprintln!(
+                    "Failed to save terminal session to {}: {e}",
+                    file_path.display()
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn terminal(cwd: &str, command: Option<&str>) -> PaneSession {
+        PaneSession::Terminal {
+            cwd: Some(cwd.to_string()),
+            command: command.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn round_trips_nested_splits() {
+        let session = TerminalSession {
+            tabs: vec![
+                TabSession {
+                    title: "build".to_string(),
+                    profile: Some("Solarized".to_string()),
+                    panes: PaneSession::Split {
+                        direction: SplitDirection::SideBySide,
+                        position: 480,
+                        start: Box::new(terminal("/src/app", Some("cargo watch"))),
+                        end: Box::new(PaneSession::Split {
+                            direction: SplitDirection::Stacked,
+                            position: 200,
+                            start: Box::new(terminal("/src/app", None)),
+                            end: Box::new(terminal("/var/log", Some("journalctl -f"))),
+                        }),
+                    },
+                },
+                TabSession::default(),
+            ],
+            active: 1,
+        };
+        let json = serde_json::to_string_pretty(&session).unwrap();
+        assert_eq!(
+            serde_json::from_str::<TerminalSession>(&json).unwrap(),
+            session
+        );
+    }
+
+    #[test]
+    fn fills_in_what_older_or_partial_files_leave_out() {
+        assert_eq!(
+            serde_json::from_str::<TerminalSession>("{}").unwrap(),
+            TerminalSession::default()
+        );
+
+        let session: TerminalSession = serde_json::from_str(
+            r#"{"tabs": [
+                {"title": "T1"},
+                {"title": "logs", "panes": {"Terminal": {"cwd": "/var/log"}}}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(session.active, 0);
+        assert_eq!(
+            session.tabs[0],
+            TabSession {
+                title: "T1".to_string(),
+                profile: None,
+                panes: PaneSession::default(),
+            }
+        );
+        assert_eq!(session.tabs[1].panes, terminal("/var/log", None));
+    }
+}
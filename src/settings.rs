@@ -1,6 +1,7 @@
 use directories::ProjectDirs;
 use serde::{Deserialize, Serialize};
 use std::fs;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub enum Theme {
@@ -15,6 +16,160 @@ pub enum MonitorStyle {
     Bar,
     Chart,
     Text,
+    /// Per-core heatmap; cards without a per-core breakdown fall back to the bar view.
+    Cores,
+}
+
+/// Time span the monitor charts cover.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ChartRange {
+    #[default]
+    FiveMinutes,
+    OneHour,
+    Day,
+}
+
+impl ChartRange {
+    pub const fn seconds(self) -> u64 {
+        match self {
+            Self::FiveMinutes => 5 * 60,
+            Self::OneHour => 60 * 60,
+            Self::Day => 24 * 60 * 60,
+        }
+    }
+
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::FiveMinutes => "5m",
+            Self::OneHour => "1h",
+            Self::Day => "24h",
+        }
+    }
+}
+
+/// How a chart picks the top of its y axis.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ChartScale {
+    /// Rounded up from the largest visible value.
+    #[default]
+    Auto,
+    /// The card's own maximum, e.g. 100% for CPU.
+    Fixed,
+}
+
+/// Warning and critical levels for a card's headline value. When `critical` is below
+/// `warning`, lower values are worse, as for battery charge.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct LevelThresholds {
+    pub warning: f64,
+    pub critical: f64,
+}
+
+impl LevelThresholds {
+    pub const fn new(warning: f64, critical: f64) -> Self {
+        Self { warning, critical }
+    }
+
+    /// Whether `value` has reached the warning and critical levels.
+    pub fn reached(&self, value: f64) -> (bool, bool) {
+        if self.critical < self.warning {
+            (value <= self.warning, value <= self.critical)
+        } else {
+            (value >= self.warning, value >= self.critical)
+        }
+    }
+}
+
+/// Status colouring per card, in the card's own unit; `None` leaves a card uncoloured.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct CardLevels {
+    pub cpu: Option<LevelThresholds>,
+    pub gpu: Option<LevelThresholds>,
+    /// Percent of memory in use.
+    pub ram: Option<LevelThresholds>,
+    /// Combined KB/s.
+    pub network: Option<LevelThresholds>,
+    /// Percent used on the fullest mount.
+    pub disk: Option<LevelThresholds>,
+    /// Hottest sensor in °C.
+    pub thermals: Option<LevelThresholds>,
+    /// Charge percent; lower is worse.
+    pub battery: Option<LevelThresholds>,
+}
+
+impl Default for CardLevels {
+    fn default() -> Self {
+        Self {
+            cpu: Some(LevelThresholds::new(70.0, 90.0)),
+            gpu: Some(LevelThresholds::new(70.0, 90.0)),
+            ram: Some(LevelThresholds::new(75.0, 90.0)),
+            network: None,
+            disk: Some(LevelThresholds::new(80.0, 95.0)),
+            thermals: Some(LevelThresholds::new(75.0, 90.0)),
+            battery: Some(LevelThresholds::new(20.0, 10.0)),
+        }
+    }
+}
+
+/// File format for recorded samples.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RecordFormat {
+    /// One row of headline readings per sample.
+    #[default]
+    Csv,
+    /// The full sample as one JSON object per line.
+    Ndjson,
+}
+
+impl RecordFormat {
+    /// Picks the format from a file extension, defaulting to CSV.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("ndjson" | "jsonl" | "json") => Self::Ndjson,
+            _ => Self::Csv,
+        }
+    }
+
+    pub const fn extension(self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Ndjson => "ndjson",
+        }
+    }
+}
+
+/// Where and how record mode writes samples.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct RecordingSettings {
+    pub format: RecordFormat,
+    /// Directory for recordings started from the card menu; `None` uses the data directory.
+    pub directory: Option<String>,
+    /// A recording is rotated once it reaches this size.
+    pub max_file_mb: u64,
+    /// Rotated files kept next to the live one (`name.1` is the newest).
+    pub keep_files: usize,
+}
+
+impl Default for RecordingSettings {
+    fn default() -> Self {
+        Self {
+            format: RecordFormat::Csv,
+            directory: None,
+            max_file_mb: 20,
+            keep_files: 5,
+        }
+    }
+}
+
+impl RecordingSettings {
+    pub fn directory(&self) -> Option<PathBuf> {
+        self.directory.as_ref().map(PathBuf::from).or_else(|| {
+            ProjectDirs::from("com", "moebius", "vitray-widget")
+                .map(|dirs| dirs.data_dir().join("recordings"))
+        })
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -62,6 +217,322 @@ impl Default for WidgetLayout {
     }
 }
 
+/// Include/exclude lists for the aggregate network figure. Entries are interface names or
+/// globs using `*` and `?`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct InterfaceFilter {
+    /// When non-empty, only matching interfaces are counted.
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+}
+
+impl Default for InterfaceFilter {
+    fn default() -> Self {
+        Self {
+            include: Vec::new(),
+            exclude: ["lo", "docker*", "br-*", "veth*", "virbr*"]
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+        }
+    }
+}
+
+impl InterfaceFilter {
+    pub fn matches(&self, name: &str) -> bool {
+        let included =
+            self.include.is_empty() || self.include.iter().any(|p| glob_match(p, name));
+        included && !self.exclude.iter().any(|p| glob_match(p, name))
+    }
+}
+
+/// Minimal glob matcher supporting `*` (any run) and `?` (any single character).
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = backtrack {
+            p = star_p + 1;
+            t = star_t + 1;
+            backtrack = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// Per-sensor sampling periods in milliseconds; `None` follows `refresh_interval_ms`.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+#[allow(clippy::struct_field_names)]
+pub struct SensorIntervals {
+    pub cpu_ms: Option<u64>,
+    pub memory_ms: Option<u64>,
+    pub network_ms: Option<u64>,
+    pub disk_ms: Option<u64>,
+    pub thermals_ms: Option<u64>,
+    pub gpu_ms: Option<u64>,
+    pub processes_ms: Option<u64>,
+    pub cgroups_ms: Option<u64>,
+    pub power_ms: Option<u64>,
+}
+
+/// A user script sampled like a built-in sensor and shown on its own card.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct CommandSensorConfig {
+    /// Card title; also identifies the sensor's readings.
+    pub name: String,
+    /// Run through the platform shell. Stdout is either a bare number or a JSON document.
+    pub command: String,
+    pub interval_ms: u64,
+    /// Dotted path to the value inside a JSON document, e.g. `queue.depth`. Defaults to a
+    /// top-level `value` field.
+    pub json_path: Option<String>,
+    /// Suffix shown after the value, e.g. `jobs`.
+    pub unit: String,
+    /// Value that fills the bar and tops the chart.
+    pub max: f64,
+}
+
+impl Default for CommandSensorConfig {
+    fn default() -> Self {
+        Self {
+            name: "Command".to_string(),
+            command: String::new(),
+            interval_ms: 5000,
+            json_path: None,
+            unit: String::new(),
+            max: 100.0,
+        }
+    }
+}
+
+/// Another machine sampled over `ssh` and shown in its own Vitals panel.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct RemoteHost {
+    /// Panel title.
+    pub name: String,
+    /// Destination passed to `ssh`, so aliases from `~/.ssh/config` work.
+    pub host: String,
+    pub interval_ms: u64,
+    /// Remote command printing one JSON sample per line. Defaults to running
+    /// `vitray-widget stats` in NDJSON watch mode on the host.
+    pub command: Option<String>,
+    /// Open the host's panel on startup.
+    pub pinned: bool,
+}
+
+impl RemoteHost {
+    pub fn command(&self) -> String {
+        self.command.clone().unwrap_or_else(|| {
+            format!(
+                "vitray-widget stats --format ndjson --watch --interval {}",
+                self.interval_ms.max(250)
+            )
+        })
+    }
+}
+
+impl Default for RemoteHost {
+    fn default() -> Self {
+        Self {
+            name: "Build server".to_string(),
+            host: String::new(),
+            interval_ms: 2000,
+            command: None,
+            pinned: true,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CursorShape {
+    #[default]
+    Block,
+    IBeam,
+    Underline,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CursorBlink {
+    /// Follows the desktop's cursor blink setting.
+    #[default]
+    System,
+    On,
+    Off,
+}
+
+/// What a terminal does when a program rings the bell.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BellMode {
+    #[default]
+    Audible,
+    /// Briefly dims the terminal instead of beeping.
+    Visual,
+    Off,
+}
+
+/// Tango, as in GNOME Terminal; where a custom palette starts out in the settings editor.
+pub const TANGO_PALETTE: [&str; 16] = [
+    "#2e3436", "#cc0000", "#4e9a06", "#c4a000", "#3465a4", "#75507b", "#06989a", "#d3d7cf",
+    "#555753", "#ef2929", "#8ae234", "#fce94f", "#729fcf", "#ad7fa8", "#34e2e2", "#eeeeec",
+];
+
+/// Named look and behaviour for terminal tabs. Colours are anything GDK can parse, such as
+/// `#rrggbb` or `rgba(0, 0, 0, 0.5)`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct TerminalProfile {
+    pub name: String,
+    /// Pango font description, e.g. `Monospace 11`; `None` keeps the stylesheet's font.
+    pub font: Option<String>,
+    /// `None` keeps the terminal's default colour.
+    pub foreground: Option<String>,
+    pub background: Option<String>,
+    /// Colours 0-15; empty, or any other length, keeps the terminal's built-in palette.
+    pub palette: Vec<String>,
+    /// Defaults to VTE's own 512 lines, what tabs had before profiles existed.
+    pub scrollback_lines: u32,
+    pub cursor_shape: CursorShape,
+    pub cursor_blink: CursorBlink,
+    pub bell: BellMode,
+}
+
+impl Default for TerminalProfile {
+    fn default() -> Self {
+        Self {
+            name: "Default".to_string(),
+            font: None,
+            foreground: None,
+            background: None,
+            palette: Vec::new(),
+            scrollback_lines: 512,
+            cursor_shape: CursorShape::default(),
+            cursor_blink: CursorBlink::default(),
+            bell: BellMode::default(),
+        }
+    }
+}
+
+impl TerminalProfile {
+    /// The profile named `name`, else the one named `default`, else the first; built-in
+    /// defaults when `profiles` is empty.
+    pub fn pick(profiles: &[Self], default: &str, name: Option<&str>) -> Self {
+        let find = |name: &str| profiles.iter().find(|p| p.name == name);
+        name.and_then(find)
+            .or_else(|| find(default))
+            .or_else(|| profiles.first())
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+/// Reading an alert rule watches.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub enum AlertMetric {
+    CpuUsage,
+    RamUsage,
+    SwapUsage,
+    GpuUsage,
+    /// Free space on the emptiest mount shown on the Disk card.
+    DiskFree,
+    /// Hottest temperature sensor.
+    Temperature,
+    NetworkRate,
+    BatteryCharge,
+    /// A command sensor, by name.
+    Command(String),
+}
+
+impl AlertMetric {
+    pub fn label(&self) -> String {
+        match self {
+            Self::CpuUsage => "CPU usage".to_string(),
+            Self::RamUsage => "RAM usage".to_string(),
+            Self::SwapUsage => "Swap usage".to_string(),
+            Self::GpuUsage => "GPU usage".to_string(),
+            Self::DiskFree => "Disk free".to_string(),
+            Self::Temperature => "Temperature".to_string(),
+            Self::NetworkRate => "Network".to_string(),
+            Self::BatteryCharge => "Battery".to_string(),
+            Self::Command(name) => name.clone(),
+        }
+    }
+
+    pub const fn unit(&self) -> &'static str {
+        match self {
+            Self::CpuUsage
+            | Self::RamUsage
+            | Self::SwapUsage
+            | Self::GpuUsage
+            | Self::BatteryCharge => "%",
+            Self::DiskFree => " GB",
+            Self::Temperature => "°C",
+            Self::NetworkRate => " KB/s",
+            Self::Command(_) => "",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum AlertComparison {
+    Above,
+    Below,
+}
+
+/// Fires when `metric` stays past `threshold` for `duration_secs`, and clears once it comes
+/// back by more than `hysteresis`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct AlertRule {
+    pub enabled: bool,
+    pub metric: AlertMetric,
+    pub comparison: AlertComparison,
+    pub threshold: f64,
+    pub duration_secs: u64,
+    pub hysteresis: f64,
+}
+
+impl Default for AlertRule {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            metric: AlertMetric::CpuUsage,
+            comparison: AlertComparison::Above,
+            threshold: 90.0,
+            duration_secs: 30,
+            hysteresis: 5.0,
+        }
+    }
+}
+
+fn default_alert_rules() -> Vec<AlertRule> {
+    vec![
+        AlertRule::default(),
+        AlertRule {
+            metric: AlertMetric::DiskFree,
+            comparison: AlertComparison::Below,
+            threshold: 5.0,
+            duration_secs: 0,
+            hysteresis: 1.0,
+            ..AlertRule::default()
+        },
+    ]
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(default)]
 #[allow(clippy::struct_excessive_bools)]
@@ -76,12 +547,46 @@ pub struct Settings {
     pub show_gpu: bool,
     pub show_ram: bool,
     pub show_network: bool,
+    pub network_filter: InterfaceFilter,
+    /// Interface shown on the Network card; `None` shows the filtered aggregate.
+    pub network_interface: Option<String>,
+    pub show_disk: bool,
+    /// Mount points summarised on the Disk card; empty shows every mount.
+    pub disk_mounts: Vec<String>,
+    pub show_thermals: bool,
+    pub show_processes: bool,
+    pub show_battery: bool,
+    pub show_cgroups: bool,
+    /// cgroup v2 mount read for the containers and units panel.
+    pub cgroup_root: String,
+    /// Rows shown in the top processes panel.
+    pub process_count: usize,
+    /// Rows shown in the containers and units panel.
+    pub cgroup_count: usize,
+    pub refresh_interval_ms: u64,
+    pub sensor_intervals: SensorIntervals,
+    pub command_sensors: Vec<CommandSensorConfig>,
+    pub remote_hosts: Vec<RemoteHost>,
+    pub alert_rules: Vec<AlertRule>,
+    pub card_levels: CardLevels,
+    /// Serve Prometheus metrics on `127.0.0.1:<port>/metrics`; `None` disables the exporter.
+    pub metrics_port: Option<u16>,
+    pub recording: RecordingSettings,
     pub monitor_style: MonitorStyle,
+    pub chart_range: ChartRange,
+    pub chart_scale: ChartScale,
+    /// Draw y-axis gridlines and labels on charts.
+    pub chart_axes: bool,
     pub launch_at_start: bool,
     pub lock_in_place: bool,
     pub lock_size: bool,
     #[serde(default = "default_shell")]
     pub shell: String,
+    /// Reopen the terminal tabs, their directories and startup commands on launch.
+    pub restore_terminal_session: bool,
+    pub terminal_profiles: Vec<TerminalProfile>,
+    /// Profile for new tabs and for tabs whose profile was removed.
+    pub default_terminal_profile: String,
 
     // New fields for detachable sections
     pub terminal_style: SectionStyle,
@@ -108,11 +613,36 @@ impl Default for Settings {
             show_gpu: true,
             show_ram: true,
             show_network: true,
+            network_filter: InterfaceFilter::default(),
+            network_interface: None,
+            show_disk: true,
+            disk_mounts: vec!["/".to_string()],
+            show_thermals: true,
+            show_processes: true,
+            show_cgroups: true,
+            cgroup_root: "/sys/fs/cgroup".to_string(),
+            show_battery: true,
+            process_count: 8,
+            cgroup_count: 8,
+            refresh_interval_ms: 1000,
+            sensor_intervals: SensorIntervals::default(),
+            command_sensors: Vec::new(),
+            remote_hosts: Vec::new(),
+            alert_rules: default_alert_rules(),
+            card_levels: CardLevels::default(),
+            metrics_port: None,
+            recording: RecordingSettings::default(),
             monitor_style: MonitorStyle::Text,
+            chart_range: ChartRange::default(),
+            chart_scale: ChartScale::default(),
+            chart_axes: true,
             launch_at_start: false,
             lock_in_place: true,
             lock_size: true,
             shell: default_shell(),
+            restore_terminal_session: true,
+            terminal_profiles: vec![TerminalProfile::default()],
+            default_terminal_profile: "Default".to_string(),
 
             terminal_style: SectionStyle {
                 font_family: "Monospace".to_string(),
@@ -184,3 +714,69 @@ impl Settings {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob_matches_literals_and_wildcards() {
+        assert!(glob_match("lo", "lo"));
+        assert!(!glob_match("lo", "lo0"));
+        assert!(glob_match("docker*", "docker0"));
+        assert!(glob_match("docker*", "docker"));
+        assert!(glob_match("*", ""));
+        assert!(glob_match("veth?", "veth0"));
+        assert!(!glob_match("veth?", "veth"));
+        assert!(!glob_match("veth?", "veth10"));
+        assert!(glob_match("wl*0", "wlp3s0"));
+        assert!(!glob_match("wl*0", "wlp3s1"));
+        assert!(!glob_match("", "eth0"));
+    }
+
+    #[test]
+    fn glob_star_backtracks() {
+        assert!(glob_match("*s0", "enp0s31s0"));
+        assert!(glob_match("*-*-*", "br-1-2-3"));
+        assert!(glob_match("a*b*c", "aXbYbZc"));
+        assert!(!glob_match("a*b*c", "aXbYbZ"));
+        assert!(glob_match("**?", "x"));
+        assert!(glob_match("tün*", "tün0"));
+    }
+
+    #[test]
+    fn interface_filter_applies_include_then_exclude() {
+        let mut filter = InterfaceFilter::default();
+        assert!(filter.matches("eth0"));
+        assert!(!filter.matches("lo"));
+        assert!(!filter.matches("veth12ab"));
+
+        filter.include = vec!["wl*".to_string(), "eth?".to_string()];
+        filter.exclude = vec!["wlx*".to_string()];
+        assert!(filter.matches("wlp3s0"));
+        assert!(filter.matches("eth1"));
+        assert!(!filter.matches("wlx00c0ca"));
+        assert!(!filter.matches("enp0s31f6"));
+    }
+
+    #[test]
+    fn picks_the_named_then_default_then_first_profile() {
+        let profile = |name: &str| TerminalProfile {
+            name: name.to_string(),
+            ..TerminalProfile::default()
+        };
+        let profiles = [profile("Work"), profile("Solarized"), profile("Night")];
+        let pick = |default: &str, name: Option<&str>| {
+            TerminalProfile::pick(&profiles, default, name).name
+        };
+
+        assert_eq!(pick("Night", Some("Solarized")), "Solarized");
+        assert_eq!(pick("Night", Some("Removed")), "Night");
+        assert_eq!(pick("Night", None), "Night");
+        assert_eq!(pick("Removed", Some("Also removed")), "Work");
+        assert_eq!(
+            TerminalProfile::pick(&[], "Night", Some("Work")),
+            TerminalProfile::default()
+        );
+    }
+}
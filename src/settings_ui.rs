@@ -4,7 +4,11 @@ use gtk4::{
 };
 use std::{cell::RefCell, rc::Rc};
 
-use crate::settings::{MonitorStyle, Settings, Theme};
+use crate::settings::{
+    AlertComparison, AlertMetric, AlertRule, BellMode, CardLevels, ChartScale,
+    CommandSensorConfig, CursorBlink, CursorShape, LevelThresholds, MonitorStyle, RecordFormat,
+    RecordingSettings, RemoteHost, Settings, TerminalProfile, Theme, TANGO_PALETTE,
+};
 use font_kit::source::SystemSource;
 
 #[allow(clippy::too_many_lines)]
@@ -69,10 +73,12 @@ pub fn show_settings_window(
     style_combo.append_text("Just numbers");
     style_combo.append_text("Bar");
     style_combo.append_text("Chart");
+    style_combo.append_text("Per-core heatmap");
     style_combo.set_active(Some(match settings_snapshot.monitor_style {
         MonitorStyle::Text => 0,
         MonitorStyle::Bar => 1,
         MonitorStyle::Chart => 2,
+        MonitorStyle::Cores => 3,
     }));
     style_box.append(&style_combo);
     
@@ -84,6 +90,22 @@ pub fn show_settings_window(
     style_box.append(&demo_chart);
     
     style_group.append(&style_box);
+
+    let chart_box = Box::new(Orientation::Horizontal, 10);
+    let scale_combo = ComboBoxText::new();
+    scale_combo.append_text("Auto scale");
+    scale_combo.append_text("Fixed scale");
+    scale_combo.set_active(Some(match settings_snapshot.chart_scale {
+        ChartScale::Auto => 0,
+        ChartScale::Fixed => 1,
+    }));
+    scale_combo.set_tooltip_text(Some(
+        "Fixed scale tops each chart at the card's maximum, e.g. 100% for CPU",
+    ));
+    chart_box.append(&scale_combo);
+    let axes_toggle = create_toggle("Chart gridlines", settings_snapshot.chart_axes);
+    chart_box.append(&axes_toggle.0);
+    style_group.append(&chart_box);
     main_box.append(&style_group);
 
     // Sections
@@ -110,14 +132,198 @@ pub fn show_settings_window(
     let gpu_box = create_toggle("Show GPU", settings_snapshot.show_gpu);
     let ram_box = create_toggle("Show RAM", settings_snapshot.show_ram);
     let net_box = create_toggle("Show Network", settings_snapshot.show_network);
+    let disk_box = create_toggle("Show Disk", settings_snapshot.show_disk);
+    let thermals_box = create_toggle("Show Thermals", settings_snapshot.show_thermals);
+    let processes_box = create_toggle("Show top processes", settings_snapshot.show_processes);
+    let cgroups_box = create_toggle("Show containers & units", settings_snapshot.show_cgroups);
+    let battery_box = create_toggle("Show Battery", settings_snapshot.show_battery);
     
     sensors_box.append(&cpu_box.0);
     sensors_box.append(&gpu_box.0);
     sensors_box.append(&ram_box.0);
     sensors_box.append(&net_box.0);
+
+    let iface_grid = gtk4::Grid::new();
+    iface_grid.set_column_spacing(10);
+    iface_grid.set_row_spacing(6);
+
+    iface_grid.attach(&Label::new(Some("Network card")), 0, 0, 1, 1);
+    let iface_combo = ComboBoxText::new();
+    iface_combo.append(None, "All interfaces");
+    let mut iface_names: Vec<String> = sysinfo::Networks::new_with_refreshed_list()
+        .keys()
+        .cloned()
+        .collect();
+    if let Some(selected) = &settings_snapshot.network_interface {
+        if !iface_names.contains(selected) {
+            iface_names.push(selected.clone());
+        }
+    }
+    iface_names.sort();
+    for name in &iface_names {
+        iface_combo.append(Some(name), name);
+    }
+    iface_combo.set_active(Some(0));
+    if let Some(selected) = &settings_snapshot.network_interface {
+        iface_combo.set_active_id(Some(selected));
+    }
+    iface_combo.set_hexpand(true);
+    iface_grid.attach(&iface_combo, 1, 0, 1, 1);
+
+    iface_grid.attach(&Label::new(Some("Include")), 0, 1, 1, 1);
+    let iface_include = gtk4::Entry::new();
+    iface_include.set_placeholder_text(Some("all, e.g. eth*, wlan0"));
+    iface_include.set_text(&settings_snapshot.network_filter.include.join(", "));
+    iface_grid.attach(&iface_include, 1, 1, 1, 1);
+
+    iface_grid.attach(&Label::new(Some("Exclude")), 0, 2, 1, 1);
+    let iface_exclude = gtk4::Entry::new();
+    iface_exclude.set_placeholder_text(Some("none, e.g. docker*, veth*"));
+    iface_exclude.set_text(&settings_snapshot.network_filter.exclude.join(", "));
+    iface_grid.attach(&iface_exclude, 1, 2, 1, 1);
+
+    sensors_box.append(&iface_grid);
+    sensors_box.append(&thermals_box.0);
+    sensors_box.append(&battery_box.0);
+    sensors_box.append(&processes_box.0);
+
+    let process_count_box = Box::new(Orientation::Horizontal, 10);
+    process_count_box.append(&Label::new(Some("Processes listed")));
+    let process_count = gtk4::SpinButton::with_range(1.0, 25.0, 1.0);
+    #[allow(clippy::cast_precision_loss)]
+    process_count.set_value(settings_snapshot.process_count as f64);
+    process_count.set_hexpand(true);
+    process_count_box.append(&process_count);
+    sensors_box.append(&process_count_box);
+    sensors_box.append(&cgroups_box.0);
+
+    let cgroup_root_box = Box::new(Orientation::Horizontal, 10);
+    cgroup_root_box.append(&Label::new(Some("cgroup v2 root")));
+    let cgroup_root = gtk4::Entry::new();
+    cgroup_root.set_text(&settings_snapshot.cgroup_root);
+    cgroup_root.set_placeholder_text(Some("/sys/fs/cgroup"));
+    cgroup_root.set_hexpand(true);
+    cgroup_root_box.append(&cgroup_root);
+    sensors_box.append(&cgroup_root_box);
+
+    let cgroup_count_box = Box::new(Orientation::Horizontal, 10);
+    cgroup_count_box.append(&Label::new(Some("Containers & units listed")));
+    let cgroup_count = gtk4::SpinButton::with_range(1.0, 50.0, 1.0);
+    #[allow(clippy::cast_precision_loss)]
+    cgroup_count.set_value(settings_snapshot.cgroup_count as f64);
+    cgroup_count.set_hexpand(true);
+    cgroup_count_box.append(&cgroup_count);
+    sensors_box.append(&cgroup_count_box);
+    sensors_box.append(&disk_box.0);
+
+    let mounts_label = Label::new(Some("Disk card mounts (none checked shows all)"));
+    mounts_label.set_halign(Align::Start);
+    sensors_box.append(&mounts_label);
+    let mut mount_points: Vec<String> = sysinfo::Disks::new_with_refreshed_list()
+        .list()
+        .iter()
+        .map(|d| d.mount_point().to_string_lossy().into_owned())
+        .collect();
+    mount_points.extend(settings_snapshot.disk_mounts.iter().cloned());
+    mount_points.sort();
+    mount_points.dedup();
+    let mount_checks: Vec<gtk4::CheckButton> = mount_points
+        .iter()
+        .map(|mount| {
+            let check = gtk4::CheckButton::with_label(mount);
+            check.set_active(settings_snapshot.disk_mounts.contains(mount));
+            sensors_box.append(&check);
+            check
+        })
+        .collect();
+
+    let sampling_label = Label::new(Some("Sampling (ms, 0 follows the global interval)"));
+    sampling_label.set_halign(Align::Start);
+    sensors_box.append(&sampling_label);
+    let sampling_grid = gtk4::Grid::new();
+    sampling_grid.set_column_spacing(10);
+    sampling_grid.set_row_spacing(6);
+    let interval_spin = |row: i32, label: &str, min: f64, value: Option<u64>| {
+        sampling_grid.attach(&Label::new(Some(label)), 0, row, 1, 1);
+        let spin = gtk4::SpinButton::with_range(min, 60_000.0, 250.0);
+        #[allow(clippy::cast_precision_loss)]
+        spin.set_value(value.unwrap_or(0) as f64);
+        spin.set_hexpand(true);
+        sampling_grid.attach(&spin, 1, row, 1, 1);
+        spin
+    };
+    let overrides = &settings_snapshot.sensor_intervals;
+    let refresh_spin = interval_spin(
+        0,
+        "Every sensor",
+        250.0,
+        Some(settings_snapshot.refresh_interval_ms),
+    );
+    let cpu_interval = interval_spin(1, "CPU", 0.0, overrides.cpu_ms);
+    let memory_interval = interval_spin(2, "Memory", 0.0, overrides.memory_ms);
+    let network_interval = interval_spin(3, "Network", 0.0, overrides.network_ms);
+    let disk_interval = interval_spin(4, "Disk", 0.0, overrides.disk_ms);
+    let thermals_interval = interval_spin(5, "Thermals", 0.0, overrides.thermals_ms);
+    let gpu_interval = interval_spin(6, "GPU", 0.0, overrides.gpu_ms);
+    let processes_interval = interval_spin(7, "Processes", 0.0, overrides.processes_ms);
+    let power_interval = interval_spin(8, "Battery", 0.0, overrides.power_ms);
+    let cgroups_interval = interval_spin(9, "Containers", 0.0, overrides.cgroups_ms);
+    sensors_box.append(&sampling_grid);
+
     sensors_expander.set_child(Some(&sensors_box));
     main_box.append(&sensors_expander);
 
+    let levels_expander = gtk4::Expander::new(Some("Status levels"));
+    let levels_grid = gtk4::Grid::new();
+    levels_grid.set_column_spacing(10);
+    levels_grid.set_row_spacing(6);
+    for (column, heading) in (0..).zip(["Card", "Warning", "Critical"]) {
+        let label = Label::new(Some(heading));
+        label.set_halign(Align::Start);
+        levels_grid.attach(&label, column, 0, 1, 1);
+    }
+    let card_levels = &settings_snapshot.card_levels;
+    let level_rows: Vec<LevelRow> = [
+        ("CPU %", card_levels.cpu, 100.0),
+        ("GPU %", card_levels.gpu, 100.0),
+        ("RAM %", card_levels.ram, 100.0),
+        ("Network KB/s", card_levels.network, 10_000_000.0),
+        ("Disk used %", card_levels.disk, 100.0),
+        ("Thermals °C", card_levels.thermals, 150.0),
+        ("Battery %", card_levels.battery, 100.0),
+    ]
+    .into_iter()
+    .zip(1..)
+    .map(|((label, thresholds, max), row)| LevelRow::new(&levels_grid, row, label, thresholds, max))
+    .collect();
+    let levels_hint = Label::new(Some(
+        "Set critical below warning when lower values are worse, as for battery.",
+    ));
+    levels_hint.add_css_class("card-detail");
+    levels_hint.set_wrap(true);
+    levels_grid.attach(&levels_hint, 0, 8, 3, 1);
+    levels_expander.set_child(Some(&levels_grid));
+    main_box.append(&levels_expander);
+
+    let commands_expander = gtk4::Expander::new(Some("Command sensors"));
+    let (commands_editor, command_rows) =
+        build_command_sensor_editor(&settings_snapshot.command_sensors);
+    commands_expander.set_child(Some(&commands_editor));
+    main_box.append(&commands_expander);
+
+    let remote_expander = gtk4::Expander::new(Some("Remote hosts"));
+    let (remote_editor, remote_rows) = build_remote_host_editor(&settings_snapshot.remote_hosts);
+    remote_expander.set_child(Some(&remote_editor));
+    main_box.append(&remote_expander);
+
+    let profiles_expander = gtk4::Expander::new(Some("Terminal profiles"));
+    let (profiles_editor, profile_rows) = build_terminal_profile_editor(
+        &settings_snapshot.terminal_profiles,
+        &settings_snapshot.default_terminal_profile,
+    );
+    profiles_expander.set_child(Some(&profiles_editor));
+    main_box.append(&profiles_expander);
+
     let system_expander = gtk4::Expander::new(Some("System"));
     let system_box = Box::new(Orientation::Vertical, 6);
     let auto_start_box = create_toggle("Launch at start", settings_snapshot.launch_at_start);
@@ -135,6 +341,62 @@ pub fn show_settings_window(
     system_box.append(&lock_place_box.0);
     system_box.append(&lock_size_box.0);
     system_box.append(&shell_box);
+
+    let restore_session_box = create_toggle(
+        "Restore terminal tabs on launch",
+        settings_snapshot.restore_terminal_session,
+    );
+    system_box.append(&restore_session_box.0);
+
+    let metrics_box = Box::new(Orientation::Horizontal, 10);
+    metrics_box.append(&Label::new(Some("Prometheus port (0 = off)")));
+    let metrics_port = gtk4::SpinButton::with_range(0.0, 65535.0, 1.0);
+    metrics_port.set_value(f64::from(settings_snapshot.metrics_port.unwrap_or(0)));
+    metrics_port.set_tooltip_text(Some("Serves /metrics on 127.0.0.1 only"));
+    metrics_port.set_hexpand(true);
+    metrics_box.append(&metrics_port);
+    system_box.append(&metrics_box);
+
+    let recording_label = Label::new(Some("Recording (started from a card's right-click menu)"));
+    recording_label.set_halign(Align::Start);
+    system_box.append(&recording_label);
+    let recording_grid = gtk4::Grid::new();
+    recording_grid.set_column_spacing(10);
+    recording_grid.set_row_spacing(6);
+    recording_grid.attach(&Label::new(Some("Format")), 0, 0, 1, 1);
+    let record_format = ComboBoxText::new();
+    record_format.append_text("CSV");
+    record_format.append_text("NDJSON");
+    record_format.set_active(Some(match settings_snapshot.recording.format {
+        RecordFormat::Csv => 0,
+        RecordFormat::Ndjson => 1,
+    }));
+    recording_grid.attach(&record_format, 1, 0, 1, 1);
+    recording_grid.attach(&Label::new(Some("Folder")), 0, 1, 1, 1);
+    let record_dir = gtk4::Entry::new();
+    record_dir.set_text(
+        settings_snapshot
+            .recording
+            .directory
+            .as_deref()
+            .unwrap_or_default(),
+    );
+    if let Some(default_dir) = RecordingSettings::default().directory() {
+        record_dir.set_placeholder_text(Some(&default_dir.to_string_lossy()));
+    }
+    record_dir.set_hexpand(true);
+    recording_grid.attach(&record_dir, 1, 1, 1, 1);
+    recording_grid.attach(&Label::new(Some("Rotate at (MB)")), 0, 2, 1, 1);
+    let record_max_mb = gtk4::SpinButton::with_range(1.0, 10_000.0, 1.0);
+    #[allow(clippy::cast_precision_loss)]
+    record_max_mb.set_value(settings_snapshot.recording.max_file_mb as f64);
+    recording_grid.attach(&record_max_mb, 1, 2, 1, 1);
+    recording_grid.attach(&Label::new(Some("Rotated files kept")), 0, 3, 1, 1);
+    let record_keep = gtk4::SpinButton::with_range(0.0, 100.0, 1.0);
+    #[allow(clippy::cast_precision_loss)]
+    record_keep.set_value(settings_snapshot.recording.keep_files as f64);
+    recording_grid.attach(&record_keep, 1, 3, 1, 1);
+    system_box.append(&recording_grid);
     system_expander.set_child(Some(&system_box));
     main_box.append(&system_expander);
 
@@ -241,6 +503,19 @@ fn get_system_fonts() -> Vec<String> {
         create_section_controls("Shortcuts", &settings_snapshot.shortcuts_style);
     styling_box.append(&sc_box);
 
+    // Alerts Tab (Also captured by the save closure)
+    let (alerts_box, alert_rows) = build_alert_editor(
+        &settings_snapshot.alert_rules,
+        settings_snapshot
+            .command_sensors
+            .iter()
+            .map(|c| c.name.clone())
+            .collect(),
+    );
+    let alerts_scroll = gtk4::ScrolledWindow::new();
+    alerts_scroll.set_child(Some(&alerts_box));
+    alerts_scroll.set_vexpand(true);
+
     // Save Button
     // TODO(senior-ui): Swap to Apply/Reset buttons with undo to encourage experimentation.
     // Save / Reset Buttons
@@ -280,8 +555,14 @@ fn get_system_fonts() -> Vec<String> {
         new_settings.monitor_style = match style_combo.active().unwrap_or(0) {
             1 => MonitorStyle::Bar,
             2 => MonitorStyle::Chart,
+            3 => MonitorStyle::Cores,
             _ => MonitorStyle::Text,
         };
+        new_settings.chart_scale = match scale_combo.active() {
+            Some(1) => ChartScale::Fixed,
+            _ => ChartScale::Auto,
+        };
+        new_settings.chart_axes = axes_toggle.1.is_active();
 
         new_settings.show_terminal = terminal_toggle.1.is_active();
         new_settings.show_monitoring = monitoring_toggle.1.is_active();
@@ -290,6 +571,86 @@ fn get_system_fonts() -> Vec<String> {
         new_settings.show_gpu = gpu_box.1.is_active();
         new_settings.show_ram = ram_box.1.is_active();
         new_settings.show_network = net_box.1.is_active();
+        new_settings.network_interface = iface_combo.active_id().map(|id| id.to_string());
+        new_settings.network_filter.include = split_patterns(&iface_include.text());
+        new_settings.network_filter.exclude = split_patterns(&iface_exclude.text());
+        new_settings.show_disk = disk_box.1.is_active();
+        new_settings.show_thermals = thermals_box.1.is_active();
+        new_settings.show_processes = processes_box.1.is_active();
+        new_settings.show_cgroups = cgroups_box.1.is_active();
+        let root = cgroup_root.text().trim().to_string();
+        if !root.is_empty() {
+            new_settings.cgroup_root = root;
+        }
+        new_settings.show_battery = battery_box.1.is_active();
+        if let [cpu, gpu, ram, network, disk, thermals, battery] = level_rows.as_slice() {
+            new_settings.card_levels = CardLevels {
+                cpu: cpu.thresholds(),
+                gpu: gpu.thresholds(),
+                ram: ram.thresholds(),
+                network: network.thresholds(),
+                disk: disk.thresholds(),
+                thermals: thermals.thresholds(),
+                battery: battery.thresholds(),
+            };
+        }
+        new_settings.process_count = usize::try_from(process_count.value_as_int()).unwrap_or(1);
+        new_settings.cgroup_count = usize::try_from(cgroup_count.value_as_int()).unwrap_or(1);
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let spin_ms = |spin: &gtk4::SpinButton| Some(spin.value() as u64).filter(|ms| *ms > 0);
+        new_settings.refresh_interval_ms = spin_ms(&refresh_spin).unwrap_or(1000);
+        new_settings.sensor_intervals.cpu_ms = spin_ms(&cpu_interval);
+        new_settings.sensor_intervals.memory_ms = spin_ms(&memory_interval);
+        new_settings.sensor_intervals.network_ms = spin_ms(&network_interval);
+        new_settings.sensor_intervals.disk_ms = spin_ms(&disk_interval);
+        new_settings.sensor_intervals.thermals_ms = spin_ms(&thermals_interval);
+        new_settings.sensor_intervals.gpu_ms = spin_ms(&gpu_interval);
+        new_settings.sensor_intervals.processes_ms = spin_ms(&processes_interval);
+        new_settings.sensor_intervals.power_ms = spin_ms(&power_interval);
+        new_settings.sensor_intervals.cgroups_ms = spin_ms(&cgroups_interval);
+        // Readings are matched to cards by name, so the first sensor with a name wins.
+        let mut sensor_names = std::collections::HashSet::new();
+        new_settings.command_sensors = command_rows
+            .borrow()
+            .iter()
+            .map(CommandSensorRow::config)
+            .filter(|c| !c.name.is_empty() && !c.command.trim().is_empty())
+            .filter(|c| sensor_names.insert(c.name.clone()))
+            .collect();
+        // Panels are tracked by name too.
+        let mut host_names = std::collections::HashSet::new();
+        new_settings.remote_hosts = remote_rows
+            .borrow()
+            .iter()
+            .map(RemoteHostRow::host)
+            .filter(|h| !h.name.is_empty() && !h.host.is_empty())
+            .filter(|h| host_names.insert(h.name.clone()))
+            .collect();
+        let mut profile_names = std::collections::HashSet::new();
+        let profiles: Vec<(TerminalProfile, bool)> = profile_rows
+            .borrow()
+            .iter()
+            .map(|row| (row.profile(), row.default.is_active()))
+            .filter(|(p, _)| !p.name.is_empty() && profile_names.insert(p.name.clone()))
+            .collect();
+        new_settings.default_terminal_profile = profiles
+            .iter()
+            .find(|(_, is_default)| *is_default)
+            .or_else(|| profiles.first())
+            .map(|(p, _)| p.name.clone())
+            .unwrap_or_default();
+        new_settings.terminal_profiles = profiles.into_iter().map(|(p, _)| p).collect();
+        new_settings.alert_rules = alert_rows
+            .borrow()
+            .iter()
+            .filter_map(AlertRuleRow::rule)
+            .collect();
+        new_settings.disk_mounts = mount_checks
+            .iter()
+            .filter(|c| c.is_active())
+            .filter_map(|c| c.label().map(|l| l.to_string()))
+            .collect();
 
         let launch = auto_start_box.1.is_active();
         new_settings.set_auto_launch(launch);
@@ -297,6 +658,19 @@ fn get_system_fonts() -> Vec<String> {
         new_settings.lock_in_place = lock_place_box.1.is_active();
         new_settings.lock_size = lock_size_box.1.is_active();
         new_settings.shell = shell_entry.text().to_string();
+        new_settings.restore_terminal_session = restore_session_box.1.is_active();
+        new_settings.metrics_port =
+            u16::try_from(metrics_port.value_as_int()).ok().filter(|port| *port > 0);
+        new_settings.recording.format = match record_format.active() {
+            Some(1) => RecordFormat::Ndjson,
+            _ => RecordFormat::Csv,
+        };
+        let record_dir = record_dir.text().trim().to_string();
+        new_settings.recording.directory = Some(record_dir).filter(|dir| !dir.is_empty());
+        new_settings.recording.max_file_mb =
+            u64::try_from(record_max_mb.value_as_int()).unwrap_or(1);
+        new_settings.recording.keep_files =
+            usize::try_from(record_keep.value_as_int()).unwrap_or(0);
 
         // Styling
         new_settings.terminal_style.opacity = term_op.value();
@@ -327,6 +701,8 @@ fn get_system_fonts() -> Vec<String> {
 
     notebook.append_page(&styling_scroll, Some(&Label::new(Some("Styling"))));
 
+    notebook.append_page(&alerts_scroll, Some(&Label::new(Some("Alerts"))));
+
     // Help tab
     let help_box = Box::new(Orientation::Vertical, 10);
     help_box.set_margin_top(16);
@@ -367,3 +743,767 @@ fn create_toggle(label: &str, active: bool) -> (Box, Switch) {
     hbox.append(&switch);
     (hbox, switch)
 }
+
+fn split_patterns(text: &str) -> Vec<String> {
+    text.split(',')
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(ToString::to_string)
+        .collect()
+}
+
+/// Editable fields for one command sensor.
+#[derive(Clone)]
+struct CommandSensorRow {
+    widget: Box,
+    name: gtk4::Entry,
+    command: gtk4::Entry,
+    interval: gtk4::SpinButton,
+    unit: gtk4::Entry,
+    json_path: gtk4::Entry,
+    max: gtk4::SpinButton,
+}
+
+impl CommandSensorRow {
+    fn new(config: &CommandSensorConfig) -> Self {
+        let widget = Box::new(Orientation::Vertical, 6);
+        let grid = gtk4::Grid::new();
+        grid.set_column_spacing(10);
+        grid.set_row_spacing(6);
+
+        let entry = |row: i32, label: &str, text: &str, placeholder: &str| {
+            grid.attach(&Label::new(Some(label)), 0, row, 1, 1);
+            let entry = gtk4::Entry::new();
+            entry.set_text(text);
+            entry.set_placeholder_text(Some(placeholder));
+            entry.set_hexpand(true);
+            grid.attach(&entry, 1, row, 1, 1);
+            entry
+        };
+        let name = entry(0, "Name", &config.name, "Card title");
+        let command = entry(1, "Command", &config.command, "e.g. ls ~/queue | wc -l");
+        let unit = entry(2, "Unit", &config.unit, "optional");
+        let json_path = entry(
+            3,
+            "JSON path",
+            config.json_path.as_deref().unwrap_or_default(),
+            "value",
+        );
+
+        grid.attach(&Label::new(Some("Every (ms)")), 0, 4, 1, 1);
+        let interval = gtk4::SpinButton::with_range(250.0, 3_600_000.0, 250.0);
+        #[allow(clippy::cast_precision_loss)]
+        interval.set_value(config.interval_ms as f64);
+        grid.attach(&interval, 1, 4, 1, 1);
+
+        grid.attach(&Label::new(Some("Scale max")), 0, 5, 1, 1);
+        let max = gtk4::SpinButton::with_range(1.0, 1e12, 1.0);
+        max.set_value(config.max);
+        grid.attach(&max, 1, 5, 1, 1);
+
+        widget.append(&grid);
+        Self {
+            widget,
+            name,
+            command,
+            interval,
+            unit,
+            json_path,
+            max,
+        }
+    }
+
+    fn config(&self) -> CommandSensorConfig {
+        let json_path = self.json_path.text().trim().to_string();
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        CommandSensorConfig {
+            name: self.name.text().trim().to_string(),
+            command: self.command.text().to_string(),
+            interval_ms: self.interval.value() as u64,
+            json_path: Some(json_path).filter(|p| !p.is_empty()),
+            unit: self.unit.text().trim().to_string(),
+            max: self.max.value(),
+        }
+    }
+}
+
+/// Enable toggle plus warning/critical spins for one card's status levels.
+struct LevelRow {
+    enabled: gtk4::CheckButton,
+    warning: gtk4::SpinButton,
+    critical: gtk4::SpinButton,
+}
+
+impl LevelRow {
+    fn new(
+        grid: &gtk4::Grid,
+        row: i32,
+        label: &str,
+        thresholds: Option<LevelThresholds>,
+        max: f64,
+    ) -> Self {
+        let enabled = gtk4::CheckButton::with_label(label);
+        enabled.set_active(thresholds.is_some());
+        let defaults = thresholds.unwrap_or_else(|| LevelThresholds::new(max * 0.7, max * 0.9));
+        let warning = gtk4::SpinButton::with_range(0.0, max, 1.0);
+        warning.set_value(defaults.warning);
+        let critical = gtk4::SpinButton::with_range(0.0, max, 1.0);
+        critical.set_value(defaults.critical);
+        for spin in [&warning, &critical] {
+            enabled
+                .bind_property("active", spin, "sensitive")
+                .sync_create()
+                .build();
+        }
+        grid.attach(&enabled, 0, row, 1, 1);
+        grid.attach(&warning, 1, row, 1, 1);
+        grid.attach(&critical, 2, row, 1, 1);
+        Self {
+            enabled,
+            warning,
+            critical,
+        }
+    }
+
+    fn thresholds(&self) -> Option<LevelThresholds> {
+        self.enabled
+            .is_active()
+            .then(|| LevelThresholds::new(self.warning.value(), self.critical.value()))
+    }
+}
+
+/// List of command sensors with add and remove buttons. The rows are read back on Apply.
+fn build_command_sensor_editor(
+    configs: &[CommandSensorConfig],
+) -> (Box, Rc<RefCell<Vec<CommandSensorRow>>>) {
+    let editor = Box::new(Orientation::Vertical, 8);
+    let list = Box::new(Orientation::Vertical, 8);
+    let rows: Rc<RefCell<Vec<CommandSensorRow>>> = Rc::new(RefCell::new(Vec::new()));
+
+    let add_row = {
+        let list = list.clone();
+        let rows = rows.clone();
+        move |config: &CommandSensorConfig| {
+            let row = CommandSensorRow::new(config);
+            let remove = Button::with_label("Remove");
+            remove.add_css_class("danger");
+            remove.set_halign(Align::End);
+            row.widget.append(&remove);
+            row.widget.append(&gtk4::Separator::new(Orientation::Horizontal));
+
+            let list_clone = list.clone();
+            let rows_clone = rows.clone();
+            let widget = row.widget.clone();
+            remove.connect_clicked(move |_| {
+                list_clone.remove(&widget);
+                rows_clone.borrow_mut().retain(|r| r.widget != widget);
+            });
+
+            list.append(&row.widget);
+            rows.borrow_mut().push(row);
+        }
+    };
+    for config in configs {
+        add_row(config);
+    }
+
+    let hint = Label::new(Some(
+        "Each command prints a number, or JSON with a `value` field (and optional `text`).",
+    ));
+    hint.set_wrap(true);
+    hint.set_halign(Align::Start);
+    hint.add_css_class("card-title");
+
+    let add_btn = Button::with_label("Add sensor");
+    add_btn.add_css_class("pill-btn");
+    add_btn.set_halign(Align::Start);
+    add_btn.connect_clicked(move |_| add_row(&CommandSensorConfig::default()));
+
+    editor.append(&hint);
+    editor.append(&list);
+    editor.append(&add_btn);
+    (editor, rows)
+}
+
+/// Editable fields for one remote host.
+#[derive(Clone)]
+struct RemoteHostRow {
+    widget: Box,
+    name: gtk4::Entry,
+    host: gtk4::Entry,
+    command: gtk4::Entry,
+    interval: gtk4::SpinButton,
+    pinned: gtk4::CheckButton,
+}
+
+impl RemoteHostRow {
+    fn new(config: &RemoteHost) -> Self {
+        let widget = Box::new(Orientation::Vertical, 6);
+        let grid = gtk4::Grid::new();
+        grid.set_column_spacing(10);
+        grid.set_row_spacing(6);
+
+        let entry = |row: i32, label: &str, text: &str, placeholder: &str| {
+            grid.attach(&Label::new(Some(label)), 0, row, 1, 1);
+            let entry = gtk4::Entry::new();
+            entry.set_text(text);
+            entry.set_placeholder_text(Some(placeholder));
+            entry.set_hexpand(true);
+            grid.attach(&entry, 1, row, 1, 1);
+            entry
+        };
+        let name = entry(0, "Name", &config.name, "Panel title");
+        let host = entry(1, "Host", &config.host, "user@server or ssh config alias");
+        let command = entry(
+            2,
+            "Command",
+            config.command.as_deref().unwrap_or_default(),
+            "vitray-widget stats --format ndjson --watch",
+        );
+
+        grid.attach(&Label::new(Some("Every (ms)")), 0, 3, 1, 1);
+        let interval = gtk4::SpinButton::with_range(250.0, 60_000.0, 250.0);
+        #[allow(clippy::cast_precision_loss)]
+        interval.set_value(config.interval_ms as f64);
+        grid.attach(&interval, 1, 3, 1, 1);
+
+        let pinned = gtk4::CheckButton::with_label("Pin panel");
+        pinned.set_active(config.pinned);
+        grid.attach(&pinned, 1, 4, 1, 1);
+
+        widget.append(&grid);
+        Self {
+            widget,
+            name,
+            host,
+            command,
+            interval,
+            pinned,
+        }
+    }
+
+    fn host(&self) -> RemoteHost {
+        let command = self.command.text().trim().to_string();
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        RemoteHost {
+            name: self.name.text().trim().to_string(),
+            host: self.host.text().trim().to_string(),
+            interval_ms: self.interval.value() as u64,
+            command: Some(command).filter(|c| !c.is_empty()),
+            pinned: self.pinned.is_active(),
+        }
+    }
+}
+
+/// List of remote hosts with add and remove buttons, read back on Apply.
+fn build_remote_host_editor(hosts: &[RemoteHost]) -> (Box, Rc<RefCell<Vec<RemoteHostRow>>>) {
+    let editor = Box::new(Orientation::Vertical, 8);
+    let list = Box::new(Orientation::Vertical, 8);
+    let rows: Rc<RefCell<Vec<RemoteHostRow>>> = Rc::new(RefCell::new(Vec::new()));
+
+    let add_row = {
+        let list = list.clone();
+        let rows = rows.clone();
+        move |config: &RemoteHost| {
+            let row = RemoteHostRow::new(config);
+            let remove = Button::with_label("Remove");
+            remove.add_css_class("danger");
+            remove.set_halign(Align::End);
+            row.widget.append(&remove);
+            row.widget.append(&gtk4::Separator::new(Orientation::Horizontal));
+
+            let list_clone = list.clone();
+            let rows_clone = rows.clone();
+            let widget = row.widget.clone();
+            remove.connect_clicked(move |_| {
+                list_clone.remove(&widget);
+                rows_clone.borrow_mut().retain(|r| r.widget != widget);
+            });
+
+            list.append(&row.widget);
+            rows.borrow_mut().push(row);
+        }
+    };
+    for host in hosts {
+        add_row(host);
+    }
+
+    let hint = Label::new(Some(
+        "Sampled over ssh with your ssh config and keys; the host needs vitray-widget installed \
+         unless a command printing one JSON sample per line is given.",
+    ));
+    hint.set_wrap(true);
+    hint.set_halign(Align::Start);
+    hint.add_css_class("card-title");
+
+    let add_btn = Button::with_label("Add host");
+    add_btn.add_css_class("pill-btn");
+    add_btn.set_halign(Align::Start);
+    add_btn.connect_clicked(move |_| add_row(&RemoteHost::default()));
+
+    editor.append(&hint);
+    editor.append(&list);
+    editor.append(&add_btn);
+    (editor, rows)
+}
+
+struct TerminalProfileRow {
+    widget: Box,
+    name: gtk4::Entry,
+    font: gtk4::Entry,
+    foreground: gtk4::Entry,
+    background: gtk4::Entry,
+    builtin_palette: gtk4::CheckButton,
+    palette: Vec<gtk4::ColorButton>,
+    scrollback: gtk4::SpinButton,
+    cursor_shape: ComboBoxText,
+    cursor_blink: ComboBoxText,
+    bell: ComboBoxText,
+    default: gtk4::CheckButton,
+}
+
+impl TerminalProfileRow {
+    fn new(profile: &TerminalProfile, is_default: bool, group: Option<&gtk4::CheckButton>) -> Self {
+        let widget = Box::new(Orientation::Vertical, 6);
+        let grid = gtk4::Grid::new();
+        grid.set_column_spacing(10);
+        grid.set_row_spacing(6);
+
+        let entry = |row: i32, label: &str, text: Option<&str>, placeholder: &str| {
+            grid.attach(&Label::new(Some(label)), 0, row, 1, 1);
+            let entry = gtk4::Entry::new();
+            entry.set_text(text.unwrap_or_default());
+            entry.set_placeholder_text(Some(placeholder));
+            entry.set_hexpand(true);
+            grid.attach(&entry, 1, row, 1, 1);
+            entry
+        };
+        let name = entry(0, "Name", Some(&profile.name), "Profile name");
+        let font = entry(
+            1,
+            "Font",
+            profile.font.as_deref(),
+            "e.g. Monospace 11 (empty: theme font)",
+        );
+        let foreground = entry(
+            2,
+            "Foreground",
+            profile.foreground.as_deref(),
+            "#rrggbb (empty: default)",
+        );
+        let background = entry(
+            3,
+            "Background",
+            profile.background.as_deref(),
+            "#rrggbb or rgba(…) (empty: default)",
+        );
+
+        grid.attach(&Label::new(Some("Palette")), 0, 4, 1, 1);
+        let (palette_box, builtin_palette, palette) = build_palette_editor(&profile.palette);
+        grid.attach(&palette_box, 1, 4, 1, 1);
+
+        grid.attach(&Label::new(Some("Scrollback lines")), 0, 5, 1, 1);
+        let scrollback = gtk4::SpinButton::with_range(0.0, 1_000_000.0, 1000.0);
+        scrollback.set_value(f64::from(profile.scrollback_lines));
+        grid.attach(&scrollback, 1, 5, 1, 1);
+
+        let combo = |row: i32, label: &str, options: &[&str], active: u32| {
+            grid.attach(&Label::new(Some(label)), 0, row, 1, 1);
+            let combo = ComboBoxText::new();
+            for option in options {
+                combo.append_text(option);
+            }
+            combo.set_active(Some(active));
+            grid.attach(&combo, 1, row, 1, 1);
+            combo
+        };
+        let cursor_shape = combo(
+            6,
+            "Cursor",
+            &["Block", "I-beam", "Underline"],
+            match profile.cursor_shape {
+                CursorShape::Block => 0,
+                CursorShape::IBeam => 1,
+                CursorShape::Underline => 2,
+            },
+        );
+        let cursor_blink = combo(
+            7,
+            "Cursor blink",
+            &["Follow system", "Always", "Never"],
+            match profile.cursor_blink {
+                CursorBlink::System => 0,
+                CursorBlink::On => 1,
+                CursorBlink::Off => 2,
+            },
+        );
+        let bell = combo(
+            8,
+            "Bell",
+            &["Sound", "Flash", "Off"],
+            match profile.bell {
+                BellMode::Audible => 0,
+                BellMode::Visual => 1,
+                BellMode::Off => 2,
+            },
+        );
+
+        let default = gtk4::CheckButton::with_label("Default for new tabs");
+        default.set_group(group);
+        default.set_active(is_default);
+        grid.attach(&default, 1, 9, 1, 1);
+
+        widget.append(&grid);
+        Self {
+            widget,
+            name,
+            font,
+            foreground,
+            background,
+            builtin_palette,
+            palette,
+            scrollback,
+            cursor_shape,
+            cursor_blink,
+            bell,
+            default,
+        }
+    }
+
+    fn profile(&self) -> TerminalProfile {
+        let text = |entry: &gtk4::Entry| {
+            Some(entry.text().trim().to_string()).filter(|t| !t.is_empty())
+        };
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        TerminalProfile {
+            name: self.name.text().trim().to_string(),
+            font: text(&self.font),
+            foreground: text(&self.foreground),
+            background: text(&self.background),
+            palette: if self.builtin_palette.is_active() {
+                Vec::new()
+            } else {
+                self.palette.iter().map(|b| hex(&b.rgba())).collect()
+            },
+            scrollback_lines: self.scrollback.value() as u32,
+            cursor_shape: match self.cursor_shape.active() {
+                Some(1) => CursorShape::IBeam,
+                Some(2) => CursorShape::Underline,
+                _ => CursorShape::Block,
+            },
+            cursor_blink: match self.cursor_blink.active() {
+                Some(1) => CursorBlink::On,
+                Some(2) => CursorBlink::Off,
+                _ => CursorBlink::System,
+            },
+            bell: match self.bell.active() {
+                Some(1) => BellMode::Visual,
+                Some(2) => BellMode::Off,
+                _ => BellMode::Audible,
+            },
+        }
+    }
+}
+
+/// The palette buttons under a "Use terminal default" check that greys them out; the check
+/// starts on unless `palette` holds 16 usable colours.
+fn build_palette_editor(palette: &[String]) -> (Box, gtk4::CheckButton, Vec<gtk4::ColorButton>) {
+    let (grid, buttons, custom) = build_palette_buttons(palette);
+    let builtin = gtk4::CheckButton::with_label("Use terminal default");
+    builtin.set_active(!custom);
+    grid.set_sensitive(custom);
+    {
+        let grid = grid.clone();
+        builtin.connect_toggled(move |check| grid.set_sensitive(!check.is_active()));
+    }
+    let editor = Box::new(Orientation::Vertical, 4);
+    editor.append(&builtin);
+    editor.append(&grid);
+    (editor, builtin, buttons)
+}
+
+/// Two rows of eight colour buttons and whether they show `palette`; one that is not 16 valid
+/// colours, such as the empty built-in choice, starts the buttons from Tango instead.
+fn build_palette_buttons(palette: &[String]) -> (gtk4::Grid, Vec<gtk4::ColorButton>, bool) {
+    let parse = |colours: &[String]| -> Vec<_> {
+        colours
+            .iter()
+            .filter_map(|c| gtk4::gdk::RGBA::parse(c).ok())
+            .collect()
+    };
+    let mut colours = parse(palette);
+    let custom = colours.len() == 16;
+    if !custom {
+        colours = TANGO_PALETTE
+            .iter()
+            .filter_map(|c| gtk4::gdk::RGBA::parse(*c).ok())
+            .collect();
+    }
+
+    let grid = gtk4::Grid::new();
+    grid.set_column_spacing(2);
+    grid.set_row_spacing(2);
+    let buttons: Vec<_> = colours.iter().map(gtk4::ColorButton::with_rgba).collect();
+    for (index, button) in (0..).zip(&buttons) {
+        grid.attach(button, index % 8, index / 8, 1, 1);
+    }
+    (grid, buttons, custom)
+}
+
+/// `#rrggbb`, the form palettes are written in by hand.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn hex(rgba: &gtk4::gdk::RGBA) -> String {
+    let byte = |c: f32| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+    format!("#{:02x}{:02x}{:02x}", byte(rgba.red()), byte(rgba.green()), byte(rgba.blue()))
+}
+
+/// List of terminal profiles with add and remove buttons, read back on Apply.
+fn build_terminal_profile_editor(
+    profiles: &[TerminalProfile],
+    default: &str,
+) -> (Box, Rc<RefCell<Vec<TerminalProfileRow>>>) {
+    let editor = Box::new(Orientation::Vertical, 8);
+    let list = Box::new(Orientation::Vertical, 8);
+    let rows: Rc<RefCell<Vec<TerminalProfileRow>>> = Rc::new(RefCell::new(Vec::new()));
+    // Radio group for the default checks; its first member outlives removed rows.
+    let group: Rc<RefCell<Option<gtk4::CheckButton>>> = Rc::new(RefCell::new(None));
+
+    let add_row = {
+        let list = list.clone();
+        let rows = rows.clone();
+        move |profile: &TerminalProfile, is_default: bool| {
+            let row = TerminalProfileRow::new(profile, is_default, group.borrow().as_ref());
+            group.borrow_mut().get_or_insert_with(|| row.default.clone());
+            let remove = Button::with_label("Remove");
+            remove.add_css_class("danger");
+            remove.set_halign(Align::End);
+            row.widget.append(&remove);
+            row.widget.append(&gtk4::Separator::new(Orientation::Horizontal));
+
+            let list_clone = list.clone();
+            let rows_clone = rows.clone();
+            let widget = row.widget.clone();
+            remove.connect_clicked(move |_| {
+                list_clone.remove(&widget);
+                rows_clone.borrow_mut().retain(|r| r.widget != widget);
+            });
+
+            list.append(&row.widget);
+            rows.borrow_mut().push(row);
+        }
+    };
+    for profile in profiles {
+        add_row(profile, profile.name == default);
+    }
+
+    let hint = Label::new(Some(
+        "Pick a tab's profile by double-clicking the tab. Tabs whose profile is removed use \
+         the default one.",
+    ));
+    hint.set_wrap(true);
+    hint.set_halign(Align::Start);
+    hint.add_css_class("card-title");
+
+    let add_btn = Button::with_label("Add profile");
+    add_btn.add_css_class("pill-btn");
+    add_btn.set_halign(Align::Start);
+    add_btn.connect_clicked(move |_| {
+        let profile = TerminalProfile {
+            name: "New profile".to_string(),
+            ..TerminalProfile::default()
+        };
+        add_row(&profile, false);
+    });
+
+    editor.append(&hint);
+    editor.append(&list);
+    editor.append(&add_btn);
+    (editor, rows)
+}
+
+const ALERT_METRICS: [(&str, AlertMetric); 8] = [
+    ("cpu", AlertMetric::CpuUsage),
+    ("ram", AlertMetric::RamUsage),
+    ("swap", AlertMetric::SwapUsage),
+    ("gpu", AlertMetric::GpuUsage),
+    ("disk", AlertMetric::DiskFree),
+    ("temperature", AlertMetric::Temperature),
+    ("network", AlertMetric::NetworkRate),
+    ("battery", AlertMetric::BatteryCharge),
+];
+
+/// Combo box id for a metric; command sensors are keyed by name.
+fn alert_metric_id(metric: &AlertMetric) -> String {
+    match metric {
+        AlertMetric::Command(name) => format!("command:{name}"),
+        _ => ALERT_METRICS
+            .iter()
+            .find(|(_, m)| m == metric)
+            .map_or_else(String::new, |(id, _)| (*id).to_string()),
+    }
+}
+
+fn alert_metric_from_id(id: &str) -> Option<AlertMetric> {
+    if let Some(name) = id.strip_prefix("command:") {
+        return Some(AlertMetric::Command(name.to_string()));
+    }
+    ALERT_METRICS
+        .iter()
+        .find(|(key, _)| *key == id)
+        .map(|(_, metric)| metric.clone())
+}
+
+/// Editable fields for one alert rule.
+#[derive(Clone)]
+struct AlertRuleRow {
+    widget: Box,
+    enabled: Switch,
+    metric: ComboBoxText,
+    comparison: ComboBoxText,
+    threshold: gtk4::SpinButton,
+    duration: gtk4::SpinButton,
+    hysteresis: gtk4::SpinButton,
+}
+
+impl AlertRuleRow {
+    fn new(rule: &AlertRule, command_names: &[String]) -> Self {
+        let widget = Box::new(Orientation::Vertical, 6);
+        let grid = gtk4::Grid::new();
+        grid.set_column_spacing(10);
+        grid.set_row_spacing(6);
+
+        grid.attach(&Label::new(Some("Enabled")), 0, 0, 1, 1);
+        let enabled = Switch::new();
+        enabled.set_active(rule.enabled);
+        enabled.set_halign(Align::Start);
+        grid.attach(&enabled, 1, 0, 1, 1);
+
+        grid.attach(&Label::new(Some("Metric")), 0, 1, 1, 1);
+        let metric = ComboBoxText::new();
+        for (id, m) in &ALERT_METRICS {
+            metric.append(Some(id), &m.label());
+        }
+        let mut commands = command_names.to_vec();
+        if let AlertMetric::Command(name) = &rule.metric {
+            if !commands.contains(name) {
+                commands.push(name.clone());
+            }
+        }
+        for name in &commands {
+            metric.append(Some(&format!("command:{name}")), name);
+        }
+        metric.set_active_id(Some(&alert_metric_id(&rule.metric)));
+        metric.set_hexpand(true);
+        grid.attach(&metric, 1, 1, 1, 1);
+
+        grid.attach(&Label::new(Some("When")), 0, 2, 1, 1);
+        let comparison = ComboBoxText::new();
+        comparison.append(Some("above"), "Above");
+        comparison.append(Some("below"), "Below");
+        comparison.set_active_id(Some(match rule.comparison {
+            AlertComparison::Above => "above",
+            AlertComparison::Below => "below",
+        }));
+        grid.attach(&comparison, 1, 2, 1, 1);
+
+        let spin = |row: i32, label: &str, max: f64, step: f64, value: f64| {
+            grid.attach(&Label::new(Some(label)), 0, row, 1, 1);
+            let spin = gtk4::SpinButton::with_range(0.0, max, step);
+            spin.set_digits(u32::from(step < 1.0));
+            spin.set_value(value);
+            grid.attach(&spin, 1, row, 1, 1);
+            spin
+        };
+        let threshold = spin(3, "Threshold", 1e12, 0.5, rule.threshold);
+        #[allow(clippy::cast_precision_loss)]
+        let duration = spin(4, "For (s)", 86_400.0, 1.0, rule.duration_secs as f64);
+        let hysteresis = spin(5, "Hysteresis", 1e12, 0.5, rule.hysteresis);
+
+        widget.append(&grid);
+        Self {
+            widget,
+            enabled,
+            metric,
+            comparison,
+            threshold,
+            duration,
+            hysteresis,
+        }
+    }
+
+    fn rule(&self) -> Option<AlertRule> {
+        let metric = alert_metric_from_id(&self.metric.active_id()?)?;
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        Some(AlertRule {
+            enabled: self.enabled.is_active(),
+            metric,
+            comparison: if self.comparison.active_id().as_deref() == Some("below") {
+                AlertComparison::Below
+            } else {
+                AlertComparison::Above
+            },
+            threshold: self.threshold.value(),
+            duration_secs: self.duration.value() as u64,
+            hysteresis: self.hysteresis.value(),
+        })
+    }
+}
+
+/// List of alert rules with add and remove buttons. The rows are read back on Apply.
+fn build_alert_editor(
+    rules: &[AlertRule],
+    command_names: Vec<String>,
+) -> (Box, Rc<RefCell<Vec<AlertRuleRow>>>) {
+    let editor = Box::new(Orientation::Vertical, 8);
+    editor.set_margin_top(16);
+    editor.set_margin_bottom(16);
+    editor.set_margin_start(16);
+    editor.set_margin_end(16);
+    let list = Box::new(Orientation::Vertical, 8);
+    let rows: Rc<RefCell<Vec<AlertRuleRow>>> = Rc::new(RefCell::new(Vec::new()));
+
+    let add_row = {
+        let list = list.clone();
+        let rows = rows.clone();
+        move |rule: &AlertRule| {
+            let row = AlertRuleRow::new(rule, &command_names);
+            let remove = Button::with_label("Remove");
+            remove.add_css_class("danger");
+            remove.set_halign(Align::End);
+            row.widget.append(&remove);
+            row.widget.append(&gtk4::Separator::new(Orientation::Horizontal));
+
+            let list_clone = list.clone();
+            let rows_clone = rows.clone();
+            let widget = row.widget.clone();
+            remove.connect_clicked(move |_| {
+                list_clone.remove(&widget);
+                rows_clone.borrow_mut().retain(|r| r.widget != widget);
+            });
+
+            list.append(&row.widget);
+            rows.borrow_mut().push(row);
+        }
+    };
+    for rule in rules {
+        add_row(rule);
+    }
+
+    let hint = Label::new(Some(
+        "A rule fires once the metric stays past its threshold for the given time, and clears \
+         when it comes back by more than the hysteresis. Units follow the card: %, GB, °C, KB/s.",
+    ));
+    hint.set_wrap(true);
+    hint.set_halign(Align::Start);
+    hint.add_css_class("card-title");
+
+    let add_btn = Button::with_label("Add rule");
+    add_btn.add_css_class("pill-btn");
+    add_btn.set_halign(Align::Start);
+    add_btn.connect_clicked(move |_| add_row(&AlertRule::default()));
+
+    editor.append(&hint);
+    editor.append(&list);
+    editor.append(&add_btn);
+    (editor, rows)
+}
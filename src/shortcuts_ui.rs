@@ -1,12 +1,12 @@
-use async_channel::Sender;
 use gtk4::prelude::*;
 use gtk4::{
-    Align, ApplicationWindow, Box, Button, Dialog, Entry, Label, ListBox, ListBoxRow, Orientation,
-    Overlay, Popover, Revealer, RevealerTransitionType,
+    glib, Align, ApplicationWindow, Box, Button, Dialog, Entry, Label, ListBox, ListBoxRow,
+    Orientation, Overlay, Popover, Revealer, RevealerTransitionType,
 };
 use std::{cell::RefCell, rc::Rc};
 
 use crate::shortcuts::{Shortcut, Shortcuts};
+use crate::terminal::{RunTarget, TerminalTabs};
 
 #[derive(Clone)]
 pub struct ShortcutsPanel {
@@ -14,11 +14,11 @@ pub struct ShortcutsPanel {
     list: ListBox,
     data: Rc<RefCell<Shortcuts>>,
     parent: ApplicationWindow,
-    sender: Sender<String>,
+    tabs: TerminalTabs,
 }
 
 impl ShortcutsPanel {
-    pub fn new(parent: &ApplicationWindow, sender: Sender<String>) -> Self {
+    pub fn new(parent: &ApplicationWindow, tabs: TerminalTabs) -> Self {
         let revealer = Revealer::new();
         revealer.set_transition_type(RevealerTransitionType::SlideLeft);
         revealer.set_reveal_child(true);
@@ -78,7 +78,7 @@ impl ShortcutsPanel {
             list,
             data,
             parent: parent.clone(),
-            sender,
+            tabs,
         };
 
         {
@@ -107,8 +107,8 @@ impl ShortcutsPanel {
         }
     }
 
-    pub fn run_shortcut(&self, command: String) {
-        let _ = self.sender.try_send(command);
+    pub fn run_shortcut(&self, command: &str, target: RunTarget) {
+        self.tabs.run(command, target);
     }
 }
 
@@ -154,12 +154,17 @@ fn build_row(panel: ShortcutsPanel, shortcut: Shortcut) -> ListBoxRow {
     edit_btn.add_css_class("icon-btn");
     edit_btn.set_tooltip_text(Some("Edit shortcut"));
 
+    let more_btn = Button::from_icon_name("view-more-symbolic");
+    more_btn.add_css_class("icon-btn");
+    more_btn.set_tooltip_text(Some("Run in another tab"));
+
     let delete_btn = Button::from_icon_name("window-close-symbolic");
     delete_btn.add_css_class("icon-btn");
     delete_btn.set_tooltip_text(Some("Delete shortcut"));
 
     actions.append(&edit_btn);
     actions.append(&use_btn);
+    actions.append(&more_btn);
     actions.append(&delete_btn);
 
     content.append(&title);
@@ -174,8 +179,17 @@ fn build_row(panel: ShortcutsPanel, shortcut: Shortcut) -> ListBoxRow {
     let shortcut_command = shortcut.command.clone();
     {
         let panel_clone = panel.clone();
+        let command = shortcut_command.clone();
         use_btn.connect_clicked(move |_| {
-            panel_clone.run_shortcut(shortcut_command.clone());
+            panel_clone.run_shortcut(&command, RunTarget::Active);
+        });
+    }
+
+    {
+        let panel_clone = panel.clone();
+        more_btn.connect_clicked(move |btn| {
+            let popover = build_run_popover(&panel_clone, &shortcut_command, btn);
+            popover.popup();
         });
     }
 
@@ -197,6 +211,78 @@ fn build_row(panel: ShortcutsPanel, shortcut: Shortcut) -> ListBoxRow {
     row
 }
 
+fn build_run_popover(panel: &ShortcutsPanel, command: &str, anchor: &Button) -> Popover {
+    let popover = Popover::builder().has_arrow(true).build();
+    popover.set_parent(anchor);
+
+    let column = Box::new(Orientation::Vertical, 6);
+    column.set_margin_top(8);
+    column.set_margin_bottom(8);
+    column.set_margin_start(8);
+    column.set_margin_end(8);
+
+    let choices = Box::new(Orientation::Vertical, 6);
+    let new_tab = Button::with_label("Run in new tab");
+    new_tab.add_css_class("flat");
+    let pick_tab = Button::with_label("Run in tab…");
+    pick_tab.add_css_class("flat");
+    choices.append(&new_tab);
+    choices.append(&pick_tab);
+    column.append(&choices);
+
+    {
+        let pop = popover.clone();
+        let panel_clone = panel.clone();
+        let command = command.to_string();
+        new_tab.connect_clicked(move |_| {
+            panel_clone.run_shortcut(&command, RunTarget::NewTab);
+            pop.popdown();
+        });
+    }
+
+    {
+        let pop = popover.clone();
+        let panel_clone = panel.clone();
+        let command = command.to_string();
+        let column_clone = column.clone();
+        pick_tab.connect_clicked(move |_| {
+            column_clone.remove(&choices);
+            column_clone.append(&build_tab_choices(&panel_clone, &pop, &command));
+        });
+    }
+
+    popover.set_child(Some(&column));
+    popover.connect_closed(|pop| {
+        let pop = pop.clone();
+        glib::idle_add_local_once(move || pop.unparent());
+    });
+    popover
+}
+
+/// One button per open tab, in notebook order.
+fn build_tab_choices(panel: &ShortcutsPanel, popover: &Popover, command: &str) -> Box {
+    let column = Box::new(Orientation::Vertical, 6);
+
+    let prompt = Label::new(Some("Run in which tab?"));
+    prompt.add_css_class("popover-label");
+    column.append(&prompt);
+
+    for (index, title) in (0u32..).zip(panel.tabs.titles()) {
+        let btn = Button::with_label(&title);
+        btn.add_css_class("flat");
+        let pop = popover.clone();
+        let panel_clone = panel.clone();
+        let command = command.to_string();
+        btn.connect_clicked(move |_| {
+            panel_clone.run_shortcut(&command, RunTarget::Tab(index));
+            pop.popdown();
+        });
+        column.append(&btn);
+    }
+
+    column
+}
+
 fn build_delete_popover(panel: &ShortcutsPanel, name: String, anchor: &Button) -> Popover {
     let popover = Popover::builder().has_arrow(true).build();
     popover.set_parent(anchor);
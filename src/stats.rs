@@ -0,0 +1,458 @@
+use clap::ValueEnum;
+use serde::Serialize;
+use std::fmt::Write as _;
+use std::io::{self, Write};
+use std::thread;
+use std::time::Duration;
+
+use crate::history::now_secs;
+use crate::monitor::{format_rate, MonitorData, SamplingConfig, SystemMonitor};
+use crate::settings::Settings;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum StatsFormat {
+    /// Human-readable summary.
+    #[default]
+    Table,
+    /// One JSON document: an object for a single sample, an array otherwise.
+    Json,
+    /// One compact JSON object per line, flushed as each sample is taken.
+    Ndjson,
+}
+
+/// Options for `vitray stats`.
+#[derive(clap::Args, Debug)]
+pub struct StatsArgs {
+    #[arg(long, short, value_enum, default_value_t = StatsFormat::Table)]
+    pub format: StatsFormat,
+
+    /// Keep sampling until interrupted (or until --samples have been printed).
+    #[arg(long, short)]
+    pub watch: bool,
+
+    /// Milliseconds between samples; rates are averaged over this period.
+    #[arg(
+        long,
+        short,
+        default_value_t = 1000,
+        value_parser = clap::value_parser!(u64).range(250..)
+    )]
+    pub interval: u64,
+
+    /// Number of samples to print; defaults to 1, or unlimited with --watch (except for JSON,
+    /// which needs a count to know when the document is complete).
+    #[arg(long, short = 'n', value_parser = clap::value_parser!(u64).range(1..))]
+    pub samples: Option<u64>,
+}
+
+/// A sample as printed: the snapshot plus when it was taken.
+#[derive(Serialize)]
+pub struct TimedSample<'a> {
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+    #[serde(flatten)]
+    pub data: &'a MonitorData,
+}
+
+/// Samples the system without starting GTK and writes the results to stdout. Returns the
+/// process exit code.
+pub fn run(args: &StatsArgs) -> i32 {
+    // A JSON document is only printed once sampling ends, which an open-ended watch never does.
+    if args.format == StatsFormat::Json && args.watch && args.samples.is_none() {
+        eprintln!("--format json needs --samples with --watch; use --format ndjson to stream");
+        return 2;
+    }
+    let settings = Settings::load();
+    let interval = Duration::from_millis(args.interval);
+    let mut monitor =
+        SystemMonitor::new(SamplingConfig::uniform(interval), &settings.command_sensors);
+    monitor.set_cgroup_root(settings.cgroup_root.into());
+    // CPU usage and every rate are deltas, so the first refresh only sets the baseline.
+    monitor.refresh();
+
+    let limit = args.samples.or(if args.watch { None } else { Some(1) });
+    let mut stdout = io::stdout().lock();
+    let mut collected = Vec::new();
+    let mut taken = 0;
+    while limit.is_none_or(|limit| taken < limit) {
+        thread::sleep(interval);
+        let data = monitor.refresh();
+        let timestamp = now_secs();
+        taken += 1;
+
+        let sample = TimedSample {
+            timestamp,
+            data: &data,
+        };
+        let written = write_sample(&mut stdout, args.format, taken == 1, &sample);
+        if args.format == StatsFormat::Json {
+            collected.push((timestamp, data));
+        }
+        if let Err(e) = written {
+            // A closed pipe (e.g. `| head`) is the reader's way of saying it has enough.
+            if e.kind() == io::ErrorKind::BrokenPipe {
+                return 0;
+            }
+            eprintln!("Failed to write stats: {e}");
+            return 1;
+        }
+    }
+
+    if args.format == StatsFormat::Json {
+        let samples: Vec<TimedSample> = collected
+            .iter()
+            .map(|(timestamp, data)| TimedSample {
+                timestamp: *timestamp,
+                data,
+            })
+            .collect();
+        if let Err(e) = write_document(&mut stdout, &samples) {
+            if e.kind() != io::ErrorKind::BrokenPipe {
+                eprintln!("Failed to write stats: {e}");
+                return 1;
+            }
+        }
+    }
+    0
+}
+
+/// Writes one sample as soon as it is taken. JSON samples are held back for
+/// [`write_document`], so nothing is written for them here.
+fn write_sample(
+    out: &mut impl Write,
+    format: StatsFormat,
+    first: bool,
+    sample: &TimedSample,
+) -> io::Result<()> {
+    match format {
+        StatsFormat::Table => {
+            let separator = if first { "" } else { "\n" };
+            let table = format_table(sample.timestamp, sample.data);
+            write!(out, "{separator}{table}")?;
+        }
+        StatsFormat::Ndjson => {
+            serde_json::to_writer(&mut *out, sample)?;
+            writeln!(out)?;
+        }
+        StatsFormat::Json => return Ok(()),
+    }
+    out.flush()
+}
+
+/// Writes the samples collected for `--format json`: a lone object, or an array of several.
+fn write_document(out: &mut impl Write, samples: &[TimedSample]) -> io::Result<()> {
+    let json = if let [single] = samples {
+        serde_json::to_string_pretty(single)?
+    } else {
+        serde_json::to_string_pretty(samples)?
+    };
+    writeln!(out, "{json}")
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn gib(bytes: u64) -> f64 {
+    bytes as f64 / 1024.0 / 1024.0 / 1024.0
+}
+
+/// Plain-text summary of one sample, one reading per line.
+fn format_table(timestamp: u64, data: &MonitorData) -> String {
+    let mut rows: Vec<(String, String)> = Vec::new();
+    let mut row = |name: &str, value: String| rows.push((name.to_string(), value));
+
+    row("time", timestamp.to_string());
+    row("cpu", format!("{:.1}%", data.cpu_usage));
+    if let Some(mhz) = data.average_frequency_mhz() {
+        row("cpu clock", format!("{mhz} MHz"));
+    }
+    row(
+        "load",
+        format!(
+            "{:.2} {:.2} {:.2}",
+            data.load_average.one, data.load_average.five, data.load_average.fifteen
+        ),
+    );
+    row(
+        "memory",
+        format!("{:.1}/{:.1} GB", gib(data.ram_used), gib(data.ram_total)),
+    );
+    row(
+        "swap",
+        format!("{:.1}/{:.1} GB", gib(data.swap_used), gib(data.swap_total)),
+    );
+    for iface in &data.interfaces {
+        row(
+            &format!("net {}", iface.name),
+            format!(
+                "↓{} ↑{}",
+                format_rate(iface.rx_bytes_per_sec),
+                format_rate(iface.tx_bytes_per_sec)
+            ),
+        );
+    }
+    for disk in &data.disk_io {
+        row(
+            &format!("io {}", disk.device),
+            format!(
+                "R {} W {}",
+                format_rate(disk.read_bytes_per_sec),
+                format_rate(disk.write_bytes_per_sec)
+            ),
+        );
+    }
+    for mount in &data.mounts {
+        row(
+            &format!("disk {}", mount.mount_point),
+            format!("{:.1}/{:.1} GB", gib(mount.used), gib(mount.total)),
+        );
+    }
+    for reading in &data.temperatures {
+        row(
+            &format!("temp {} {}", reading.chip, reading.label),
+            format!("{:.0}°C", reading.celsius),
+        );
+    }
+    for fan in &data.fans {
+        row(
+            &format!("fan {} {}", fan.chip, fan.label),
+            format!("{} RPM", fan.rpm),
+        );
+    }
+    for gpu in &data.gpus {
+        let busy = gpu
+            .busy_percent
+            .map_or_else(|| "—".to_string(), |busy| format!("{busy:.0}%"));
+        row(&format!("gpu {}", gpu.id), format!("{busy} {}", gpu.name));
+    }
+    for battery in &data.power.batteries {
+        row(
+            &format!("battery {}", battery.name),
+            format!("{:.0}% {}", battery.capacity_percent, battery.state.label()),
+        );
+    }
+    for reading in &data.commands {
+        let value = reading.value.map_or_else(
+            || reading.error.clone().unwrap_or_default(),
+            |value| value.to_string(),
+        );
+        row(&format!("command {}", reading.name), value);
+    }
+
+    let width = rows
+        .iter()
+        .map(|(name, _)| name.chars().count())
+        .max()
+        .unwrap_or(0);
+    let mut out = String::new();
+    for (name, value) in &rows {
+        let _ = writeln!(out, "{name:<width$}  {value}");
+    }
+    out
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::command_sensor::CommandReading;
+    use crate::gpu::GpuStats;
+    use crate::hwmon::{SensorKind, TemperatureReading};
+    use crate::monitor::{CoreStats, InterfaceStats, LoadAverage, MountStats};
+    use crate::power::{BatteryStats, ChargeState, PowerStatus};
+
+    const GIB: u64 = 1 << 30;
+
+    fn sample_data() -> MonitorData {
+        MonitorData {
+            cpu_usage: 12.5,
+            cores: vec![
+                CoreStats {
+                    usage: 20.0,
+                    frequency_mhz: 2400,
+                },
+                CoreStats {
+                    usage: 5.0,
+                    frequency_mhz: 0,
+                },
+            ],
+            ram_used: 3 * GIB,
+            ram_total: 16 * GIB,
+            swap_total: 2 * GIB,
+            load_average: LoadAverage {
+                one: 0.5,
+                five: 1.25,
+                fifteen: 2.0,
+            },
+            interfaces: vec![InterfaceStats {
+                name: "eth0".to_string(),
+                rx_bytes: 0,
+                tx_bytes: 0,
+                rx_bytes_per_sec: 1.5 * 1024.0 * 1024.0,
+                tx_bytes_per_sec: 512.0 * 1024.0,
+                rx_packets_per_sec: 0.0,
+                tx_packets_per_sec: 0.0,
+                errors_per_sec: 0.0,
+                rx_packets: 0,
+                tx_packets: 0,
+                rx_errors: 0,
+                tx_errors: 0,
+            }],
+            mounts: vec![MountStats {
+                mount_point: "/".to_string(),
+                device: "/dev/nvme0n1p2".to_string(),
+                used: 50 * GIB,
+                total: 100 * GIB,
+            }],
+            temperatures: vec![TemperatureReading {
+                chip: "coretemp".to_string(),
+                label: "Package id 0".to_string(),
+                kind: SensorKind::Cpu,
+                celsius: 54.6,
+                high: None,
+                critical: None,
+            }],
+            gpus: vec![GpuStats {
+                id: "card1".to_string(),
+                name: "AMD".to_string(),
+                driver: "amdgpu".to_string(),
+                busy_percent: None,
+                vram_used: None,
+                vram_total: None,
+                core_clock_mhz: None,
+                memory_clock_mhz: None,
+                temperature_c: None,
+                power_watts: None,
+            }],
+            power: PowerStatus {
+                on_ac: Some(false),
+                batteries: vec![BatteryStats {
+                    name: "BAT0".to_string(),
+                    capacity_percent: 81.0,
+                    state: ChargeState::Discharging,
+                    power_watts: None,
+                    time_to_empty_secs: None,
+                    time_to_full_secs: None,
+                }],
+            },
+            commands: vec![
+                CommandReading {
+                    name: "queue".to_string(),
+                    value: Some(7.0),
+                    ..CommandReading::default()
+                },
+                CommandReading {
+                    name: "broken".to_string(),
+                    error: Some("timed out after 10s".to_string()),
+                    ..CommandReading::default()
+                },
+            ],
+            ..MonitorData::default()
+        }
+    }
+
+    fn written(format: StatsFormat, samples: &[TimedSample]) -> String {
+        let mut out = Vec::new();
+        for (index, sample) in samples.iter().enumerate() {
+            write_sample(&mut out, format, index == 0, sample).unwrap();
+        }
+        if format == StatsFormat::Json {
+            write_document(&mut out, samples).unwrap();
+        }
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn formats_a_sample_as_an_aligned_table() {
+        assert_eq!(
+            format_table(1_700_000_000, &sample_data()),
+            "time                        1700000000\n\
+             cpu                         12.5%\n\
+             cpu clock                   2400 MHz\n\
+             load                        0.50 1.25 2.00\n\
+             memory                      3.0/16.0 GB\n\
+             swap                        0.0/2.0 GB\n\
+             net eth0                    ↓1.5 MB/s ↑512 KB/s\n\
+             disk /                      50.0/100.0 GB\n\
+             temp coretemp Package id 0  55°C\n\
+             gpu card1                   — AMD\n\
+             battery BAT0                81% Discharging\n\
+             command queue               7\n\
+             command broken              timed out after 10s\n"
+        );
+    }
+
+    #[test]
+    fn separates_watched_tables_with_a_blank_line() {
+        let data = MonitorData::default();
+        let samples = [1, 2].map(|timestamp| TimedSample {
+            timestamp,
+            data: &data,
+        });
+        let text = written(StatsFormat::Table, &samples);
+        let table = |timestamp| format_table(timestamp, &data);
+        assert_eq!(text, format!("{}\n{}", table(1), table(2)));
+        assert!(text.contains("swap    0.0/0.0 GB\n\ntime    2\n"), "{text}");
+    }
+
+    #[test]
+    fn writes_one_compact_object_per_ndjson_line() {
+        let data = sample_data();
+        let samples = [1_700_000_000, 1_700_000_001].map(|timestamp| TimedSample {
+            timestamp,
+            data: &data,
+        });
+        let text = written(StatsFormat::Ndjson, &samples);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with(r#"{"timestamp":1700000000,"cpu_usage":12.5,"cores":["#));
+        assert!(lines[1].starts_with(r#"{"timestamp":1700000001,"cpu_usage":12.5,"#));
+
+        let parsed: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(parsed["mounts"][0]["mount_point"], "/");
+        assert_eq!(parsed["power"]["batteries"][0]["state"], "Discharging");
+        assert_eq!(parsed["commands"][1]["error"], "timed out after 10s");
+        // The flattened sample reads back as the data it was taken from.
+        let data: MonitorData = serde_json::from_str(lines[1]).unwrap();
+        assert_eq!(data.interfaces[0].name, "eth0");
+    }
+
+    #[test]
+    fn writes_a_json_object_for_one_sample_and_an_array_for_more() {
+        let data = MonitorData::default();
+        let one = [TimedSample {
+            timestamp: 5,
+            data: &data,
+        }];
+        let text = written(StatsFormat::Json, &one);
+        assert!(
+            text.starts_with("{\n  \"timestamp\": 5,\n  \"cpu_usage\": 0.0,\n"),
+            "{text}"
+        );
+        assert!(text.ends_with("}\n"));
+
+        let two = [5, 6].map(|timestamp| TimedSample {
+            timestamp,
+            data: &data,
+        });
+        let parsed: serde_json::Value =
+            serde_json::from_str(&written(StatsFormat::Json, &two)).unwrap();
+        let timestamps: Vec<_> = parsed
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|sample| sample["timestamp"].as_u64().unwrap())
+            .collect();
+        assert_eq!(timestamps, [5, 6]);
+    }
+
+    #[test]
+    fn rejects_an_open_ended_json_watch() {
+        let args = StatsArgs {
+            format: StatsFormat::Json,
+            watch: true,
+            interval: 1000,
+            samples: None,
+        };
+        // Turned away before sampling starts, with the usage error status.
+        assert_eq!(run(&args), 2);
+    }
+}
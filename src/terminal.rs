@@ -1,8 +1,23 @@
 use gtk4::prelude::*;
-use gtk4::{glib, Align, Label};
+use gtk4::{
+    glib, Box, Button, CallbackAction, EventControllerFocus, GestureClick, Label, Notebook,
+    Orientation, Paned, PropagationPhase, ScrolledWindow, SearchBar, SearchEntry, Shortcut,
+    ShortcutController, ShortcutTrigger, ToggleButton,
+};
+use std::{
+    cell::{Cell, RefCell},
+    path::Path,
+    rc::{Rc, Weak},
+    time::Duration,
+};
 
+use crate::session::{PaneSession, SplitDirection, TabSession, TerminalSession};
+use crate::settings::{BellMode, CursorBlink, CursorShape, TerminalProfile};
+
+#[cfg(not(target_os = "linux"))]
+use gtk4::Align;
 #[cfg(target_os = "linux")]
-use vte4::{PtyFlags, Terminal, TerminalExtManual};
+use vte4::{PtyFlags, Terminal, TerminalExt, TerminalExtManual};
 
 #[derive(Clone)]
 pub enum AppTerminal {
@@ -30,30 +45,206 @@ impl AppTerminal {
             Self::Windows(_) => {},
         }
     }
+
+    /// Working directory last reported by the shell through OSC 7.
+    pub fn current_dir(&self) -> Option<String> {
+        match self {
+            #[cfg(target_os = "linux")]
+            Self::Linux(t) => t
+                .current_directory_uri()
+                .and_then(|uri| glib::filename_from_uri(&uri).ok())
+                .map(|(path, _)| path.to_string_lossy().into_owned()),
+            #[cfg(not(target_os = "linux"))]
+            Self::Windows(_) => None,
+        }
+    }
+
+    /// Applies a profile's font, colours, scrollback, cursor and bell. Colours and fonts that
+    /// fail to parse fall back to the terminal's defaults.
+    pub fn apply_profile(&self, profile: &TerminalProfile) {
+        match self {
+            #[cfg(target_os = "linux")]
+            Self::Linux(t) => {
+                let font = profile
+                    .font
+                    .as_deref()
+                    .filter(|font| !font.trim().is_empty())
+                    .map(gtk4::pango::FontDescription::from_string);
+                t.set_font(font.as_ref());
+
+                let colour = |c: Option<&str>| c.and_then(|c| gtk4::gdk::RGBA::parse(c).ok());
+                let mut palette: Vec<_> = profile
+                    .palette
+                    .iter()
+                    .filter_map(|c| colour(Some(c)))
+                    .collect();
+                if palette.len() != 16 {
+                    if !profile.palette.is_empty() {
+                        eprintln!(
+                            "Ignoring palette of terminal profile '{}': needs 16 valid colours",
+                            profile.name
+                        );
+                    }
+                    palette.clear();
+                }
+                let palette: Vec<_> = palette.iter().collect();
+                t.set_colors(
+                    colour(profile.foreground.as_deref()).as_ref(),
+                    colour(profile.background.as_deref()).as_ref(),
+                    &palette,
+                );
+
+                t.set_scrollback_lines(profile.scrollback_lines.into());
+                t.set_cursor_shape(match profile.cursor_shape {
+                    CursorShape::Block => vte4::CursorShape::Block,
+                    CursorShape::IBeam => vte4::CursorShape::Ibeam,
+                    CursorShape::Underline => vte4::CursorShape::Underline,
+                });
+                t.set_cursor_blink_mode(match profile.cursor_blink {
+                    CursorBlink::System => vte4::CursorBlinkMode::System,
+                    CursorBlink::On => vte4::CursorBlinkMode::On,
+                    CursorBlink::Off => vte4::CursorBlinkMode::Off,
+                });
+                t.set_audible_bell(profile.bell == BellMode::Audible);
+                // Read by the bell handler set up in `create_terminal`.
+                if profile.bell == BellMode::Visual {
+                    t.add_css_class("visual-bell");
+                } else {
+                    t.remove_css_class("visual-bell");
+                }
+            }
+            #[cfg(not(target_os = "linux"))]
+            Self::Windows(_) => {
+                let _ = profile;
+            }
+        }
+    }
+
+    /// Searches the scrollback for `pattern`, matched literally unless `regex` is set; an
+    /// empty pattern clears the search. Errors carry the regex compiler's message.
+    pub fn set_search(
+        &self,
+        pattern: &str,
+        regex: bool,
+        case_sensitive: bool,
+    ) -> Result<(), String> {
+        match self {
+            #[cfg(target_os = "linux")]
+            Self::Linux(t) => {
+                if pattern.is_empty() {
+                    t.search_set_regex(None, 0);
+                    t.unselect_all();
+                    return Ok(());
+                }
+                let pattern = if regex {
+                    glib::GString::from(pattern)
+                } else {
+                    glib::Regex::escape_string(pattern)
+                };
+                let flags = if case_sensitive {
+                    PCRE2_MULTILINE
+                } else {
+                    PCRE2_MULTILINE | PCRE2_CASELESS
+                };
+                let regex = vte4::Regex::for_search(&pattern, flags)
+                    .map_err(|e| e.message().to_string())?;
+                t.search_set_regex(Some(&regex), 0);
+                t.search_set_wrap_around(true);
+                Ok(())
+            }
+            #[cfg(not(target_os = "linux"))]
+            Self::Windows(_) => {
+                let _ = (pattern, regex, case_sensitive);
+                Ok(())
+            }
+        }
+    }
+
+    /// Selects the next match below the current one; `false` when there is none.
+    pub fn search_next(&self) -> bool {
+        match self {
+            #[cfg(target_os = "linux")]
+            Self::Linux(t) => t.search_find_next(),
+            #[cfg(not(target_os = "linux"))]
+            Self::Windows(_) => false,
+        }
+    }
+
+    /// Selects the next match above the current one; `false` when there is none.
+    pub fn search_previous(&self) -> bool {
+        match self {
+            #[cfg(target_os = "linux")]
+            Self::Linux(t) => t.search_find_previous(),
+            #[cfg(not(target_os = "linux"))]
+            Self::Windows(_) => false,
+        }
+    }
+
+    pub fn connect_directory_changed(&self, f: impl Fn() + 'static) {
+        match self {
+            #[cfg(target_os = "linux")]
+            Self::Linux(t) => {
+                t.connect_current_directory_uri_changed(move |_| f());
+            }
+            #[cfg(not(target_os = "linux"))]
+            Self::Windows(_) => drop(f),
+        }
+    }
 }
 
-pub fn create_terminal(shell: &str, cwd: Option<&str>, env: Option<&[(&str, &str)]>) -> AppTerminal {
+// From pcre2.h; VTE compiles search patterns with PCRE2 but vte4 does not export its flags.
+#[cfg(target_os = "linux")]
+const PCRE2_CASELESS: u32 = 0x0000_0008;
+#[cfg(target_os = "linux")]
+const PCRE2_MULTILINE: u32 = 0x0000_0400;
+
+/// Spawns `shell` in a new terminal. `command`, when given, is typed into the shell once it
+/// has started.
+pub fn create_terminal(
+    shell: &str,
+    cwd: Option<&str>,
+    env: Option<&[(&str, &str)]>,
+    command: Option<&str>,
+) -> AppTerminal {
     #[cfg(target_os = "linux")]
     {
         let terminal = Terminal::new();
         terminal.add_css_class("glass-terminal");
+        terminal.connect_bell(|t| {
+            if t.has_css_class("visual-bell") {
+                t.add_css_class("bell-flash");
+                let t = t.downgrade();
+                glib::timeout_add_local_once(Duration::from_millis(150), move || {
+                    if let Some(t) = t.upgrade() {
+                        t.remove_css_class("bell-flash");
+                    }
+                });
+            }
+        });
 
-        let command = [shell];
+        let argv = [shell];
         let env_vars: Vec<String> = env.map_or_else(Vec::new, |vars| {
             vars.iter().map(|(k, v)| format!("{k}={v}")).collect()
         });
         let env_ptrs: Vec<&str> = env_vars.iter().map(String::as_str).collect();
 
+        let startup = command.map(|c| (terminal.clone(), format!("{c}\n")));
         terminal.spawn_async(
             PtyFlags::DEFAULT,
             cwd,
-            &command,
+            &argv,
             &env_ptrs,
             glib::SpawnFlags::DEFAULT,
             || {},                     // child setup closure
             -1,                        // timeout
             None::<&gtk4::gio::Cancellable>, // cancellable
-            |_| {},                    // callback
+            move |result| {
+                match (result, &startup) {
+                    (Ok(_), Some((terminal, line))) => terminal.feed_child(line.as_bytes()),
+                    (Err(e), _) => eprintln!("Failed to start shell: {e}"),
+                    _ => {}
+                }
+            },
         );
 
         AppTerminal::Linux(terminal)
@@ -64,6 +255,7 @@ pub fn create_terminal(shell: &str, cwd: Option<&str>, env: Option<&[(&str, &str
         let _ = shell;
         let _ = cwd;
         let _ = env;
+        let _ = command;
         let label = Label::new(Some("Terminal not supported on Windows"));
         label.add_css_class("glass-terminal");
         label.set_halign(Align::Center);
@@ -71,3 +263,874 @@ pub fn create_terminal(shell: &str, cwd: Option<&str>, env: Option<&[(&str, &str
         AppTerminal::Windows(label)
     }
 }
+
+/// Where a shortcut's command is typed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RunTarget {
+    /// The tab currently shown.
+    Active,
+    NewTab,
+    /// A tab by its position in the notebook.
+    Tab(u32),
+}
+
+/// Which neighbouring pane to move focus to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PaneDirection {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+#[derive(Clone)]
+struct Pane {
+    widget: ScrolledWindow,
+    terminal: AppTerminal,
+    /// Startup command, editable from the tab's dialog.
+    command: Rc<RefCell<Option<String>>>,
+    /// Directory the pane was opened in, kept until the shell reports its own.
+    cwd: Option<String>,
+}
+
+/// The focused pane, falling back to the first one when focus never entered the tab.
+fn focused_in(panes: &RefCell<Vec<Pane>>, focused: &glib::WeakRef<ScrolledWindow>) -> Option<Pane> {
+    let focused = focused.upgrade();
+    let panes = panes.borrow();
+    focused
+        .and_then(|widget| panes.iter().find(|pane| pane.widget == widget))
+        .or_else(|| panes.first())
+        .cloned()
+}
+
+#[derive(Clone)]
+struct TerminalTab {
+    /// Notebook page: the search bar above `area`.
+    page: Box,
+    /// Holds the root of the pane tree.
+    area: Box,
+    search: SearchBar,
+    search_entry: SearchEntry,
+    title: Label,
+    /// Profile name picked for the tab; `None` follows the default profile.
+    profile: Rc<RefCell<Option<String>>>,
+    panes: Rc<RefCell<Vec<Pane>>>,
+    /// Pane that last had keyboard focus. Weak, as the pane's own focus handler holds this.
+    focused: Rc<glib::WeakRef<ScrolledWindow>>,
+}
+
+impl TerminalTab {
+    fn focused_pane(&self) -> Option<Pane> {
+        focused_in(&self.panes, &self.focused)
+    }
+
+    fn pane_for(&self, widget: &gtk4::Widget) -> Option<Pane> {
+        self.panes
+            .borrow()
+            .iter()
+            .find(|pane| pane.widget.upcast_ref::<gtk4::Widget>() == widget)
+            .cloned()
+    }
+
+    fn to_session(&self, widget: &gtk4::Widget) -> PaneSession {
+        if let Some(paned) = widget.downcast_ref::<Paned>() {
+            let child = |child: Option<gtk4::Widget>| {
+                let state = child.map_or_else(PaneSession::default, |c| self.to_session(&c));
+                std::boxed::Box::new(state)
+            };
+            return PaneSession::Split {
+                direction: if paned.orientation() == Orientation::Horizontal {
+                    SplitDirection::SideBySide
+                } else {
+                    SplitDirection::Stacked
+                },
+                position: paned.position(),
+                start: child(paned.start_child()),
+                end: child(paned.end_child()),
+            };
+        }
+        self.pane_for(widget)
+            .map_or_else(PaneSession::default, |pane| PaneSession::Terminal {
+                cwd: pane.terminal.current_dir().or_else(|| pane.cwd.clone()),
+                command: pane.command.borrow().clone(),
+            })
+    }
+}
+
+/// Accelerator and the tab action it triggers.
+type KeyBinding = (&'static str, fn(&TerminalTabs));
+
+/// The terminal notebook together with the panes behind each page, so commands reach the
+/// terminal the user is looking at rather than whichever was opened first.
+#[derive(Clone)]
+pub struct TerminalTabs {
+    pub notebook: Notebook,
+    tabs: Rc<RefCell<Vec<TerminalTab>>>,
+    shell: Rc<RefCell<String>>,
+    profiles: Rc<RefCell<Vec<TerminalProfile>>>,
+    default_profile: Rc<RefCell<String>>,
+    /// Whether the layout is written to the session file as it changes.
+    persist: Rc<Cell<bool>>,
+    save_pending: Rc<Cell<bool>>,
+}
+
+impl TerminalTabs {
+    pub fn new(shell: &str) -> Self {
+        let notebook = Notebook::new();
+        notebook.set_show_tabs(true);
+        notebook.set_scrollable(true);
+        notebook.add_css_class("terminal-notebook");
+        notebook.set_group_name(Some("terminal-tabs"));
+
+        let tabs = Self {
+            notebook,
+            tabs: Rc::new(RefCell::new(Vec::new())),
+            shell: Rc::new(RefCell::new(shell.to_string())),
+            profiles: Rc::new(RefCell::new(Vec::new())),
+            default_profile: Rc::new(RefCell::new(String::new())),
+            persist: Rc::new(Cell::new(false)),
+            save_pending: Rc::new(Cell::new(false)),
+        };
+
+        {
+            let this = tabs.clone();
+            tabs.notebook.connect_page_removed(move |notebook, _, _| {
+                this.tabs
+                    .borrow_mut()
+                    .retain(|tab| notebook.page_num(&tab.page).is_some());
+                this.schedule_save();
+            });
+        }
+        {
+            let this = tabs.clone();
+            tabs.notebook
+                .connect_page_reordered(move |_, _, _| this.schedule_save());
+        }
+        {
+            let this = tabs.clone();
+            tabs.notebook
+                .connect_switch_page(move |_, _, _| this.schedule_save());
+        }
+        tabs.install_shortcuts();
+
+        tabs
+    }
+
+    /// Ctrl+Shift+F opens the search bar, Ctrl+Shift+E / Ctrl+Shift+O split side by side /
+    /// stacked, Ctrl+Shift+W closes the focused pane and Alt+arrows move between panes.
+    /// Captured before VTE sees the keys.
+    fn install_shortcuts(&self) {
+        let controller = ShortcutController::new();
+        controller.set_propagation_phase(PropagationPhase::Capture);
+
+        let bindings: [KeyBinding; 8] = [
+            ("<Control><Shift>f", Self::show_search),
+            ("<Control><Shift>e", |t| t.split(SplitDirection::SideBySide)),
+            ("<Control><Shift>o", |t| t.split(SplitDirection::Stacked)),
+            ("<Control><Shift>w", Self::close_pane),
+            ("<Alt>Left", |t| t.focus_pane(PaneDirection::Left)),
+            ("<Alt>Right", |t| t.focus_pane(PaneDirection::Right)),
+            ("<Alt>Up", |t| t.focus_pane(PaneDirection::Up)),
+            ("<Alt>Down", |t| t.focus_pane(PaneDirection::Down)),
+        ];
+        for (accelerator, handler) in bindings {
+            let this = self.clone();
+            let action = CallbackAction::new(move |_, _| {
+                handler(&this);
+                glib::Propagation::Stop
+            });
+            controller.add_shortcut(Shortcut::new(
+                ShortcutTrigger::parse_string(accelerator),
+                Some(action),
+            ));
+        }
+        self.notebook.add_controller(controller);
+    }
+
+    /// Shell used for tabs opened from now on.
+    pub fn set_shell(&self, shell: &str) {
+        shell.clone_into(&mut self.shell.borrow_mut());
+    }
+
+    /// Replaces the profiles tabs can use and restyles every open terminal.
+    pub fn set_profiles(&self, profiles: &[TerminalProfile], default: &str) {
+        profiles.clone_into(&mut self.profiles.borrow_mut());
+        default.clone_into(&mut self.default_profile.borrow_mut());
+        for tab in self.tabs.borrow().iter() {
+            self.apply_tab_profile(tab);
+        }
+    }
+
+    fn profile_for(&self, tab: &TerminalTab) -> TerminalProfile {
+        TerminalProfile::pick(
+            &self.profiles.borrow(),
+            &self.default_profile.borrow(),
+            tab.profile.borrow().as_deref(),
+        )
+    }
+
+    fn apply_tab_profile(&self, tab: &TerminalTab) {
+        let profile = self.profile_for(tab);
+        for pane in tab.panes.borrow().iter() {
+            pane.terminal.apply_profile(&profile);
+        }
+    }
+
+    /// Turns session saving on or off; turning it on writes the current layout straight away.
+    pub fn set_persist(&self, persist: bool) {
+        let was_persisting = self.persist.replace(persist);
+        if persist && !was_persisting {
+            self.schedule_save();
+        }
+    }
+
+    /// Opens a tab running the configured shell and switches to it. Without a title the tab
+    /// is numbered.
+    pub fn add_tab(&self, title: Option<&str>) -> AppTerminal {
+        self.add_tab_typing(title, None)
+    }
+
+    /// Like [`Self::add_tab`], typing `input` into the shell once it has started. Unlike a
+    /// startup command, the input is neither kept with the pane nor saved with the session.
+    fn add_tab_typing(&self, title: Option<&str>, input: Option<&str>) -> AppTerminal {
+        let title =
+            title.map_or_else(|| format!("T{}", self.notebook.n_pages() + 1), str::to_string);
+        let state = TabSession {
+            title,
+            ..TabSession::default()
+        };
+        self.open_tab(&state, input)
+    }
+
+    /// Builds a tab from its saved state and returns the terminal in its first pane, which is
+    /// also the one sent `input`.
+    fn open_tab(&self, state: &TabSession, input: Option<&str>) -> AppTerminal {
+        let page = Box::new(Orientation::Vertical, 0);
+        let area = Box::new(Orientation::Vertical, 0);
+        area.set_vexpand(true);
+        let panes = Rc::new(RefCell::new(Vec::new()));
+        let focused = Rc::new(glib::WeakRef::new());
+        let (search, search_entry) = build_search_bar(&panes, &focused);
+        page.append(&search);
+        page.append(&area);
+
+        let tab = TerminalTab {
+            page: page.clone(),
+            area,
+            search,
+            search_entry,
+            title: Label::new(Some(&state.title)),
+            profile: Rc::new(RefCell::new(state.profile.clone())),
+            panes,
+            focused,
+        };
+        tab.area.append(&self.build_panes(&tab, &state.panes, input));
+        let tab_label = self.build_tab_label(&tab);
+        let first = tab.panes.borrow()[0].terminal.clone();
+
+        self.tabs.borrow_mut().push(tab);
+        self.notebook.append_page(&page, Some(&tab_label));
+        self.notebook.set_tab_reorderable(&page, true);
+        self.notebook.set_tab_detachable(&page, true);
+        self.notebook.set_current_page(self.notebook.page_num(&page));
+        first
+    }
+
+    /// Builds the pane tree; `input` goes to the first pane created.
+    fn build_panes(
+        &self,
+        tab: &TerminalTab,
+        state: &PaneSession,
+        input: Option<&str>,
+    ) -> gtk4::Widget {
+        match state {
+            PaneSession::Terminal { cwd, command } => self
+                .new_pane(tab, cwd.clone(), command.clone(), input)
+                .widget
+                .upcast(),
+            PaneSession::Split {
+                direction,
+                position,
+                start,
+                end,
+            } => {
+                let paned = self.new_paned(*direction);
+                paned.set_start_child(Some(&self.build_panes(tab, start, input)));
+                paned.set_end_child(Some(&self.build_panes(tab, end, None)));
+                paned.set_position(*position);
+                paned.upcast()
+            }
+        }
+    }
+
+    /// Opens a pane whose shell is sent its startup `command`, then the one-off `input`.
+    fn new_pane(
+        &self,
+        tab: &TerminalTab,
+        cwd: Option<String>,
+        command: Option<String>,
+        input: Option<&str>,
+    ) -> Pane {
+        // A directory removed since the last run would leave the pane without a shell.
+        let cwd = cwd.filter(|dir| Path::new(dir).is_dir());
+        // Both go through the spawn callback: the pty does not exist before then, so anything
+        // fed earlier is lost.
+        let typed: Vec<&str> = command.as_deref().into_iter().chain(input).collect();
+        let typed = (!typed.is_empty()).then(|| typed.join("\n"));
+        let terminal = create_terminal(
+            &self.shell.borrow(),
+            cwd.as_deref(),
+            None,
+            typed.as_deref(),
+        );
+        terminal.apply_profile(&self.profile_for(tab));
+        let widget = ScrolledWindow::new();
+        widget.set_child(Some(terminal.widget()));
+        widget.set_vexpand(true);
+        widget.set_hexpand(true);
+
+        let focus = EventControllerFocus::new();
+        {
+            let focused = tab.focused.clone();
+            // Weak, or a closed pane would keep itself and its shell alive.
+            let widget = widget.downgrade();
+            focus.connect_enter(move |_| {
+                focused.set(widget.upgrade().as_ref());
+            });
+        }
+        widget.add_controller(focus);
+        {
+            let this = self.clone();
+            terminal.connect_directory_changed(move || this.schedule_save());
+        }
+
+        let pane = Pane {
+            widget,
+            terminal,
+            command: Rc::new(RefCell::new(command)),
+            cwd,
+        };
+        tab.panes.borrow_mut().push(pane.clone());
+        pane
+    }
+
+    fn new_paned(&self, direction: SplitDirection) -> Paned {
+        let paned = Paned::new(match direction {
+            SplitDirection::SideBySide => Orientation::Horizontal,
+            SplitDirection::Stacked => Orientation::Vertical,
+        });
+        paned.set_wide_handle(true);
+        paned.set_shrink_start_child(false);
+        paned.set_shrink_end_child(false);
+        paned.set_hexpand(true);
+        paned.set_vexpand(true);
+        let this = self.clone();
+        paned.connect_position_notify(move |_| this.schedule_save());
+        paned
+    }
+
+    /// Splits the focused pane of the current tab in two; the new half opens in the same
+    /// directory and takes focus.
+    pub fn split(&self, direction: SplitDirection) {
+        let Some(tab) = self.current_tab() else {
+            return;
+        };
+        let Some(pane) = tab.focused_pane() else {
+            return;
+        };
+        let size = match direction {
+            SplitDirection::SideBySide => pane.widget.width(),
+            SplitDirection::Stacked => pane.widget.height(),
+        };
+
+        let paned = self.new_paned(direction);
+        replace_child(pane.widget.upcast_ref(), paned.upcast_ref());
+        let cwd = pane.terminal.current_dir().or_else(|| pane.cwd.clone());
+        let added = self.new_pane(&tab, cwd, None, None);
+        paned.set_start_child(Some(&pane.widget));
+        paned.set_end_child(Some(&added.widget));
+        if size > 0 {
+            paned.set_position(size / 2);
+        }
+        added.terminal.widget().grab_focus();
+        self.schedule_save();
+    }
+
+    /// Closes the focused pane, giving its space to its neighbour; the last pane closes the
+    /// tab.
+    pub fn close_pane(&self) {
+        let Some(tab) = self.current_tab() else {
+            return;
+        };
+        let Some(pane) = tab.focused_pane() else {
+            return;
+        };
+        let Some(paned) = pane.widget.parent().and_downcast::<Paned>() else {
+            if let Some(index) = self.notebook.page_num(&tab.page) {
+                self.notebook.remove_page(Some(index));
+            }
+            return;
+        };
+
+        let sibling = if paned.start_child().as_ref() == Some(pane.widget.upcast_ref()) {
+            paned.end_child()
+        } else {
+            paned.start_child()
+        };
+        paned.set_start_child(None::<&gtk4::Widget>);
+        paned.set_end_child(None::<&gtk4::Widget>);
+        if let Some(sibling) = &sibling {
+            replace_child(paned.upcast_ref(), sibling);
+        }
+        tab.panes.borrow_mut().retain(|p| p.widget != pane.widget);
+        tab.focused.set(None);
+
+        let next = sibling.and_then(|sibling| {
+            tab.panes
+                .borrow()
+                .iter()
+                .find(|p| p.widget.is_ancestor(&sibling) || p.widget == sibling)
+                .cloned()
+        });
+        if let Some(next) = next {
+            next.terminal.widget().grab_focus();
+        }
+        self.schedule_save();
+    }
+
+    /// Opens the current tab's search bar, or focuses it when already open.
+    pub fn show_search(&self) {
+        if let Some(tab) = self.current_tab() {
+            tab.search.set_search_mode(true);
+            tab.search_entry.grab_focus();
+        }
+    }
+
+    /// Moves focus to the nearest pane in `direction` within the current tab.
+    pub fn focus_pane(&self, direction: PaneDirection) {
+        let Some(tab) = self.current_tab() else {
+            return;
+        };
+        let Some(current) = tab.focused_pane() else {
+            return;
+        };
+        let centre = |pane: &Pane| {
+            pane.widget.compute_bounds(&tab.page).map(|b| {
+                (b.x() + b.width() / 2.0, b.y() + b.height() / 2.0)
+            })
+        };
+        let Some((cx, cy)) = centre(&current) else {
+            return;
+        };
+
+        let target = tab
+            .panes
+            .borrow()
+            .iter()
+            .filter(|pane| pane.widget != current.widget)
+            .filter_map(|pane| {
+                let (x, y) = centre(pane)?;
+                let (ahead, across) = match direction {
+                    PaneDirection::Left => (cx - x, y - cy),
+                    PaneDirection::Right => (x - cx, y - cy),
+                    PaneDirection::Up => (cy - y, x - cx),
+                    PaneDirection::Down => (y - cy, x - cx),
+                };
+                // Prefer panes straight ahead over ones further off to the side.
+                (ahead > 0.0).then(|| (across.abs().mul_add(2.0, ahead), pane.clone()))
+            })
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(_, pane)| pane);
+        if let Some(pane) = target {
+            pane.terminal.widget().grab_focus();
+        }
+    }
+
+    /// Reopens the tabs of a saved session. Returns `false`, opening nothing, when it has none.
+    pub fn restore(&self, session: &TerminalSession) -> bool {
+        if session.tabs.is_empty() {
+            return false;
+        }
+        for state in &session.tabs {
+            self.open_tab(state, None);
+        }
+        self.notebook
+            .set_current_page(Some(session.active.min(self.notebook.n_pages() - 1)));
+        true
+    }
+
+    /// The current layout, in notebook order.
+    pub fn session(&self) -> TerminalSession {
+        TerminalSession {
+            tabs: (0..self.notebook.n_pages())
+                .filter_map(|index| self.tab_at(index))
+                .map(|tab| TabSession {
+                    title: tab.title.text().to_string(),
+                    profile: tab.profile.borrow().clone(),
+                    panes: tab
+                        .area
+                        .first_child()
+                        .map_or_else(PaneSession::default, |root| tab.to_session(&root)),
+                })
+                .collect(),
+            active: self.notebook.current_page().unwrap_or(0),
+        }
+    }
+
+    /// Saves the session shortly after the last change, so a burst of `cd`s or tab moves
+    /// writes the file once.
+    fn schedule_save(&self) {
+        if !self.persist.get() || self.save_pending.replace(true) {
+            return;
+        }
+        let this = self.clone();
+        glib::timeout_add_local_once(Duration::from_secs(1), move || {
+            this.save_pending.set(false);
+            // A notebook torn down with its window has dropped its pages; keep the last layout.
+            if this.persist.get() && this.notebook.root().is_some() {
+                this.session().save();
+            }
+        });
+    }
+
+    fn tab_at(&self, index: u32) -> Option<TerminalTab> {
+        let page = self.notebook.nth_page(Some(index))?;
+        self.tabs
+            .borrow()
+            .iter()
+            .find(|tab| tab.page.upcast_ref::<gtk4::Widget>() == &page)
+            .cloned()
+    }
+
+    fn current_tab(&self) -> Option<TerminalTab> {
+        self.tab_at(self.notebook.current_page()?)
+    }
+
+    fn terminal_at(&self, index: u32) -> Option<AppTerminal> {
+        Some(self.tab_at(index)?.focused_pane()?.terminal)
+    }
+
+    /// Tab titles in notebook order, for "Run in tab…".
+    pub fn titles(&self) -> Vec<String> {
+        (0..self.notebook.n_pages())
+            .filter_map(|index| self.tab_at(index))
+            .map(|tab| tab.title.text().to_string())
+            .collect()
+    }
+
+    /// Types `command` and a newline into the target tab's focused pane and brings that tab
+    /// to the front.
+    /// With no tab left to run in, a new one is opened and its shell is sent the command once
+    /// it has started.
+    pub fn run(&self, command: &str, target: RunTarget) {
+        let existing = match target {
+            RunTarget::Active => self
+                .notebook
+                .current_page()
+                .and_then(|index| self.terminal_at(index)),
+            RunTarget::Tab(index) => {
+                let terminal = self.terminal_at(index);
+                if terminal.is_some() {
+                    self.notebook.set_current_page(Some(index));
+                }
+                terminal
+            }
+            RunTarget::NewTab => None,
+        };
+        let terminal = existing.map_or_else(
+            || self.add_tab_typing(None, Some(command)),
+            |terminal| {
+                terminal.feed_child(format!("{command}\n").as_bytes());
+                terminal
+            },
+        );
+        terminal.widget().grab_focus();
+    }
+
+    /// Tab label with a close button; double-click edits the title and the focused pane's
+    /// startup command.
+    fn build_tab_label(&self, tab: &TerminalTab) -> Box {
+        let box_ = Box::new(Orientation::Horizontal, 4);
+        box_.append(&tab.title);
+
+        let close_btn = Button::from_icon_name("window-close-symbolic");
+        close_btn.add_css_class("flat");
+        close_btn.add_css_class("small-icon");
+        box_.append(&close_btn);
+
+        let notebook_clone = self.notebook.clone();
+        let page_clone = tab.page.clone();
+        close_btn.connect_clicked(move |_| {
+            if let Some(idx) = notebook_clone.page_num(&page_clone) {
+                notebook_clone.remove_page(Some(idx));
+            }
+        });
+
+        // Edit on double click
+        let gesture = GestureClick::new();
+        gesture.set_button(1);
+        let this = self.clone();
+        let tab_clone = tab.clone();
+
+        gesture.connect_pressed(move |_gesture, n_press, _, _| {
+            if n_press == 2 {
+                // Looked up on click: the first tab is built before the notebook has a window.
+                let parent_win = this
+                    .notebook
+                    .root()
+                    .and_then(|r| r.downcast::<gtk4::Window>().ok());
+                if let (Some(win), Some(pane)) = (&parent_win, tab_clone.focused_pane()) {
+                    let names: Vec<String> =
+                        this.profiles.borrow().iter().map(|p| p.name.clone()).collect();
+                    let (this, tab) = (this.clone(), tab_clone.clone());
+                    prompt_edit(
+                        win,
+                        &tab_clone.title,
+                        &pane.command,
+                        &tab_clone.profile,
+                        &names,
+                        move || {
+                            this.apply_tab_profile(&tab);
+                            this.schedule_save();
+                        },
+                    );
+                }
+            }
+        });
+        box_.add_controller(gesture);
+
+        box_
+    }
+}
+
+/// What the search bar needs from its tab, held weakly so the bar does not keep a closed
+/// tab's terminals alive.
+#[derive(Clone)]
+struct SearchState {
+    panes: Weak<RefCell<Vec<Pane>>>,
+    focused: Rc<glib::WeakRef<ScrolledWindow>>,
+    entry: glib::WeakRef<SearchEntry>,
+    case_sensitive: Rc<Cell<bool>>,
+    regex: Rc<Cell<bool>>,
+}
+
+impl SearchState {
+    fn terminal(&self) -> Option<AppTerminal> {
+        let panes = self.panes.upgrade()?;
+        focused_in(&panes, &self.focused).map(|pane| pane.terminal)
+    }
+
+    /// Searches the focused pane for the entry's text, upwards (older output) or downwards.
+    /// The pattern is set again each time so a pane focused since the last search is covered.
+    fn find(&self, upwards: bool) {
+        let (Some(entry), Some(terminal)) = (self.entry.upgrade(), self.terminal()) else {
+            return;
+        };
+        let text = entry.text();
+        let result = terminal.set_search(&text, self.regex.get(), self.case_sensitive.get());
+        let problem = match result {
+            Err(message) => Some(message),
+            Ok(()) if text.is_empty() => None,
+            Ok(()) => {
+                let found = if upwards {
+                    terminal.search_previous()
+                } else {
+                    terminal.search_next()
+                };
+                (!found).then(|| "No matches".to_string())
+            }
+        };
+        if problem.is_some() {
+            entry.add_css_class("error");
+        } else {
+            entry.remove_css_class("error");
+        }
+        entry.set_tooltip_text(problem.as_deref());
+    }
+
+    fn clear(&self) {
+        if let Some(terminal) = self.terminal() {
+            let _ = terminal.set_search("", false, false);
+            terminal.widget().grab_focus();
+        }
+    }
+}
+
+/// Search bar for a tab: Enter or ↑ finds older matches, Ctrl+G or ↓ newer ones; the match
+/// is selected in the focused pane.
+fn build_search_bar(
+    panes: &Rc<RefCell<Vec<Pane>>>,
+    focused: &Rc<glib::WeakRef<ScrolledWindow>>,
+) -> (SearchBar, SearchEntry) {
+    let bar = SearchBar::new();
+    bar.add_css_class("terminal-search");
+    bar.set_show_close_button(true);
+
+    let row = Box::new(Orientation::Horizontal, 4);
+    let entry = SearchEntry::new();
+    entry.set_placeholder_text(Some("Search scrollback"));
+    entry.set_hexpand(true);
+
+    let case_btn = ToggleButton::with_label("Aa");
+    case_btn.add_css_class("flat");
+    case_btn.set_tooltip_text(Some("Match case"));
+    let regex_btn = ToggleButton::with_label(".*");
+    regex_btn.add_css_class("flat");
+    regex_btn.set_tooltip_text(Some("Regular expression"));
+    let up_btn = Button::from_icon_name("go-up-symbolic");
+    up_btn.add_css_class("flat");
+    up_btn.set_tooltip_text(Some("Previous match (Enter)"));
+    let down_btn = Button::from_icon_name("go-down-symbolic");
+    down_btn.add_css_class("flat");
+    down_btn.set_tooltip_text(Some("Next match (Ctrl+G)"));
+
+    row.append(&entry);
+    row.append(&case_btn);
+    row.append(&regex_btn);
+    row.append(&up_btn);
+    row.append(&down_btn);
+    bar.set_child(Some(&row));
+    bar.connect_entry(&entry);
+
+    let state = SearchState {
+        panes: Rc::downgrade(panes),
+        focused: focused.clone(),
+        entry: entry.downgrade(),
+        case_sensitive: Rc::new(Cell::new(false)),
+        regex: Rc::new(Cell::new(false)),
+    };
+
+    {
+        let state = state.clone();
+        entry.connect_search_changed(move |_| state.find(true));
+    }
+    {
+        let state = state.clone();
+        entry.connect_activate(move |_| state.find(true));
+    }
+    {
+        let state = state.clone();
+        entry.connect_previous_match(move |_| state.find(true));
+    }
+    {
+        let state = state.clone();
+        entry.connect_next_match(move |_| state.find(false));
+    }
+    {
+        let state = state.clone();
+        up_btn.connect_clicked(move |_| state.find(true));
+    }
+    {
+        let state = state.clone();
+        down_btn.connect_clicked(move |_| state.find(false));
+    }
+    {
+        let state = state.clone();
+        case_btn.connect_toggled(move |btn| {
+            state.case_sensitive.set(btn.is_active());
+            state.find(true);
+        });
+    }
+    {
+        let state = state.clone();
+        regex_btn.connect_toggled(move |btn| {
+            state.regex.set(btn.is_active());
+            state.find(true);
+        });
+    }
+    bar.connect_search_mode_enabled_notify(move |bar| {
+        if !bar.is_search_mode() {
+            state.clear();
+        }
+    });
+
+    (bar, entry)
+}
+
+/// Puts `new` where `old` sits in a pane tree: either half of a split, or the tab's pane area.
+fn replace_child(old: &gtk4::Widget, new: &gtk4::Widget) {
+    let Some(parent) = old.parent() else {
+        return;
+    };
+    if let Some(paned) = parent.downcast_ref::<Paned>() {
+        if paned.start_child().as_ref() == Some(old) {
+            paned.set_start_child(None::<&gtk4::Widget>);
+            paned.set_start_child(Some(new));
+        } else {
+            paned.set_end_child(None::<&gtk4::Widget>);
+            paned.set_end_child(Some(new));
+        }
+    } else if let Some(area) = parent.downcast_ref::<Box>() {
+        area.remove(old);
+        area.append(new);
+    }
+}
+
+fn prompt_edit(
+    parent: &gtk4::Window,
+    title: &Label,
+    command: &Rc<RefCell<Option<String>>>,
+    profile: &Rc<RefCell<Option<String>>>,
+    profile_names: &[String],
+    on_saved: impl Fn() + 'static,
+) {
+    let dialog = gtk4::Dialog::builder()
+        .transient_for(parent)
+        .modal(true)
+        .title("Edit Tab")
+        .build();
+
+    let entry = gtk4::Entry::new();
+    entry.set_text(&title.text());
+    entry.set_activates_default(true);
+
+    let command_entry = gtk4::Entry::new();
+    command_entry.set_placeholder_text(Some("Startup command for this pane (optional)"));
+    command_entry.set_text(command.borrow().as_deref().unwrap_or_default());
+    command_entry.set_activates_default(true);
+
+    // Id "" follows the default profile.
+    let profile_combo = gtk4::ComboBoxText::new();
+    profile_combo.append(Some(""), "Default profile");
+    for name in profile_names {
+        profile_combo.append(Some(name), name);
+    }
+    let current = profile.borrow().clone().unwrap_or_default();
+    if !profile_combo.set_active_id(Some(&current)) {
+        profile_combo.set_active_id(Some(""));
+    }
+
+    let content = dialog.content_area();
+    content.set_margin_top(10);
+    content.set_margin_bottom(10);
+    content.set_margin_start(10);
+    content.set_margin_end(10);
+    content.set_spacing(10);
+    content.append(&entry);
+    content.append(&command_entry);
+    content.append(&profile_combo);
+
+    let btn = dialog.add_button("Save", gtk4::ResponseType::Ok);
+    btn.add_css_class("suggested-action");
+    dialog.set_default_response(gtk4::ResponseType::Ok);
+
+    let title = title.clone();
+    let command = command.clone();
+    let profile = profile.clone();
+    dialog.connect_response(move |d, resp| {
+        if resp == gtk4::ResponseType::Ok {
+            let text = entry.text();
+            if !text.is_empty() {
+                title.set_text(&text);
+            }
+            let text = command_entry.text().trim().to_string();
+            command.replace((!text.is_empty()).then_some(text));
+            let id = profile_combo.active_id().unwrap_or_default().to_string();
+            profile.replace((!id.is_empty()).then_some(id));
+            on_saved();
+        }
+        d.close();
+    });
+
+    dialog.show();
+}
@@ -1,17 +1,36 @@
 use gtk4::gdk::prelude::*;
 use gtk4::prelude::*;
 use gtk4::{
-    gdk, glib, Align, Application, ApplicationWindow, Box, Button, CssProvider, DrawingArea,
-    GestureClick, Grid, Label, LevelBar, Notebook, Orientation, Popover, Stack, StackTransitionType,
+    gdk, gio, glib, Align, Application, ApplicationWindow, Box, Button, CssProvider, DrawingArea,
+    GestureClick, Grid, Label, LevelBar, Orientation, Popover, Stack, StackTransitionType,
+};
+use std::{
+    cell::{Cell, RefCell},
+    path::{Path, PathBuf},
+    rc::Rc,
 };
-use std::{cell::RefCell, rc::Rc};
 
 
-use crate::settings::{MonitorStyle, Settings, Theme};
+use crate::alerts::{AlertEngine, AlertEvent};
+use crate::cgroups_ui::CgroupPanel;
+use crate::chart::{Chart, ChartSeries};
+use crate::gpu::GpuStats;
+use crate::history::{self, HistoryStore};
+use crate::hwmon::SensorKind;
+use crate::monitor::{format_rate, CoreStats, MonitorControl, MonitorData, SamplingConfig};
+use crate::processes_ui::ProcessPanel;
+use crate::power::ChargeState;
+use crate::recorder::{self, RecordTarget};
+use crate::remote::{RemoteEvent, RemoteMonitor};
+use crate::session::TerminalSession;
+use crate::settings::{
+    AlertMetric, CardLevels, ChartRange, CommandSensorConfig, LevelThresholds, MonitorStyle,
+    RecordFormat, RemoteHost, Settings, Theme,
+};
 use crate::settings_ui::show_settings_window;
 use crate::shortcuts::Shortcuts;
 use crate::shortcuts_ui::ShortcutsPanel;
-use crate::terminal::create_terminal;
+use crate::terminal::TerminalTabs;
 
 #[derive(Clone, Copy)]
 enum Trend {
@@ -20,12 +39,67 @@ enum Trend {
     Stable,
 }
 
+/// Alert state of a single reading listed inside a card, or of a card's headline value.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SensorLevel {
+    Ok,
+    Warn,
+    Crit,
+}
+
+impl SensorLevel {
+    const fn css_class(self) -> &'static str {
+        match self {
+            Self::Ok => "sensor-ok",
+            Self::Warn => "sensor-warn",
+            Self::Crit => "sensor-crit",
+        }
+    }
+
+    fn of(thresholds: Option<LevelThresholds>, value: f64) -> Self {
+        match thresholds.map(|t| t.reached(value)) {
+            Some((_, true)) => Self::Crit,
+            Some((true, false)) => Self::Warn,
+            _ => Self::Ok,
+        }
+    }
+
+    const fn level_class(self) -> &'static str {
+        match self {
+            Self::Ok => "level-ok",
+            Self::Warn => "level-warn",
+            Self::Crit => "level-crit",
+        }
+    }
+
+    /// Chart colour for the level; `None` keeps the theme's series colours. Matches the
+    /// `.level-warn`/`.level-crit` colours in `style.css`.
+    const fn chart_colour(self) -> Option<(f64, f64, f64)> {
+        match self {
+            Self::Ok => None,
+            Self::Warn => Some((0.94, 0.78, 0.45)),
+            Self::Crit => Some((0.94, 0.42, 0.42)),
+        }
+    }
+}
+
+/// Swaps `widget`'s `level-*` class for the one matching `level`.
+fn set_level_class(widget: &impl IsA<gtk4::Widget>, level: SensorLevel) {
+    for other in [SensorLevel::Ok, SensorLevel::Warn, SensorLevel::Crit] {
+        if other != level {
+            widget.remove_css_class(other.level_class());
+        }
+    }
+    widget.add_css_class(level.level_class());
+}
+
 #[derive(Clone)]
 struct PerformanceStrip {
     cpu: Button,
     gpu: Button,
     ram: Button,
     net: Button,
+    sys: Button,
 }
 
 impl PerformanceStrip {
@@ -43,6 +117,7 @@ impl PerformanceStrip {
             gpu: chip("GPU —", "GPU Usage\nClick for details"),
             ram: chip("RAM —", "Memory Usage\nClick for details"),
             net: chip("NET —", "Network Traffic\nClick for details"),
+            sys: chip("LOAD —", "Load average (1/5/15 min) and uptime"),
         }
     }
 
@@ -54,14 +129,42 @@ impl PerformanceStrip {
         row.append(&self.gpu);
         row.append(&self.ram);
         row.append(&self.net);
+        row.append(&self.sys);
         row
     }
 
-    fn update(&self, cpu: &str, gpu: &str, ram: &str, net: &str) {
+    fn update(&self, cpu: &str, gpu: &str, ram: &str, net: &str, sys: &str) {
         self.cpu.set_label(cpu);
         self.gpu.set_label(gpu);
         self.ram.set_label(ram);
         self.net.set_label(net);
+        self.sys.set_label(sys);
+    }
+
+    /// Colours each chip like the card it summarises.
+    fn set_levels(&self, cards: &MonitorGroup) {
+        set_level_class(&self.cpu, cards.cpu.level.get());
+        set_level_class(&self.gpu, cards.gpu.level.get());
+        set_level_class(&self.ram, cards.ram.level.get());
+        set_level_class(&self.net, cards.net.level.get());
+    }
+}
+
+/// Where a card keeps its chart history: a series in the shared store plus the range the
+/// charts currently show.
+#[derive(Clone)]
+struct HistorySource {
+    store: Rc<RefCell<HistoryStore>>,
+    key: String,
+    range: Rc<Cell<ChartRange>>,
+}
+
+/// Store key of one series of a card's chart.
+fn series_key(key: &str, label: &str) -> String {
+    if label.is_empty() {
+        key.to_string()
+    } else {
+        format!("{key}/{label}")
     }
 }
 
@@ -70,11 +173,21 @@ struct MonitorCard {
     container: Box,
     value_label: Label,
     bar: LevelBar,
-    chart: DrawingArea,
+    chart: Chart,
     stack: Stack,
-    history: Rc<RefCell<Vec<f64>>>,
+    history_source: Option<HistorySource>,
+    /// Labels of the series last recorded; a single unlabelled series is stored under the
+    /// card's own key, labelled ones under `key/label`.
+    series_labels: Rc<RefCell<Vec<String>>>,
+    thresholds: Rc<Cell<Option<LevelThresholds>>>,
+    level: Rc<Cell<SensorLevel>>,
     last_value: Rc<RefCell<f64>>,
     scale_max: f64,
+    cores: Rc<RefCell<Vec<f64>>>,
+    heatmap: Option<DrawingArea>,
+    segments: Rc<RefCell<Vec<f64>>>,
+    breakdown: Option<DrawingArea>,
+    details: Box,
 }
 
 impl MonitorCard {
@@ -106,15 +219,16 @@ impl MonitorCard {
         bar.add_css_class("monitor-bar");
         stack.add_named(&bar, Some("bar"));
 
-        let chart = DrawingArea::new();
-        chart.add_css_class("monitor-chart");
-        // TODO(senior-ui): Swap to GtkPlot or custom GPU-accelerated canvas so historical data looks
-        // smooth and can display tooltips on hover.
-        stack.add_named(&chart, Some("chart"));
+        let chart = Chart::new(scale_max);
+        stack.add_named(&chart.area, Some("chart"));
 
         container.append(&stack);
 
-        let history = Rc::new(RefCell::new(Vec::<f64>::new()));
+        let details = Box::new(Orientation::Vertical, 2);
+        details.add_css_class("card-details");
+        details.set_visible(false);
+        container.append(&details);
+
         let last_value = Rc::new(RefCell::new(0.0));
 
         let card = Self {
@@ -123,71 +237,265 @@ impl MonitorCard {
             bar,
             chart,
             stack,
-            history,
+            history_source: None,
+            series_labels: Rc::new(RefCell::new(vec![String::new()])),
+            thresholds: Rc::new(Cell::new(None)),
+            level: Rc::new(Cell::new(SensorLevel::Ok)),
             last_value,
             scale_max,
+            cores: Rc::new(RefCell::new(Vec::new())),
+            heatmap: None,
+            segments: Rc::new(RefCell::new(Vec::new())),
+            breakdown: None,
+            details,
         };
         card.set_style(style);
-        card.install_chart_drawer();
         card
     }
 
-    fn install_chart_drawer(&self) {
-        let hist = self.history.clone();
-        self.chart.set_draw_func(move |_area, cr, width, height| {
-            #[allow(clippy::cast_precision_loss)]
-            {
-            let data = hist.borrow();
-            if data.len() < 2 {
-                return;
-            }
+    /// Builds a card that also offers the per-core heatmap view.
+    fn with_cores(title: &str, style: &MonitorStyle, scale_max: f64) -> Self {
+        let mut card = Self::new(title, style, scale_max);
 
-            let max = data
-                .iter()
-                .copied()
-                .fold(1.0_f64, |a, b| if b > a { b } else { a });
-            let step = f64::from(width) / (data.len() - 1) as f64;
-
-            // Fill path
-            cr.set_source_rgba(0.2, 0.6, 1.0, 0.15);
-            cr.move_to(0.0, f64::from(height));
-            
-            for (idx, val) in data.iter().enumerate() {
-                let x = idx as f64 * step;
-                let y = ((val / max) * f64::from(height)).mul_add(-0.95, f64::from(height));
-                cr.line_to(x, y);
-            }
-            cr.line_to(f64::from(width), f64::from(height));
-            cr.close_path();
+        let heatmap = DrawingArea::new();
+        heatmap.add_css_class("monitor-cores");
+        card.stack.add_named(&heatmap, Some("cores"));
+        card.heatmap = Some(heatmap);
+
+        card.install_heatmap_drawer();
+        card.set_style(style);
+        card
+    }
+
+    /// Builds a card whose bar view is a stacked breakdown (used, cached, buffers, free).
+    fn with_breakdown(title: &str, style: &MonitorStyle, scale_max: f64) -> Self {
+        let mut card = Self::new(title, style, scale_max);
+
+        let breakdown = DrawingArea::new();
+        breakdown.add_css_class("monitor-breakdown");
+        card.stack.add_named(&breakdown, Some("breakdown"));
+
+        let segments = card.segments.clone();
+        breakdown.set_draw_func(move |_area, cr, width, height| {
+            // Used, cached and buffers; whatever remains is drawn as free.
+            const COLORS: [(f64, f64, f64); 3] =
+                [(0.2, 0.6, 1.0), (0.55, 0.45, 0.95), (0.3, 0.8, 0.7)];
+            let (width, height) = (f64::from(width), f64::from(height));
+
+            cr.set_source_rgba(1.0, 1.0, 1.0, 0.06);
+            cr.rectangle(0.0, 0.0, width, height);
             let _ = cr.fill();
 
-            // Stroke path
-            cr.set_source_rgba(0.2, 0.6, 1.0, 0.8);
-            cr.set_line_width(2.0);
-            
-            for (idx, val) in data.iter().enumerate() {
-                let x = idx as f64 * step;
-                let y = ((val / max) * f64::from(height)).mul_add(-0.95, f64::from(height));
-                if idx == 0 {
-                    cr.move_to(x, y);
-                } else {
-                    cr.line_to(x, y);
-                }
+            let mut x = 0.0;
+            for (fraction, (r, g, b)) in segments.borrow().iter().zip(COLORS) {
+                let w = fraction.clamp(0.0, 1.0) * width;
+                cr.set_source_rgba(r, g, b, 0.85);
+                cr.rectangle(x, 0.0, w, height);
+                let _ = cr.fill();
+                x += w;
             }
+        });
+        card.breakdown = Some(breakdown);
+
+        card.set_style(style);
+        card
+    }
+
+    fn install_heatmap_drawer(&self) {
+        let Some(heatmap) = &self.heatmap else {
+            return;
+        };
+        let cores = self.cores.clone();
+        heatmap.set_draw_func(move |_area, cr, width, height| {
+            let data = cores.borrow();
+            if data.is_empty() {
+                return;
+            }
+
+            // Lay cores out in a grid whose aspect roughly follows the drawing area.
+            #[allow(
+                clippy::cast_precision_loss,
+                clippy::cast_possible_truncation,
+                clippy::cast_sign_loss
+            )]
+            let (cols, rows) = {
+                let count = data.len() as f64;
+                let aspect = f64::from(width.max(1)) / f64::from(height.max(1));
+                let cols = (count * aspect).sqrt().ceil().clamp(1.0, count) as usize;
+                (cols, data.len().div_ceil(cols))
+            };
+
+            #[allow(clippy::cast_precision_loss)]
+            let (cell_w, cell_h) = (
+                f64::from(width) / cols as f64,
+                f64::from(height) / rows as f64,
+            );
+            let gap = 2.0;
+
+            for (idx, usage) in data.iter().enumerate() {
+                #[allow(clippy::cast_precision_loss)]
+                let (x, y) = (
+                    (idx % cols) as f64 * cell_w,
+                    (idx / cols) as f64 * cell_h,
+                );
+                let heat = (usage / 100.0).clamp(0.0, 1.0);
+
+                // Cell background tinted by load, cool blue through to hot red.
+                cr.set_source_rgba(heat.mul_add(0.8, 0.2), 0.6 * (1.0 - heat), 1.0 - heat, 0.25);
+                cr.rectangle(x + gap / 2.0, y + gap / 2.0, cell_w - gap, cell_h - gap);
+                let _ = cr.fill();
+
+                // Mini bar rising from the bottom of the cell.
+                let bar_h = (cell_h - gap) * heat;
+                cr.set_source_rgba(heat.mul_add(0.8, 0.2), 0.6 * (1.0 - heat), 1.0 - heat, 0.85);
+                cr.rectangle(
+                    x + gap / 2.0,
+                    y + cell_h - gap / 2.0 - bar_h,
+                    cell_w - gap,
+                    bar_h,
+                );
+                let _ = cr.fill();
             }
-            let _ = cr.stroke();
         });
     }
 
+    /// Keeps this card's history in `store` under `key` instead of only in memory.
+    fn with_history(
+        mut self,
+        store: &Rc<RefCell<HistoryStore>>,
+        key: &str,
+        range: &Rc<Cell<ChartRange>>,
+    ) -> Self {
+        self.history_source = Some(HistorySource {
+            store: store.clone(),
+            key: key.to_string(),
+            range: range.clone(),
+        });
+        self.reload_history();
+        self
+    }
+
+    /// Suffix for the chart's y-axis labels and hover readout.
+    fn with_unit(self, unit: &str) -> Self {
+        self.chart.set_unit(unit);
+        self
+    }
+
+    /// Re-reads the chart points for the current range from the store.
+    fn reload_history(&self) {
+        let Some(source) = &self.history_source else {
+            return;
+        };
+        let range = source.range.get();
+        let now = history::now_secs();
+        let store = source.store.borrow();
+        let series = self
+            .series_labels
+            .borrow()
+            .iter()
+            .map(|label| ChartSeries {
+                label: label.clone(),
+                points: store.points(&series_key(&source.key, label), range, now),
+            })
+            .collect();
+        self.chart.set_span(range.seconds());
+        self.chart.set_series(series);
+    }
+
+    /// Appends one point per series to the chart, replacing the series set when the labels
+    /// change (e.g. a GPU appears).
+    fn record_series(&self, values: &[(String, f64)]) {
+        let labels: Vec<String> = values.iter().map(|(label, _)| label.clone()).collect();
+        if *self.series_labels.borrow() != labels {
+            self.series_labels.replace(labels);
+        }
+
+        let now = history::now_secs();
+        if let Some(source) = &self.history_source {
+            let mut store = source.store.borrow_mut();
+            for (label, value) in values {
+                store.record(&series_key(&source.key, label), now, *value);
+            }
+            drop(store);
+            self.reload_history();
+            return;
+        }
+
+        let mut series = self.chart.series();
+        series.retain(|s| values.iter().any(|(label, _)| *label == s.label));
+        for (label, value) in values {
+            let index = series.iter().position(|s| s.label == *label).unwrap_or_else(|| {
+                series.push(ChartSeries {
+                    label: label.clone(),
+                    points: Vec::new(),
+                });
+                series.len() - 1
+            });
+            series[index].points.push((now, *value));
+            series[index]
+                .points
+                .retain(|(ts, _)| *ts >= now.saturating_sub(ChartRange::default().seconds()));
+        }
+        self.chart.set_series(series);
+    }
+
     fn set_style(&self, style: &MonitorStyle) {
+        let bar = if self.breakdown.is_some() { "breakdown" } else { "bar" };
         match style {
-            MonitorStyle::Bar => self.stack.set_visible_child_name("bar"),
+            MonitorStyle::Bar => self.stack.set_visible_child_name(bar),
             MonitorStyle::Chart => self.stack.set_visible_child_name("chart"),
             MonitorStyle::Text => self.stack.set_visible_child_name("text"),
+            MonitorStyle::Cores => {
+                let name = if self.heatmap.is_some() { "cores" } else { bar };
+                self.stack.set_visible_child_name(name);
+            }
         }
     }
 
+    /// Sets the stacked segments as fractions of the whole, in draw order.
+    fn update_breakdown(&self, fractions: &[f64]) {
+        let Some(breakdown) = &self.breakdown else {
+            return;
+        };
+        self.segments.replace(fractions.to_vec());
+        breakdown.queue_draw();
+    }
+
+    fn update_cores(&self, cores: &[CoreStats]) {
+        let Some(heatmap) = &self.heatmap else {
+            return;
+        };
+        self.cores
+            .replace(cores.iter().map(|c| f64::from(c.usage)).collect());
+
+        let busiest = cores
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.usage.total_cmp(&b.1.usage));
+        if let Some((idx, core)) = busiest {
+            heatmap.set_tooltip_text(Some(&format!(
+                "{} cores\nBusiest: core {idx} at {:.0}% ({} MHz)",
+                cores.len(),
+                core.usage,
+                core.frequency_mhz
+            )));
+        }
+        heatmap.queue_draw();
+    }
+
     fn update(&self, numeric: f64, display: &str, style: &MonitorStyle) {
+        self.update_with_series(numeric, display, style, &[(String::new(), numeric)]);
+    }
+
+    /// Like `update`, but charts `series` instead of `numeric`, e.g. receive and transmit
+    /// separately.
+    fn update_with_series(
+        &self,
+        numeric: f64,
+        display: &str,
+        style: &MonitorStyle,
+        series: &[(String, f64)],
+    ) {
         self.set_style(style);
         self.value_label.set_text(display);
 
@@ -212,25 +520,54 @@ impl MonitorCard {
             Trend::Stable => "trend-stable",
         });
 
-        let clamped = numeric.min(self.scale_max);
-        self.bar.set_value(clamped);
-
-        {
-            let mut hist = self.history.borrow_mut();
-            hist.push(clamped);
-            
-            // Adaptive history based on width (approx 1px per sample)
-            let width = self.chart.width();
-            let max_samples = if width > 0 { width.try_into().unwrap_or(120) } else { 120 };
-            
-            if hist.len() > max_samples {
-                let remove_count = hist.len() - max_samples;
-                for _ in 0..remove_count {
-                     hist.remove(0);
-                }
+        self.bar.set_value(numeric.min(self.scale_max));
+        self.record_series(series);
+        self.set_level(SensorLevel::of(self.thresholds.get(), numeric));
+    }
+
+    fn set_level(&self, level: SensorLevel) {
+        self.level.set(level);
+        set_level_class(&self.container, level);
+        set_level_class(&self.bar, level);
+        self.chart.set_level_colour(level.chart_colour());
+    }
+
+    /// Replaces the per-reading rows under the card value, reusing labels when the row count
+    /// is unchanged.
+    fn update_rows(&self, rows: &[(String, SensorLevel)]) {
+        let mut labels = Vec::new();
+        let mut child = self.details.first_child();
+        while let Some(widget) = child {
+            child = widget.next_sibling();
+            if let Ok(label) = widget.downcast::<Label>() {
+                labels.push(label);
+            }
+        }
+
+        if labels.len() != rows.len() {
+            for label in &labels {
+                self.details.remove(label);
+            }
+            labels = rows
+                .iter()
+                .map(|_| {
+                    let label = Label::new(None);
+                    label.add_css_class("card-detail");
+                    label.set_halign(Align::Start);
+                    self.details.append(&label);
+                    label
+                })
+                .collect();
+        }
+
+        for (label, (text, level)) in labels.iter().zip(rows) {
+            label.set_text(text);
+            for cls in ["sensor-ok", "sensor-warn", "sensor-crit"] {
+                label.remove_css_class(cls);
             }
+            label.add_css_class(level.css_class());
         }
-        self.chart.queue_draw();
+        self.details.set_visible(!rows.is_empty());
     }
 
     fn set_visible(&self, show: bool) {
@@ -238,20 +575,151 @@ impl MonitorCard {
     }
 }
 
+/// Record mode state shared by every card menu and the `--record` flag. Samples are written by
+/// the monitoring thread; this only tells it where.
+#[derive(Clone)]
+struct RecordingControl {
+    control: async_channel::Sender<MonitorControl>,
+    path: Rc<RefCell<Option<PathBuf>>>,
+    indicator: Label,
+}
+
+impl RecordingControl {
+    fn new(control: async_channel::Sender<MonitorControl>) -> Self {
+        let indicator = Label::new(Some("● REC"));
+        indicator.add_css_class("recording-indicator");
+        indicator.set_visible(false);
+        Self {
+            control,
+            path: Rc::new(RefCell::new(None)),
+            indicator,
+        }
+    }
+
+    fn is_recording(&self) -> bool {
+        self.path.borrow().is_some()
+    }
+
+    fn start(&self, path: PathBuf, settings: &Settings) {
+        let target = RecordTarget {
+            format: RecordFormat::from_path(&path),
+            path: path.clone(),
+            max_bytes: settings.recording.max_file_mb.max(1) * 1024 * 1024,
+            keep: settings.recording.keep_files,
+            network_filter: settings.network_filter.clone(),
+        };
+        let _ = self
+            .control
+            .try_send(MonitorControl::SetRecording(Some(target)));
+        self.indicator
+            .set_tooltip_text(Some(&format!("Recording to {}", path.display())));
+        self.indicator.set_visible(true);
+        self.path.replace(Some(path));
+    }
+
+    /// Starts a recording named after the current time in the configured directory.
+    fn start_new(&self, settings: &Settings) {
+        let Some(dir) = settings.recording.directory() else {
+            eprintln!("No directory available for recordings");
+            return;
+        };
+        let stamp = glib::DateTime::now_local()
+            .ok()
+            .and_then(|now| now.format("%Y%m%d-%H%M%S").ok())
+            .map_or_else(|| history::now_secs().to_string(), |s| s.to_string());
+        let name = format!("vitray-{stamp}.{}", settings.recording.format.extension());
+        self.start(dir.join(name), settings);
+    }
+
+    fn stop(&self) {
+        let _ = self.control.try_send(MonitorControl::SetRecording(None));
+        self.indicator.set_visible(false);
+        self.path.replace(None);
+    }
+}
+
 #[derive(Clone)]
 struct MonitorGroup {
     cpu: MonitorCard,
     gpu: MonitorCard,
     ram: MonitorCard,
     net: MonitorCard,
+    disk: MonitorCard,
+    thermals: MonitorCard,
+    battery: MonitorCard,
+    /// One card per command sensor, laid out in the grid after the built-in cards.
+    commands: Rc<RefCell<Vec<(CommandSensorConfig, MonitorCard)>>>,
+    grid: Grid,
+    history: Rc<RefCell<HistoryStore>>,
+    chart_range: Rc<Cell<ChartRange>>,
+    recording: RecordingControl,
+    settings: Rc<RefCell<Settings>>,
 }
 
 impl MonitorGroup {
+    /// Grid row of the first command sensor card.
+    const COMMAND_ROW: i32 = 4;
+
+    fn all_cards(&self) -> Vec<MonitorCard> {
+        let mut cards = vec![
+            self.cpu.clone(),
+            self.gpu.clone(),
+            self.ram.clone(),
+            self.net.clone(),
+            self.disk.clone(),
+            self.thermals.clone(),
+            self.battery.clone(),
+        ];
+        cards.extend(self.commands.borrow().iter().map(|(_, card)| card.clone()));
+        cards
+    }
+
+    /// Switches every chart to `range`, reloading points from the history store.
+    fn set_chart_range(&self, range: ChartRange) {
+        if self.chart_range.get() == range {
+            return;
+        }
+        self.chart_range.set(range);
+        for card in self.all_cards() {
+            card.reload_history();
+        }
+    }
+
+    fn set_levels(&self, levels: &CardLevels) {
+        for (card, thresholds) in [
+            (&self.cpu, levels.cpu),
+            (&self.gpu, levels.gpu),
+            (&self.ram, levels.ram),
+            (&self.net, levels.network),
+            (&self.disk, levels.disk),
+            (&self.thermals, levels.thermals),
+            (&self.battery, levels.battery),
+        ] {
+            card.thresholds.set(thresholds);
+            if thresholds.is_none() {
+                card.set_level(SensorLevel::Ok);
+            }
+        }
+    }
+
+    fn set_chart_options(&self, settings: &Settings) {
+        for card in self.all_cards() {
+            card.chart
+                .set_options(settings.chart_scale, settings.chart_axes, &settings.theme);
+        }
+    }
+
     fn set_style(&self, style: &MonitorStyle) {
         self.cpu.set_style(style);
         self.gpu.set_style(style);
         self.ram.set_style(style);
         self.net.set_style(style);
+        self.disk.set_style(style);
+        self.thermals.set_style(style);
+        self.battery.set_style(style);
+        for (_, card) in self.commands.borrow().iter() {
+            card.set_style(style);
+        }
     }
 
     fn set_visibility(&self, settings: &Settings) {
@@ -259,6 +727,315 @@ impl MonitorGroup {
         self.gpu.set_visible(settings.show_gpu);
         self.ram.set_visible(settings.show_ram);
         self.net.set_visible(settings.show_network);
+        self.disk.set_visible(settings.show_disk);
+        self.thermals.set_visible(settings.show_thermals);
+        self.battery.set_visible(settings.show_battery);
+    }
+
+    /// Rebuilds the command sensor cards when the configured sensors changed. Returns whether
+    /// anything changed.
+    fn sync_commands(&self, settings: &Settings) -> bool {
+        let mut commands = self.commands.borrow_mut();
+        let unchanged = commands.len() == settings.command_sensors.len()
+            && commands
+                .iter()
+                .zip(&settings.command_sensors)
+                .all(|((config, _), wanted)| config == wanted);
+        if unchanged {
+            return false;
+        }
+
+        for (_, card) in commands.drain(..) {
+            self.grid.remove(&card.container);
+        }
+        for (index, config) in settings.command_sensors.iter().enumerate() {
+            let unit = if config.unit.is_empty() {
+                String::new()
+            } else {
+                format!(" {}", config.unit)
+            };
+            let card = MonitorCard::new(&config.name, &settings.monitor_style, config.max)
+                .with_unit(&unit)
+                .with_history(
+                    &self.history,
+                    &format!("command-{}", config.name),
+                    &self.chart_range,
+                );
+            self.install_card_menu(&card);
+            #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+            let index = index as i32;
+            self.grid
+                .attach(&card.container, index % 2, Self::COMMAND_ROW + index / 2, 1, 1);
+            commands.push((config.clone(), card));
+        }
+        true
+    }
+
+    /// Right-click menu on a card: record mode toggle and history export.
+    fn install_card_menu(&self, card: &MonitorCard) {
+        let gesture = GestureClick::new();
+        gesture.set_button(3);
+        let recording = self.recording.clone();
+        let settings = self.settings.clone();
+        let chart = card.chart.clone();
+        let name = card
+            .history_source
+            .as_ref()
+            .map_or_else(|| "chart".to_string(), |source| source.key.clone());
+        let range = self.chart_range.clone();
+        gesture.connect_pressed(move |gesture, _, x, y| {
+            let Some(anchor) = gesture.widget() else {
+                return;
+            };
+            let popover =
+                build_card_popover(&anchor, &recording, &settings, &chart, &name, range.get());
+            #[allow(clippy::cast_possible_truncation)]
+            popover.set_pointing_to(Some(&gdk::Rectangle::new(x as i32, y as i32, 1, 1)));
+            popover.popup();
+        });
+        card.container.add_controller(gesture);
+    }
+
+    fn card_for(&self, metric: &AlertMetric) -> Option<MonitorCard> {
+        match metric {
+            AlertMetric::CpuUsage => Some(self.cpu.clone()),
+            AlertMetric::RamUsage | AlertMetric::SwapUsage => Some(self.ram.clone()),
+            AlertMetric::GpuUsage => Some(self.gpu.clone()),
+            AlertMetric::DiskFree => Some(self.disk.clone()),
+            AlertMetric::Temperature => Some(self.thermals.clone()),
+            AlertMetric::NetworkRate => Some(self.net.clone()),
+            AlertMetric::BatteryCharge => Some(self.battery.clone()),
+            AlertMetric::Command(name) => self
+                .commands
+                .borrow()
+                .iter()
+                .find(|(config, _)| &config.name == name)
+                .map(|(_, card)| card.clone()),
+        }
+    }
+
+    /// Outlines the cards whose metrics have a firing alert.
+    fn highlight_alerts<'a>(&self, firing: impl Iterator<Item = &'a AlertMetric>) {
+        let cards = self.all_cards();
+        for card in &cards {
+            card.container.remove_css_class("alert-firing");
+        }
+        for card in firing.filter_map(|metric| self.card_for(metric)) {
+            card.container.add_css_class("alert-firing");
+        }
+    }
+
+    fn update_commands(&self, data: &MonitorData, style: &MonitorStyle) {
+        for (config, card) in self.commands.borrow().iter() {
+            let Some(reading) = data.commands.iter().find(|r| r.name == config.name) else {
+                continue;
+            };
+            let Some(value) = reading.value else {
+                card.value_label.set_text("N/A");
+                let error = reading.error.clone().unwrap_or_default();
+                card.update_rows(&[(error, SensorLevel::Warn)]);
+                continue;
+            };
+
+            let unit = if config.unit.is_empty() {
+                String::new()
+            } else {
+                format!(" {}", config.unit)
+            };
+            let number = if value.fract() == 0.0 && value.abs() < 1e15 {
+                format!("{value:.0}")
+            } else {
+                format!("{value:.2}")
+            };
+            card.update(value, &format!("{number}{unit}"), style);
+            match &reading.text {
+                Some(text) => card.update_rows(&[(text.clone(), SensorLevel::Ok)]),
+                None => card.update_rows(&[]),
+            }
+        }
+    }
+}
+
+/// Vitals for a host sampled over `ssh`, in its own window while the host is pinned. The
+/// session ends when the window goes away.
+#[derive(Clone)]
+struct RemotePanel {
+    window: ApplicationWindow,
+    status: Label,
+    cpu: MonitorCard,
+    gpu: MonitorCard,
+    ram: MonitorCard,
+    net: MonitorCard,
+    disk: MonitorCard,
+    thermals: MonitorCard,
+}
+
+impl RemotePanel {
+    /// Opens the window and starts the session. `on_close` runs when the user closes it.
+    fn open(
+        app: &Application,
+        host: &RemoteHost,
+        settings: &Rc<RefCell<Settings>>,
+        on_close: impl Fn() + 'static,
+    ) -> Self {
+        let snapshot = settings.borrow().clone();
+        let style = &snapshot.monitor_style;
+        let window = create_standalone_window(app, &format!("Vitals · {}", host.name), 600, 300);
+        window.add_css_class("monitor-window");
+        let panel = Self {
+            window,
+            status: Label::new(Some("Connecting…")),
+            cpu: MonitorCard::with_cores("CPU", style, 100.0).with_unit("%"),
+            gpu: MonitorCard::new("GPU", style, 100.0).with_unit("%"),
+            ram: MonitorCard::with_breakdown("RAM", style, 100.0).with_unit("%"),
+            net: MonitorCard::new("Network", style, 2000.0).with_unit(" KB/s"),
+            disk: MonitorCard::new("Disk", style, 100.0).with_unit("%"),
+            thermals: MonitorCard::new("Thermals", style, 110.0).with_unit("°C"),
+        };
+        panel.apply_settings(&snapshot);
+
+        let section = Box::new(Orientation::Vertical, 8);
+        section.add_css_class("monitoring-shell");
+        section.add_css_class("glass-panel");
+        let header = Box::new(Orientation::Horizontal, 8);
+        header.add_css_class("vitals-header");
+        header.append(&Label::new(Some(&host.name)));
+        panel.status.add_css_class("card-detail");
+        panel.status.set_hexpand(true);
+        panel.status.set_halign(Align::End);
+        panel.status.set_tooltip_text(Some(&host.host));
+        header.append(&panel.status);
+        section.append(&header);
+
+        let grid = Grid::new();
+        grid.set_column_spacing(12);
+        grid.set_row_spacing(12);
+        grid.add_css_class("monitoring-grid");
+        for (index, card) in (0..).zip(panel.cards()) {
+            grid.attach(&card.container, index % 2, index / 2, 1, 1);
+        }
+        section.append(&grid);
+        panel.window.set_child(Some(&section));
+
+        let monitor = RemoteMonitor::start(host);
+        let receiver = monitor.receiver.clone();
+        let session = RefCell::new(Some(monitor));
+        panel.window.connect_destroy(move |_| {
+            session.borrow_mut().take();
+        });
+        panel.window.connect_close_request(move |_| {
+            on_close();
+            glib::Propagation::Proceed
+        });
+
+        let (panel_clone, settings) = (panel.clone(), settings.clone());
+        glib::MainContext::default().spawn_local(async move {
+            while let Ok(event) = receiver.recv().await {
+                match event {
+                    RemoteEvent::Sample(data) => panel_clone.update(&data, &settings.borrow()),
+                    RemoteEvent::Status(text) => panel_clone.status.set_text(&text),
+                }
+            }
+        });
+
+        panel.window.present();
+        panel
+    }
+
+    const fn cards(&self) -> [&MonitorCard; 6] {
+        [
+            &self.cpu,
+            &self.gpu,
+            &self.ram,
+            &self.net,
+            &self.disk,
+            &self.thermals,
+        ]
+    }
+
+    fn apply_settings(&self, settings: &Settings) {
+        apply_theme_fixed(&self.window, &settings.theme);
+        self.window.set_resizable(!settings.lock_size);
+        let levels = &settings.card_levels;
+        let thresholds = [
+            levels.cpu,
+            levels.gpu,
+            levels.ram,
+            levels.network,
+            levels.disk,
+            levels.thermals,
+        ];
+        for (card, thresholds) in self.cards().into_iter().zip(thresholds) {
+            card.set_style(&settings.monitor_style);
+            card.chart
+                .set_options(settings.chart_scale, settings.chart_axes, &settings.theme);
+            card.thresholds.set(thresholds);
+            if thresholds.is_none() {
+                card.set_level(SensorLevel::Ok);
+            }
+        }
+    }
+
+    fn update(&self, data: &MonitorData, settings: &Settings) {
+        let style = &settings.monitor_style;
+        let cpu = f64::from(data.cpu_usage);
+        self.cpu.update(cpu, &format!("{cpu:.0}%"), style);
+        self.cpu.update_cores(&data.cores);
+
+        let gpu = data.gpu_usage();
+        self.gpu.set_visible(gpu.is_some());
+        if let Some(gpu) = gpu {
+            self.gpu.update(f64::from(gpu), &format!("{gpu:.0}%"), style);
+        }
+
+        #[allow(clippy::cast_precision_loss)]
+        let gib = |bytes: u64| bytes as f64 / 1024.0 / 1024.0 / 1024.0;
+        let ram_pct = if data.ram_total > 0 {
+            gib(data.ram_used) / gib(data.ram_total) * 100.0
+        } else {
+            0.0
+        };
+        let ram_display = format!("{:.1}/{:.1} GB", gib(data.ram_used), gib(data.ram_total));
+        self.ram.update(ram_pct, &ram_display, style);
+        update_memory_breakdown(&self.ram, data);
+
+        let (rx, tx) = data
+            .network_interfaces(&settings.network_filter, None)
+            .fold((0.0, 0.0), |(rx, tx), iface| {
+                (rx + iface.rx_bytes_per_sec, tx + iface.tx_bytes_per_sec)
+            });
+        self.net.update_with_series(
+            (rx + tx) / 1024.0,
+            &format!("↓{} ↑{}", format_rate(rx), format_rate(tx)),
+            style,
+            &[("rx".to_string(), rx / 1024.0), ("tx".to_string(), tx / 1024.0)],
+        );
+
+        // The local mount selection means nothing here, so the fullest mount is shown.
+        #[allow(clippy::cast_precision_loss)]
+        let fullest = data
+            .mounts
+            .iter()
+            .filter(|m| m.total > 0)
+            .map(|m| (m, m.used as f64 / m.total as f64 * 100.0))
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        match fullest {
+            Some((mount, pct)) => self.disk.update(
+                pct,
+                &format!("{pct:.0}% · {}", mount.mount_point),
+                style,
+            ),
+            None => self.disk.update(0.0, "N/A", style),
+        }
+
+        self.thermals.set_visible(!data.temperatures.is_empty());
+        update_thermals(&self.thermals, data, style);
+
+        self.status.set_text(&format!(
+            "Load {:.2} · up {}",
+            data.load_average.one,
+            format_uptime(data.uptime_secs)
+        ));
     }
 }
 
@@ -266,29 +1043,40 @@ impl MonitorGroup {
 struct UiHandles {
     main_window: ApplicationWindow,
     terminal_window: ApplicationWindow,
+    terminal_tabs: TerminalTabs,
     monitor_window: ApplicationWindow,
     shortcuts_window: ApplicationWindow,
     monitor_cards: MonitorGroup,
+    process_panel: ProcessPanel,
+    process_expander: gtk4::Expander,
+    cgroup_panel: CgroupPanel,
+    cgroup_expander: gtk4::Expander,
     performance_strip: PerformanceStrip,
+    power_indicator: Label,
     _shortcuts_panel: ShortcutsPanel,
     settings: Rc<RefCell<Settings>>,
     style_provider: CssProvider,
+    monitor_control: async_channel::Sender<MonitorControl>,
+    alerts: Rc<RefCell<AlertEngine>>,
+    /// Open remote host panels with the configuration each was started from.
+    remote_panels: Rc<RefCell<Vec<(RemoteHost, RemotePanel)>>>,
 }
 
 #[derive(Clone)]
 struct HeaderBar {
     widget: Box,
+    power_indicator: Label,
     settings_btn: Button,
     shortcuts_btn: Button,
 }
 
 #[allow(clippy::too_many_lines)]
-pub fn build_ui(app: &Application) {
+pub fn build_ui(app: &Application, record: Option<&Path>) {
     let settings = Rc::new(RefCell::new(Settings::load()));
     let _shortcuts = Shortcuts::load();
 
     let provider = CssProvider::new();
-    if std::path::Path::new("src/style.css").exists() {
+    if Path::new("src/style.css").exists() {
         provider.load_from_path("src/style.css");
     } else {
         let asset_path = crate::platform::get_asset_path().join("style.css");
@@ -335,8 +1123,6 @@ pub fn build_ui(app: &Application) {
     shortcuts_window.add_css_class("shortcuts-window");
 
 
-    // --- Terminal channel ---
-    let (sender, receiver) = async_channel::unbounded::<String>();
 
 
     // --- Header & Perf Strip (Main Window Content) ---
@@ -357,12 +1143,8 @@ pub fn build_ui(app: &Application) {
     terminal_header.add_css_class("terminal-header");
     let tabs_btn = Button::with_label("+ Tab");
     tabs_btn.add_css_class("pill-btn");
-    
-    let notebook = Notebook::new();
-    notebook.set_show_tabs(true);
-    notebook.set_scrollable(true);
-    notebook.add_css_class("terminal-notebook");
-    notebook.set_group_name(Some("terminal-tabs"));
+
+    let terminal_tabs = TerminalTabs::new(&settings.borrow().shell);
     let shortcuts_btn = Button::with_label("Shortcuts");
     shortcuts_btn.add_css_class("pill-btn");
     terminal_header.append(&Label::new(Some("Terminal")));
@@ -370,27 +1152,33 @@ pub fn build_ui(app: &Application) {
     terminal_header.append(&shortcuts_btn);
     terminal_header.set_halign(Align::Start);
 
-    // Initial tab
-    let shell = settings.borrow().shell.clone();
-    let terminal = create_terminal(&shell, None, None);
-    let scrolled = gtk4::ScrolledWindow::new();
-    scrolled.set_child(Some(terminal.widget()));
-    scrolled.set_vexpand(true);
-    
-    let tab_label = build_tab_label(&notebook, &scrolled, "Terminal");
-    notebook.append_page(&scrolled, Some(&tab_label));
-    notebook.set_tab_reorderable(&scrolled, true);
-    notebook.set_tab_detachable(&scrolled, true);
+    {
+        let s = settings.borrow();
+        terminal_tabs.set_profiles(&s.terminal_profiles, &s.default_terminal_profile);
+    }
+
+    // Last session's tabs, or a single fresh one
+    let restore = settings.borrow().restore_terminal_session;
+    if !(restore && terminal_tabs.restore(&TerminalSession::load())) {
+        terminal_tabs.add_tab(Some("Terminal"));
+    }
+    terminal_tabs.set_persist(restore);
 
     terminal_section.append(&terminal_header);
-    terminal_section.append(&notebook);
+    terminal_section.append(&terminal_tabs.notebook);
     terminal_window.set_child(Some(&terminal_section));
 
     // --- Monitoring Section Content ---
+    let monitor_service = crate::monitor::start_monitoring_service(
+        SamplingConfig::from_settings(&settings.borrow()),
+        settings.borrow().command_sensors.clone(),
+        settings.borrow().metrics_port,
+    );
+    let monitor_receiver = monitor_service.receiver;
+
     let monitoring_section = Box::new(Orientation::Vertical, 8);
     monitoring_section.add_css_class("monitoring-shell");
     monitoring_section.add_css_class("glass-panel");
-    monitoring_section.append(&Label::new(Some("Vitals")));
 
     let grid = Grid::new();
     grid.set_column_spacing(12);
@@ -400,22 +1188,88 @@ pub fn build_ui(app: &Application) {
     grid.set_margin_end(4);
     grid.add_css_class("monitoring-grid");
 
+    let history = Rc::new(RefCell::new(HistoryStore::open_default()));
+    {
+        let history = history.clone();
+        app.connect_shutdown(move |_| history.borrow_mut().flush());
+    }
+    let chart_range = Rc::new(Cell::new(settings.borrow().chart_range));
+    let style = settings.borrow().monitor_style.clone();
     let monitor_cards = MonitorGroup {
-        cpu: MonitorCard::new("CPU", &settings.borrow().monitor_style, 100.0),
-        gpu: MonitorCard::new("GPU", &settings.borrow().monitor_style, 100.0),
-        ram: MonitorCard::new("RAM", &settings.borrow().monitor_style, 100.0),
-        net: MonitorCard::new("Network", &settings.borrow().monitor_style, 2000.0),
+        cpu: MonitorCard::with_cores("CPU", &style, 100.0)
+            .with_unit("%")
+            .with_history(&history, "cpu", &chart_range),
+        gpu: MonitorCard::new("GPU", &style, 100.0)
+            .with_unit("%")
+            .with_history(&history, "gpu", &chart_range),
+        ram: MonitorCard::with_breakdown("RAM", &style, 100.0)
+            .with_unit("%")
+            .with_history(&history, "ram", &chart_range),
+        net: MonitorCard::new("Network", &style, 2000.0)
+            .with_unit(" KB/s")
+            .with_history(&history, "network", &chart_range),
+        disk: MonitorCard::new("Disk", &style, 100.0)
+            .with_unit("%")
+            .with_history(&history, "disk", &chart_range),
+        thermals: MonitorCard::new("Thermals", &style, 110.0)
+            .with_unit("°C")
+            .with_history(&history, "thermals", &chart_range),
+        battery: MonitorCard::new("Battery", &style, 100.0)
+            .with_unit("%")
+            .with_history(&history, "battery", &chart_range),
+        commands: Rc::new(RefCell::new(Vec::new())),
+        grid: grid.clone(),
+        history,
+        chart_range,
+        recording: RecordingControl::new(monitor_service.control.clone()),
+        settings: settings.clone(),
     };
+    for card in monitor_cards.all_cards() {
+        monitor_cards.install_card_menu(&card);
+    }
+    if let Some(path) = record {
+        monitor_cards
+            .recording
+            .start(path.to_path_buf(), &settings.borrow());
+    }
 
     grid.attach(&monitor_cards.cpu.container, 0, 0, 1, 1);
     grid.attach(&monitor_cards.gpu.container, 1, 0, 1, 1);
     grid.attach(&monitor_cards.ram.container, 0, 1, 1, 1);
     grid.attach(&monitor_cards.net.container, 1, 1, 1, 1);
+    grid.attach(&monitor_cards.disk.container, 0, 2, 1, 1);
+    grid.attach(&monitor_cards.thermals.container, 1, 2, 1, 1);
+    grid.attach(&monitor_cards.battery.container, 0, 3, 1, 1);
+    monitoring_section.append(&build_range_selector(&monitor_cards, &settings));
     monitoring_section.append(&grid);
+
+    let process_panel = ProcessPanel::new(monitor_service.control.clone());
+    let process_expander = gtk4::Expander::new(Some("Top processes"));
+    process_expander.add_css_class("process-expander");
+    process_expander.set_child(Some(&process_panel.widget));
+    monitoring_section.append(&process_expander);
+    {
+        let control = monitor_service.control.clone();
+        process_expander.connect_expanded_notify(move |expander| {
+            let _ = control.try_send(MonitorControl::SetProcessesEnabled(expander.is_expanded()));
+        });
+    }
+
+    let cgroup_panel = CgroupPanel::new();
+    let cgroup_expander = gtk4::Expander::new(Some("Containers & units"));
+    cgroup_expander.add_css_class("process-expander");
+    cgroup_expander.set_child(Some(&cgroup_panel.widget));
+    monitoring_section.append(&cgroup_expander);
+    {
+        let control = monitor_service.control.clone();
+        cgroup_expander.connect_expanded_notify(move |expander| {
+            let _ = control.try_send(MonitorControl::SetCgroupsEnabled(expander.is_expanded()));
+        });
+    }
     monitor_window.set_child(Some(&monitoring_section));
 
     // --- Shortcuts Content ---
-    let shortcuts_panel = ShortcutsPanel::new(&main_window, sender);
+    let shortcuts_panel = ShortcutsPanel::new(&main_window, terminal_tabs.clone());
     shortcuts_panel.set_revealed(true); // Always visible in its own window
     
     let shortcuts_wrapper = Box::new(Orientation::Vertical, 0);
@@ -425,20 +1279,10 @@ pub fn build_ui(app: &Application) {
     shortcuts_window.set_child(Some(&shortcuts_wrapper));
 
 
-    // Terminal channel feed
-    glib::MainContext::default().spawn_local(async move {
-        while let Ok(cmd) = receiver.recv().await {
-            let cmd_with_newline = format!("{cmd}\n");
-            terminal.feed_child(cmd_with_newline.as_bytes());
-        }
-    });
-
     {
-        let notebook_clone = notebook;
-        let settings_clone = settings.clone();
+        let tabs = terminal_tabs.clone();
         tabs_btn.connect_clicked(move |_| {
-            let shell = settings_clone.borrow().shell.clone();
-            add_terminal_tab(&notebook_clone, &shell);
+            tabs.add_tab(None);
         });
     }
 
@@ -456,13 +1300,22 @@ pub fn build_ui(app: &Application) {
     let handles = UiHandles {
         main_window: main_window.clone(),
         terminal_window,
+        terminal_tabs,
         monitor_window,
         shortcuts_window,
         monitor_cards,
+        process_panel,
+        process_expander,
+        cgroup_panel,
+        cgroup_expander,
         performance_strip,
+        power_indicator: header.power_indicator.clone(),
         _shortcuts_panel: shortcuts_panel,
         settings: settings.clone(),
         style_provider: dynamic_provider,
+        monitor_control: monitor_service.control,
+        alerts: Rc::new(RefCell::new(AlertEngine::new())),
+        remote_panels: Rc::new(RefCell::new(Vec::new())),
     };
 
     // Context menu / right click (Attached to main window for now)
@@ -503,9 +1356,6 @@ pub fn build_ui(app: &Application) {
 
 
     // --- Update Loop (Async) ---
-    let monitor_receiver = crate::monitor::start_monitoring_service();
-    let mut last_net: Option<(u64, u64)> = None;
-
     let handles_weak = handles; // Actually we need strong reference or weak? 
     // spawn_local keeps the future alive. We need to move handles in.
     // But handles is Clone.
@@ -516,20 +1366,43 @@ pub fn build_ui(app: &Application) {
             let style = active_settings.monitor_style.clone();
 
             let cpu = f64::from(data.cpu_usage);
+            let cpu_display = data.average_frequency_mhz().map_or_else(
+                || format!("{cpu:.0}%"),
+                #[allow(clippy::cast_precision_loss)]
+                |mhz| format!("{cpu:.0}% · {:.1} GHz", mhz as f64 / 1000.0),
+            );
             handles_weak
                 .monitor_cards
                 .cpu
-                .update(cpu, &format!("{cpu:.0}%"), &style);
+                .update(cpu, &cpu_display, &style);
+            handles_weak.monitor_cards.cpu.update_cores(&data.cores);
 
-            let gpu_usage = data.gpu_usage;
+            let gpu_usage = data.gpu_usage();
             if let Some(gpu) = gpu_usage {
-                handles_weak
-                    .monitor_cards
-                    .gpu
-                    .update(f64::from(gpu), &format!("{gpu:.0}%"), &style);
+                // One chart series per adapter that reports a busy figure.
+                let mut per_gpu: Vec<(String, f64)> = data
+                    .gpus
+                    .iter()
+                    .filter_map(|g| g.busy_percent.map(|busy| (g.id.clone(), f64::from(busy))))
+                    .collect();
+                if per_gpu.len() < 2 {
+                    per_gpu = vec![(String::new(), f64::from(gpu))];
+                }
+                handles_weak.monitor_cards.gpu.update_with_series(
+                    f64::from(gpu),
+                    &format!("{gpu:.0}%"),
+                    &style,
+                    &per_gpu,
+                );
             } else {
                 handles_weak.monitor_cards.gpu.update(0.0, "N/A", &style);
             }
+            let gpu_rows: Vec<(String, SensorLevel)> = data
+                .gpus
+                .iter()
+                .map(|g| (describe_gpu(g), SensorLevel::Ok))
+                .collect();
+            handles_weak.monitor_cards.gpu.update_rows(&gpu_rows);
 
             let (used, total) = (data.ram_used, data.ram_total);
             #[allow(clippy::cast_precision_loss)]
@@ -547,41 +1420,122 @@ pub fn build_ui(app: &Application) {
                 &format!("{used_gb:.1}/{total_gb:.1} GB"),
                 &style,
             );
+            update_memory_breakdown(&handles_weak.monitor_cards.ram, &data);
+
+            let selected = active_settings.network_interface.as_deref();
+            let (mut rx_rate, mut tx_rate) = (0.0_f64, 0.0_f64);
+            let (mut rx_total, mut tx_total) = (0_u64, 0_u64);
+            let (mut rx_packets, mut tx_packets, mut errors) = (0.0_f64, 0.0_f64, 0.0_f64);
+            let mut counted = 0;
+            for iface in data.network_interfaces(&active_settings.network_filter, selected) {
+                rx_rate += iface.rx_bytes_per_sec;
+                tx_rate += iface.tx_bytes_per_sec;
+                rx_total += iface.rx_bytes;
+                tx_total += iface.tx_bytes;
+                rx_packets += iface.rx_packets_per_sec;
+                tx_packets += iface.tx_packets_per_sec;
+                errors += iface.errors_per_sec;
+                counted += 1;
+            }
+            let total_speed = (rx_rate + tx_rate) / 1024.0;
+            let rx_display = format_rate(rx_rate);
+            let tx_display = format_rate(tx_rate);
 
-            let (rx_raw, tx_raw) = (data.rx_bytes, data.tx_bytes);
-            let (rx_rate, tx_rate) = if let Some((prev_rx, prev_tx)) = last_net {
-                (
-                    rx_raw.saturating_sub(prev_rx),
-                    tx_raw.saturating_sub(prev_tx),
-                )
-            } else {
-                (0, 0)
-            };
-            last_net = Some((rx_raw, tx_raw));
-            #[allow(clippy::cast_precision_loss)]
-            let total_speed = (rx_rate + tx_rate) as f64 / 1024.0;
+            handles_weak.monitor_cards.net.update_with_series(
+                total_speed,
+                &format!("↓{rx_display} ↑{tx_display}"),
+                &style,
+                &[("rx".to_string(), rx_rate / 1024.0), ("tx".to_string(), tx_rate / 1024.0)],
+            );
             #[allow(clippy::cast_precision_loss)]
-            let rx_kb = rx_rate as f64 / 1024.0;
+            let gib = |bytes: u64| bytes as f64 / 1024.0 / 1024.0 / 1024.0;
+            handles_weak.monitor_cards.net.container.set_tooltip_text(Some(&format!(
+                "{}\n{:.2} GB in, {:.2} GB out since boot\n\
+                 {rx_packets:.0} pkt/s in, {tx_packets:.0} pkt/s out, {errors:.0} errors",
+                selected.map_or_else(|| format!("All interfaces ({counted})"), str::to_string),
+                gib(rx_total),
+                gib(tx_total),
+            )));
+
+            let selected_mounts: Vec<_> = data
+                .selected_mounts(&active_settings.disk_mounts)
+                .collect();
             #[allow(clippy::cast_precision_loss)]
-            let tx_kb = tx_rate as f64 / 1024.0;
-            
-            let rx_display = if rx_kb > 1024.0 {
-                format!("{:.1} MB/s", rx_kb / 1024.0)
-            } else {
-                format!("{rx_kb:.0} KB/s")
-            };
-            
-            let tx_display = if tx_kb > 1024.0 {
-                format!("{:.1} MB/s", tx_kb / 1024.0)
+            let fullest = selected_mounts
+                .iter()
+                .filter(|m| m.total > 0)
+                .map(|m| (m, m.used as f64 / m.total as f64 * 100.0))
+                .max_by(|a, b| a.1.total_cmp(&b.1));
+            let (read_rate, write_rate) = data.disk_io.iter().fold((0.0, 0.0), |acc, d| {
+                (acc.0 + d.read_bytes_per_sec, acc.1 + d.write_bytes_per_sec)
+            });
+            let io_display = format!("R {} W {}", format_rate(read_rate), format_rate(write_rate));
+            if let Some((mount, pct)) = fullest {
+                #[allow(clippy::cast_precision_loss)]
+                let free_gb =
+                    mount.total.saturating_sub(mount.used) as f64 / 1024.0 / 1024.0 / 1024.0;
+                handles_weak.monitor_cards.disk.update(
+                    pct,
+                    &format!("{pct:.0}% · {free_gb:.1} GB free\n{io_display}"),
+                    &style,
+                );
             } else {
-                format!("{tx_kb:.0} KB/s")
-            };
-
-            handles_weak.monitor_cards.net.update(
-                total_speed.min(2000.0),
-                &format!("↓{rx_display} ↑{tx_display}"),
-                &style,
+                handles_weak
+                    .monitor_cards
+                    .disk
+                    .update(0.0, &format!("N/A\n{io_display}"), &style);
+            }
+            let mut disk_tooltip: Vec<String> = selected_mounts
+                .iter()
+                .map(|m| {
+                    #[allow(clippy::cast_precision_loss)]
+                    let (used_gb, total_gb) = (
+                        m.used as f64 / 1024.0 / 1024.0 / 1024.0,
+                        m.total as f64 / 1024.0 / 1024.0 / 1024.0,
+                    );
+                    format!("{} ({}): {used_gb:.1}/{total_gb:.1} GB", m.mount_point, m.device)
+                })
+                .collect();
+            disk_tooltip.extend(data.disk_io.iter().map(|d| {
+                format!(
+                    "{}: R {} W {}",
+                    d.device,
+                    format_rate(d.read_bytes_per_sec),
+                    format_rate(d.write_bytes_per_sec)
+                )
+            }));
+            handles_weak
+                .monitor_cards
+                .disk
+                .container
+                .set_tooltip_text(Some(&disk_tooltip.join("\n")));
+
+            update_thermals(&handles_weak.monitor_cards.thermals, &data, &style);
+            update_power(&handles_weak, &data, &active_settings);
+            handles_weak.monitor_cards.update_commands(&data, &style);
+
+            let events = handles_weak.alerts.borrow_mut().evaluate(
+                &active_settings,
+                &data,
+                std::time::Instant::now(),
             );
+            for event in &events {
+                send_alert_notification(&handles_weak.main_window, event);
+            }
+            handles_weak
+                .monitor_cards
+                .highlight_alerts(handles_weak.alerts.borrow().firing());
+
+            if active_settings.show_processes && handles_weak.process_expander.is_expanded() {
+                handles_weak
+                    .process_panel
+                    .update(&data.processes, active_settings.process_count);
+            }
+            if active_settings.show_cgroups && handles_weak.cgroup_expander.is_expanded() {
+                handles_weak
+                    .cgroup_panel
+                    .update(&data.cgroups, active_settings.cgroup_count);
+            }
 
             let gpu_label = gpu_usage.map(|gpu| format!("GPU {gpu:.0}%"));
             let net_label = format!("NET {total_speed:.0} KB/s");
@@ -591,101 +1545,352 @@ pub fn build_ui(app: &Application) {
                 gpu_label.as_deref().unwrap_or("GPU —"),
                 &format!("RAM {ram_pct:.0}%"),
                 &net_label,
+                &format!(
+                    "LOAD {:.2} · UP {}",
+                    data.load_average.one,
+                    format_uptime(data.uptime_secs)
+                ),
             );
+            handles_weak
+                .performance_strip
+                .set_levels(&handles_weak.monitor_cards);
+            handles_weak.performance_strip.sys.set_tooltip_text(Some(&format!(
+                "Load average: {:.2} / {:.2} / {:.2}\nUptime: {}",
+                data.load_average.one,
+                data.load_average.five,
+                data.load_average.fifteen,
+                format_uptime(data.uptime_secs)
+            )));
         }
     });
 }
 
-fn add_terminal_tab(notebook: &Notebook, shell: &str) {
-    let terminal = create_terminal(shell, None, None);
-    let scrolled = gtk4::ScrolledWindow::new();
-    scrolled.set_child(Some(terminal.widget()));
-    scrolled.set_vexpand(true);
-    
-    let idx = notebook.n_pages() + 1;
-    let title = format!("T{idx}");
-    let tab_label = build_tab_label(notebook, &scrolled, &title);
-    
-    notebook.append_page(&scrolled, Some(&tab_label));
-    notebook.set_tab_reorderable(&scrolled, true);
-    notebook.set_tab_detachable(&scrolled, true);
-    notebook.set_current_page(Some(notebook.n_pages() - 1));
+fn send_alert_notification(window: &ApplicationWindow, event: &AlertEvent) {
+    let Some(app) = window.application() else {
+        return;
+    };
+    let (title, body) = event.message();
+    let notification = gio::Notification::new(&title);
+    notification.set_body(Some(&body));
+    if event.fired {
+        notification.set_priority(gio::NotificationPriority::High);
+    }
+    // One id per rule, so the "back to normal" notice replaces the alert.
+    app.send_notification(Some(&format!("alert-{}", event.index)), &notification);
 }
 
-fn build_tab_label(notebook: &Notebook, page: &impl IsA<gtk4::Widget>, title: &str) -> Box {
-    let box_ = Box::new(Orientation::Horizontal, 4);
-    let label = Label::new(Some(title));
-    box_.append(&label);
-    
-    let close_btn = Button::from_icon_name("window-close-symbolic");
-    close_btn.add_css_class("flat");
-    close_btn.add_css_class("small-icon");
-    box_.append(&close_btn);
-    
-    let notebook_clone = notebook.clone();
-    let page_clone = page.clone();
-    close_btn.connect_clicked(move |_| {
-        if let Some(idx) = notebook_clone.page_num(&page_clone) {
-            notebook_clone.remove_page(Some(idx));
-        }
-    });
-    
-    // Rename on double click
-    let gesture = GestureClick::new();
-    gesture.set_button(1);
-    let label_clone = label;
-    let parent_win = notebook.root().and_then(|r| r.downcast::<gtk4::Window>().ok());
-    
-    gesture.connect_pressed(move |_gesture, n_press, _, _| {
-        if n_press == 2 {
-            // Simple rename dialog
-            if let Some(win) = &parent_win {
-                prompt_rename(win, &label_clone);
+/// "Vitals" title row with the 5 min / 1 h / 24 h chart range toggles.
+fn build_range_selector(cards: &MonitorGroup, settings: &Rc<RefCell<Settings>>) -> Box {
+    let row = Box::new(Orientation::Horizontal, 4);
+    row.add_css_class("vitals-header");
+    let title = Label::new(Some("Vitals"));
+    title.set_hexpand(true);
+    title.set_halign(Align::Start);
+    row.append(&title);
+    row.append(&cards.recording.indicator);
+
+    let mut group: Option<gtk4::ToggleButton> = None;
+    for range in [ChartRange::FiveMinutes, ChartRange::OneHour, ChartRange::Day] {
+        let btn = gtk4::ToggleButton::with_label(range.label());
+        btn.add_css_class("pill-btn");
+        btn.set_tooltip_text(Some("Chart time range"));
+        btn.set_group(group.as_ref());
+        btn.set_active(settings.borrow().chart_range == range);
+        let cards = cards.clone();
+        let settings = settings.clone();
+        btn.connect_toggled(move |btn| {
+            if !btn.is_active() {
+                return;
             }
-        }
-    });
-    box_.add_controller(gesture);
-    
-    box_
-}
-
-fn prompt_rename(parent: &gtk4::Window, label: &Label) {
-    let dialog = gtk4::Dialog::builder()
-        .transient_for(parent)
-        .modal(true)
-        .title("Rename Tab")
-        .build();
-        
-    let entry = gtk4::Entry::new();
-    entry.set_text(&label.text());
-    entry.set_activates_default(true);
-    
-    let content = dialog.content_area();
-    content.set_margin_top(10);
-    content.set_margin_bottom(10);
-    content.set_margin_start(10);
-    content.set_margin_end(10);
-    content.set_spacing(10);
-    content.append(&entry);
-    
-    let btn = dialog.add_button("Rename", gtk4::ResponseType::Ok);
-    btn.add_css_class("suggested-action");
-    dialog.set_default_response(gtk4::ResponseType::Ok);
-    
-    let label_clone = label.clone();
-    dialog.connect_response(move |d, resp| {
-        if resp == gtk4::ResponseType::Ok {
-            let text = entry.text();
-            if !text.is_empty() {
-                label_clone.set_text(&text);
+            cards.set_chart_range(range);
+            let mut current = settings.borrow_mut();
+            if current.chart_range != range {
+                current.chart_range = range;
+                current.save();
             }
+        });
+        row.append(&btn);
+        group.get_or_insert(btn);
+    }
+    row
+}
+
+fn build_card_popover(
+    anchor: &gtk4::Widget,
+    recording: &RecordingControl,
+    settings: &Rc<RefCell<Settings>>,
+    chart: &Chart,
+    name: &str,
+    range: ChartRange,
+) -> Popover {
+    let popover = Popover::builder().has_arrow(true).build();
+    popover.set_parent(anchor);
+
+    let column = Box::new(Orientation::Vertical, 6);
+    column.set_margin_top(8);
+    column.set_margin_bottom(8);
+    column.set_margin_start(8);
+    column.set_margin_end(8);
+
+    let record_toggle = gtk4::CheckButton::with_label("Record samples");
+    record_toggle.set_active(recording.is_recording());
+    if let Some(path) = recording.path.borrow().as_ref() {
+        record_toggle.set_tooltip_text(Some(&format!("Recording to {}", path.display())));
+    }
+    {
+        let (recording, settings, pop) = (recording.clone(), settings.clone(), popover.clone());
+        record_toggle.connect_toggled(move |toggle| {
+            if toggle.is_active() {
+                recording.start_new(&settings.borrow());
+            } else {
+                recording.stop();
+            }
+            pop.popdown();
+        });
+    }
+    column.append(&record_toggle);
+
+    let export_btn = Button::with_label("Export visible history…");
+    export_btn.add_css_class("flat");
+    {
+        let (chart, pop, name) = (chart.clone(), popover.clone(), name.to_string());
+        let file_name = format!("vitray-{name}-{}.csv", range.label());
+        export_btn.connect_clicked(move |btn| {
+            pop.popdown();
+            let window = btn.root().and_downcast::<gtk4::Window>();
+            export_chart_history(window.as_ref(), &chart, &name, &file_name);
+        });
+    }
+    column.append(&export_btn);
+    popover.set_child(Some(&column));
+
+    popover.connect_closed(|pop| {
+        let pop = pop.clone();
+        glib::idle_add_local_once(move || pop.unparent());
+    });
+    popover
+}
+
+/// Asks for a file and writes the points currently on `chart` to it, as NDJSON when the name
+/// ends in `.ndjson`/`.jsonl` and CSV otherwise.
+fn export_chart_history(window: Option<&gtk4::Window>, chart: &Chart, name: &str, file_name: &str) {
+    let dialog = gtk4::FileChooserNative::new(
+        Some("Export visible history"),
+        window,
+        gtk4::FileChooserAction::Save,
+        Some("Export"),
+        Some("Cancel"),
+    );
+    dialog.set_current_name(file_name);
+
+    // The dialog must outlive this function; the handler drops it once answered.
+    let keep_alive = Rc::new(RefCell::new(Some(dialog.clone())));
+    let series = chart.series();
+    let name = name.to_string();
+    dialog.connect_response(move |dialog, response| {
+        keep_alive.borrow_mut().take();
+        if response != gtk4::ResponseType::Accept {
+            return;
+        }
+        let Some(path) = dialog.file().and_then(|file| file.path()) else {
+            return;
+        };
+        let series: Vec<(&str, &[(u64, f64)])> = series
+            .iter()
+            .map(|s| {
+                // A lone series is unlabelled; name it after the card instead.
+                let label = if s.label.is_empty() { &name } else { &s.label };
+                (label.as_str(), s.points.as_slice())
+            })
+            .collect();
+        if let Err(e) = recorder::export_points(&path, &series) {
+            eprintln!("Failed to export history to {}: {e}", path.display());
         }
-        d.close();
     });
-    
     dialog.show();
 }
 
+fn update_memory_breakdown(card: &MonitorCard, data: &MonitorData) {
+    const GIB: f64 = 1024.0 * 1024.0 * 1024.0;
+    #[allow(clippy::cast_precision_loss)]
+    let gib = |bytes: u64| bytes as f64 / GIB;
+
+    if data.ram_total > 0 {
+        // Cache and buffers live inside "available"; split it so the bar adds up to the total.
+        let used = data.ram_total.saturating_sub(data.ram_available);
+        let buffers = data.ram_buffers.min(data.ram_available);
+        let cached = data.ram_cached.min(data.ram_available - buffers);
+        let total = gib(data.ram_total);
+        card.update_breakdown(&[gib(used) / total, gib(cached) / total, gib(buffers) / total]);
+    }
+
+    let mut rows = vec![(
+        format!(
+            "Available {:.1} GB · Cached {:.1} GB · Buffers {:.1} GB",
+            gib(data.ram_available),
+            gib(data.ram_cached),
+            gib(data.ram_buffers)
+        ),
+        SensorLevel::Ok,
+    )];
+    if data.swap_total > 0 {
+        let level = if data.swap_used * 2 > data.swap_total {
+            SensorLevel::Warn
+        } else {
+            SensorLevel::Ok
+        };
+        rows.push((
+            format!("Swap {:.1}/{:.1} GB", gib(data.swap_used), gib(data.swap_total)),
+            level,
+        ));
+    }
+    card.update_rows(&rows);
+}
+
+fn format_uptime(secs: u64) -> String {
+    let (days, hours, minutes) = (secs / 86_400, secs % 86_400 / 3600, secs % 3600 / 60);
+    if days > 0 {
+        format!("{days}d {hours}h")
+    } else if hours > 0 {
+        format!("{hours}h {minutes}m")
+    } else {
+        format!("{minutes}m")
+    }
+}
+
+/// One-line summary of an adapter listing whichever readings its driver exposes.
+fn describe_gpu(gpu: &GpuStats) -> String {
+    const GIB: f64 = 1024.0 * 1024.0 * 1024.0;
+    let mut parts = vec![format!("{}: {} ({})", gpu.id, gpu.name, gpu.driver)];
+    if let Some(busy) = gpu.busy_percent {
+        parts.push(format!("{busy:.0}%"));
+    }
+    if let (Some(used), Some(total)) = (gpu.vram_used, gpu.vram_total) {
+        #[allow(clippy::cast_precision_loss)]
+        parts.push(format!("{:.1}/{:.1} GB", used as f64 / GIB, total as f64 / GIB));
+    }
+    match (gpu.core_clock_mhz, gpu.memory_clock_mhz) {
+        (Some(core), Some(mem)) => parts.push(format!("{core}/{mem} MHz")),
+        (Some(core), None) => parts.push(format!("{core} MHz")),
+        _ => {}
+    }
+    if let Some(temp) = gpu.temperature_c {
+        parts.push(format!("{temp:.0}°C"));
+    }
+    if let Some(power) = gpu.power_watts {
+        parts.push(format!("{power:.0} W"));
+    }
+    parts.join(" · ")
+}
+
+fn update_thermals(card: &MonitorCard, data: &MonitorData, style: &MonitorStyle) {
+    let headline: Vec<String> = [SensorKind::Cpu, SensorKind::Nvme, SensorKind::Gpu]
+        .into_iter()
+        .filter_map(|kind| {
+            data.temperature_of(kind)
+                .map(|t| format!("{} {:.0}°C", kind.label(), t.celsius))
+        })
+        .collect();
+    let hottest = data
+        .temperatures
+        .iter()
+        .map(|t| f64::from(t.celsius))
+        .fold(0.0_f64, f64::max);
+    let display = if headline.is_empty() {
+        "N/A".to_string()
+    } else {
+        headline.join(" · ")
+    };
+    card.update(hottest, &display, style);
+
+    let mut rows: Vec<(String, SensorLevel)> = data
+        .temperatures
+        .iter()
+        .map(|t| {
+            let warn = t.high.unwrap_or(80.0);
+            let crit = t.critical.unwrap_or(95.0);
+            let level = if t.celsius >= crit {
+                SensorLevel::Crit
+            } else if t.celsius >= warn {
+                SensorLevel::Warn
+            } else {
+                SensorLevel::Ok
+            };
+            (format!("{} {}: {:.0}°C", t.chip, t.label, t.celsius), level)
+        })
+        .collect();
+    rows.extend(data.fans.iter().map(|f| {
+        (format!("{} {}: {} RPM", f.chip, f.label, f.rpm), SensorLevel::Ok)
+    }));
+    card.update_rows(&rows);
+}
+
+fn update_power(handles: &UiHandles, data: &MonitorData, settings: &Settings) {
+    let power = &data.power;
+    let indicator = &handles.power_indicator;
+    match (power.on_ac, power.batteries.first()) {
+        (Some(on_ac), Some(battery)) => {
+            let source = if on_ac { "AC" } else { "BAT" };
+            indicator.set_text(&format!("{source} {:.0}%", battery.capacity_percent));
+            indicator.set_tooltip_text(Some(battery.state.label()));
+            indicator.set_visible(true);
+        }
+        (Some(true), None) => {
+            indicator.set_text("AC");
+            indicator.set_tooltip_text(Some("On mains power"));
+            indicator.set_visible(true);
+        }
+        _ => indicator.set_visible(false),
+    }
+
+    let card = &handles.monitor_cards.battery;
+    // Desktops have nothing to show, so the card only appears once a battery is found.
+    card.set_visible(settings.show_battery && !power.batteries.is_empty());
+    let Some(battery) = power.batteries.first() else {
+        return;
+    };
+
+    let estimate = match (battery.time_to_empty_secs, battery.time_to_full_secs) {
+        (Some(secs), _) => format!("\n{} left", format_duration(secs)),
+        (None, Some(secs)) => format!("\n{} to full", format_duration(secs)),
+        (None, None) => String::new(),
+    };
+    let display = format!(
+        "{:.0}% · {}{estimate}",
+        battery.capacity_percent,
+        battery.state.label()
+    );
+    card.update(f64::from(battery.capacity_percent), &display, &settings.monitor_style);
+
+    let rows: Vec<(String, SensorLevel)> = power
+        .batteries
+        .iter()
+        .map(|b| {
+            let level = match (b.state, b.capacity_percent) {
+                (ChargeState::Discharging, pct) if pct <= 5.0 => SensorLevel::Crit,
+                (ChargeState::Discharging, pct) if pct <= 15.0 => SensorLevel::Warn,
+                _ => SensorLevel::Ok,
+            };
+            let draw = b
+                .power_watts
+                .map(|w| format!(" · {w:.1} W"))
+                .unwrap_or_default();
+            (format!("{}: {:.0}% {}{draw}", b.name, b.capacity_percent, b.state.label()), level)
+        })
+        .collect();
+    card.update_rows(&rows);
+}
+
+/// Hours and minutes, e.g. `3h 05m`, for battery estimates.
+fn format_duration(secs: u64) -> String {
+    let (hours, minutes) = (secs / 3600, secs % 3600 / 60);
+    if hours > 0 {
+        format!("{hours}h {minutes:02}m")
+    } else {
+        format!("{minutes}m")
+    }
+}
+
 fn apply_settings(handles: &UiHandles, settings: &Settings) {
     // Main window theme (and others if we want)
     apply_theme_fixed(&handles.main_window, &settings.theme);
@@ -696,8 +1901,28 @@ fn apply_settings(handles: &UiHandles, settings: &Settings) {
     handles.terminal_window.set_visible(settings.show_terminal);
     handles.monitor_window.set_visible(settings.show_monitoring);
     handles.shortcuts_window.set_visible(settings.show_shortcuts_panel);
+    handles.terminal_tabs.set_shell(&settings.shell);
+    handles
+        .terminal_tabs
+        .set_profiles(&settings.terminal_profiles, &settings.default_terminal_profile);
+    handles.terminal_tabs.set_persist(settings.restore_terminal_session);
     
+    if handles.monitor_cards.sync_commands(settings) {
+        let _ = handles
+            .monitor_control
+            .try_send(MonitorControl::SetCommandSensors(settings.command_sensors.clone()));
+    }
     handles.monitor_cards.set_visibility(settings);
+    handles.monitor_cards.set_chart_options(settings);
+    handles.monitor_cards.set_levels(&settings.card_levels);
+    handles.process_expander.set_visible(settings.show_processes);
+    let _ = handles.monitor_control.try_send(MonitorControl::SetProcessesEnabled(
+        settings.show_processes && handles.process_expander.is_expanded(),
+    ));
+    handles.cgroup_expander.set_visible(settings.show_cgroups);
+    let _ = handles.monitor_control.try_send(MonitorControl::SetCgroupsEnabled(
+        settings.show_cgroups && handles.cgroup_expander.is_expanded(),
+    ));
     
     // Lock size logic might need to change for multiple windows, 
     // or we just apply it to all.
@@ -707,9 +1932,66 @@ fn apply_settings(handles: &UiHandles, settings: &Settings) {
     handles.shortcuts_window.set_resizable(!settings.lock_size);
 
     handles.monitor_cards.set_style(&settings.monitor_style);
+    let _ = handles
+        .monitor_control
+        .try_send(MonitorControl::SetSampling(SamplingConfig::from_settings(settings)));
+    let _ = handles
+        .monitor_control
+        .try_send(MonitorControl::SetMetricsPort(settings.metrics_port));
+    let _ = handles
+        .monitor_control
+        .try_send(MonitorControl::SetCgroupRoot(settings.cgroup_root.clone().into()));
+    sync_remote_panels(handles, settings);
     apply_dynamic_styles(&handles.style_provider, settings);
 }
 
+/// Opens a panel for every pinned remote host and closes the rest. A host whose settings
+/// changed gets a fresh session.
+fn sync_remote_panels(handles: &UiHandles, settings: &Settings) {
+    let mut panels = handles.remote_panels.borrow_mut();
+    panels.retain(|(host, panel)| {
+        let keep = settings
+            .remote_hosts
+            .iter()
+            .any(|wanted| wanted.pinned && wanted == host);
+        if !keep {
+            // `destroy` skips the close handler, which would unpin the host.
+            panel.window.destroy();
+        }
+        keep
+    });
+    for (_, panel) in panels.iter() {
+        panel.apply_settings(settings);
+    }
+
+    let Some(app) = handles.main_window.application() else {
+        return;
+    };
+    for host in &settings.remote_hosts {
+        let open = panels.iter().any(|(open, _)| open == host);
+        if open || !host.pinned || host.host.trim().is_empty() {
+            continue;
+        }
+        let on_close = {
+            let (panels, settings, name) = (
+                handles.remote_panels.clone(),
+                handles.settings.clone(),
+                host.name.clone(),
+            );
+            move || {
+                panels.borrow_mut().retain(|(open, _)| open.name != name);
+                let mut settings = settings.borrow_mut();
+                for host in settings.remote_hosts.iter_mut().filter(|h| h.name == name) {
+                    host.pinned = false;
+                }
+                settings.save();
+            }
+        };
+        let panel = RemotePanel::open(&app, host, &handles.settings, on_close);
+        panels.push((host.clone(), panel));
+    }
+}
+
 fn apply_dynamic_styles(provider: &CssProvider, settings: &Settings) {
     let gen_css = |selector: &str, style: &crate::settings::SectionStyle| {
         format!(
@@ -765,6 +2047,11 @@ fn build_header(window: &ApplicationWindow, settings: Rc<RefCell<Settings>>) ->
     
     let time_label = Label::new(None);
     time_label.add_css_class("header-time");
+
+    // Filled in by the monitoring loop; hidden until a power supply has been read.
+    let power_indicator = Label::new(None);
+    power_indicator.add_css_class("header-power");
+    power_indicator.set_visible(false);
     
     // Update time every second
     glib::timeout_add_seconds_local(1, {
@@ -798,6 +2085,7 @@ fn build_header(window: &ApplicationWindow, settings: Rc<RefCell<Settings>>) ->
 
     header.append(&title);
     header.append(&time_label);
+    header.append(&power_indicator);
     header.append(&spacer);
     header.append(&shortcuts_btn);
     header.append(&settings_btn);
@@ -857,6 +2145,7 @@ fn build_header(window: &ApplicationWindow, settings: Rc<RefCell<Settings>>) ->
 
     HeaderBar {
         widget: header,
+        power_indicator,
         settings_btn,
         shortcuts_btn,
     }